@@ -1,5 +1,10 @@
 mod orientations;
 mod real_vector_attribute;
+mod scalar_attribute;
 
 pub use orientations::{parse_orientation_attribute, ParseOrientationError};
-pub use real_vector_attribute::{parse_real_vector_attribute, ParseRealAttributeError};
+pub use real_vector_attribute::{
+    parse_real_attribute, parse_real_list_attribute, parse_real_vector_attribute,
+    ParseRealAttributeError,
+};
+pub use scalar_attribute::{parse_bool_attribute, parse_int_attribute, ParseScalarAttributeError};
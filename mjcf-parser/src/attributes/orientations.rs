@@ -1,4 +1,5 @@
 use super::real_vector_attribute::{parse_real_vector_attribute, ParseRealAttributeError};
+use crate::model::Angle;
 use failure::Fail;
 use nalgebra as na;
 use slog;
@@ -23,11 +24,13 @@ impl From<ParseRealAttributeError> for ParseOrientationError {
 ///
 /// Geoms and other objects in the MJCF XML have multiple ways of
 /// specifying orientation. This method supports all of them, while
-/// checking that only one of them is used on the tag.
+/// checking that only one of them is used on the tag. Angles in
+/// `axisangle` and `euler` are in the given unit.
 pub fn parse_orientation_attribute<N: na::RealField>(
     logger: &slog::Logger,
     node: &roxmltree::Node,
     allow_fromto: bool,
+    angle: Angle,
 ) -> Result<na::UnitQuaternion<N>, ParseOrientationError>
 where
     N: std::str::FromStr,
@@ -55,7 +58,7 @@ where
         let axis = na::Unit::new_normalize(axis);
         output = Some(na::UnitQuaternion::<N>::from_axis_angle(
             &axis,
-            *axisangle_values.get(3).unwrap(),
+            angle.to_radians(*axisangle_values.get(3).unwrap()),
         ));
     }
 
@@ -64,9 +67,9 @@ where
         return Err(ParseOrientationError::MultipleOrientationsSpecified);
     } else if euler.is_some() {
         let euler_values: na::Vector3<N> = parse_real_vector_attribute(euler.unwrap())?;
-        let roll = *euler_values.get(0).unwrap();
-        let pitch = *euler_values.get(1).unwrap();
-        let yaw = *euler_values.get(2).unwrap();
+        let roll = angle.to_radians(*euler_values.get(0).unwrap());
+        let pitch = angle.to_radians(*euler_values.get(1).unwrap());
+        let yaw = angle.to_radians(*euler_values.get(2).unwrap());
 
         // TODO(dschwab): handle the eulerseq compiler option
         output = Some(na::UnitQuaternion::<N>::from_euler_angles(roll, pitch, yaw));
@@ -202,7 +205,9 @@ mod tests {
         let doc = roxmltree::Document::parse(&xml).unwrap();
         let node = doc.root_element();
 
-        let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true).unwrap();
+        let quat =
+            parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true, Angle::Radian)
+                .unwrap();
         assert_eq!(quat, expected_quat);
     }
 
@@ -221,7 +226,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true)?;
+            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true, Angle::Radian)?;
             prop_assert_eq!(quat, expected_quat);
         }
 
@@ -234,7 +239,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true)?;
+            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true, Angle::Radian)?;
             prop_assert_eq!(quat, expected_quat);
         }
 
@@ -246,7 +251,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true)?;
+            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true, Angle::Radian)?;
             prop_assert_eq!(quat, expected_quat);
         }
 
@@ -272,7 +277,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true)?;
+            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true, Angle::Radian)?;
             prop_assert_eq!(quat, expected_quat);
         }
 
@@ -286,7 +291,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true)?;
+            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true, Angle::Radian)?;
             prop_assert_eq!(quat, expected_quat);
         }
 
@@ -302,7 +307,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true)?;
+            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, true, Angle::Radian)?;
             prop_assert_eq!(quat, expected_quat);
         }
 
@@ -320,7 +325,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, false)?;
+            let quat = parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, false, Angle::Radian)?;
             prop_assert_eq!(quat, na::UnitQuaternion::<f32>::identity());
         }
 
@@ -333,7 +338,7 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml)?;
             let node = doc.root_element();
 
-            if let Err(error) = parse_orientation_attribute::<f32>(&**log::LOG.read().unwrap(), &node, true) {
+            if let Err(error) = parse_orientation_attribute::<f32>(&**log::LOG.read().unwrap(), &node, true, Angle::Radian) {
                 match error {
                     ParseOrientationError::MultipleOrientationsSpecified => {},
                     _ => return Err(TestCaseError::fail(format!("Unexpected parsing error: {}", error))),
@@ -343,4 +348,19 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn parse_euler_degrees() {
+        let expected_quat =
+            na::UnitQuaternion::from_euler_angles(std::f32::consts::FRAC_PI_2, 0.0, 0.0);
+
+        let xml = "<geom euler=\"90 0 0\"/>";
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let node = doc.root_element();
+
+        let quat =
+            parse_orientation_attribute(&**log::LOG.read().unwrap(), &node, false, Angle::Degree)
+                .unwrap();
+        assert!(quat.angle_to(&expected_quat) < 1e-6);
+    }
 }
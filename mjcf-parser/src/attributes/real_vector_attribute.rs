@@ -49,6 +49,35 @@ where
     Ok(output)
 }
 
+/// Parses a single real value
+pub fn parse_real_attribute<N: na::RealField>(
+    text_attribute: &str,
+) -> Result<N, ParseRealAttributeError>
+where
+    N: FromStr,
+{
+    let value: na::Vector1<N> = parse_real_vector_attribute(text_attribute)?;
+    Ok(value[0])
+}
+
+/// Parses a whitespace separated list of real values with no fixed
+/// length.
+pub fn parse_real_list_attribute<N: na::RealField>(
+    text_attribute: &str,
+) -> Result<Vec<N>, ParseRealAttributeError>
+where
+    N: FromStr,
+{
+    text_attribute
+        .split_whitespace()
+        .map(|text_value| {
+            text_value
+                .parse::<N>()
+                .map_err(|_| ParseRealAttributeError::ParseError(text_value.to_string()))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,6 +132,15 @@ mod tests {
             }
         }
 
+        #[test]
+        fn parse_real_list(real_values in proptest::collection::vec(proptest::num::f32::NORMAL, 0..10)) {
+            let text_attribute = real_values.iter().map(f32::to_string).collect::<Vec<String>>().join(" ");
+
+            let parsed_values = parse_real_list_attribute::<f32>(&text_attribute)?;
+
+            prop_assert_eq!(parsed_values, real_values);
+        }
+
         #[test]
         fn parse_invalid_float(attributes in proptest::collection::vec("[A-Za-z0-9]+", 3)) {
             prop_assume!(attributes[0].parse::<f32>().is_err() ||
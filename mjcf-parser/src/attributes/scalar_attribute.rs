@@ -0,0 +1,46 @@
+use failure::Fail;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum ParseScalarAttributeError {
+    #[fail(display = "Failed to parse string \"{}\" to integer value", 0)]
+    BadInt(String),
+    #[fail(display = "Expected \"true\" or \"false\". Got \"{}\"", 0)]
+    BadBool(String),
+}
+
+pub fn parse_int_attribute(text_attribute: &str) -> Result<i32, ParseScalarAttributeError> {
+    text_attribute
+        .trim()
+        .parse::<i32>()
+        .map_err(|_| ParseScalarAttributeError::BadInt(text_attribute.to_string()))
+}
+
+pub fn parse_bool_attribute(text_attribute: &str) -> Result<bool, ParseScalarAttributeError> {
+    match text_attribute.trim() {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(ParseScalarAttributeError::BadBool(
+            text_attribute.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn parse_ints(value in proptest::num::i32::ANY) {
+            prop_assert_eq!(parse_int_attribute(&value.to_string()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn parse_bools() {
+        assert_eq!(parse_bool_attribute("true"), Ok(true));
+        assert_eq!(parse_bool_attribute("false"), Ok(false));
+        assert!(parse_bool_attribute("1").is_err());
+    }
+}
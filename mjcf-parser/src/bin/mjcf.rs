@@ -0,0 +1,349 @@
+//! Command line tool to validate, inspect, convert and simulate
+//! models without a display, e.g. in CI pipelines.
+//!
+//! Exits with status 0 on success, and 1 when the model has errors or
+//! the command fails.
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use failure::{bail, Error};
+use mjcf_parser::{
+    MJCFModelDesc, Model, ModelFormat, ParseOptions, ParseReport, Simulation, Strictness,
+    TrajectoryFormat, TrajectoryWriter,
+};
+use nalgebra as na;
+use ncollide3d::bounding_volume::{BoundingVolume, AABB};
+use nphysics3d::world::World;
+use serde_json;
+use slog::{o, Drain};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::process;
+use std::sync::Mutex;
+
+type Real = f64;
+
+/// Steps simulated when neither --duration nor --steps is given
+const DEFAULT_STEPS: usize = 1000;
+
+fn main() {
+    let matches = app().get_matches();
+    set_up_logging(matches.occurrences_of("verbose"));
+
+    let result = match matches.subcommand() {
+        ("validate", Some(args)) => validate(args),
+        ("info", Some(args)) => info(args),
+        ("convert", Some(args)) => convert(args),
+        ("simulate", Some(args)) => simulate(args),
+        _ => unreachable!("clap requires a subcommand"),
+    };
+    match result {
+        Ok(status) => process::exit(status),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn app() -> App<'static, 'static> {
+    let model_arg = Arg::with_name("MODEL")
+        .help("MJCF XML, URDF or SDFormat file")
+        .required(true);
+    let strict_arg = Arg::with_name("strict")
+        .long("strict")
+        .help("Treat schema violations as errors");
+
+    App::new("mjcf")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Validate, inspect, convert and simulate MJCF, URDF and SDFormat models")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::VersionlessSubcommands)
+        .arg(
+            Arg::with_name("verbose")
+                .short("v")
+                .long("verbose")
+                .multiple(true)
+                .global(true)
+                .help("Log parser progress, -vv for more detail"),
+        )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about("Check a model, printing its warnings and errors")
+                .arg(model_arg.clone())
+                .arg(strict_arg.clone())
+                .arg(
+                    Arg::with_name("deny-warnings")
+                        .long("deny-warnings")
+                        .help("Fail on warnings as well as errors"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("info")
+                .about("Print the number of bodies, geoms and joints, total mass and bounding box")
+                .arg(model_arg.clone())
+                .arg(strict_arg.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("convert")
+                .about("Convert a model to MJCF XML, URDF or JSON")
+                .arg(model_arg.clone())
+                .arg(
+                    Arg::with_name("OUTPUT")
+                        .help("File to write, its extension tells the format")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .long("to")
+                        .takes_value(true)
+                        .possible_values(&["mjcf", "urdf", "json"])
+                        .help("Format to write, instead of telling it from the extension"),
+                )
+                .arg(strict_arg.clone()),
+        )
+        .subcommand(
+            SubCommand::with_name("simulate")
+                .about(
+                    "Step a model without a display, writing body poses, velocities, \
+                     contacts and sensor data",
+                )
+                .arg(model_arg)
+                .arg(
+                    Arg::with_name("duration")
+                        .long("duration")
+                        .short("d")
+                        .takes_value(true)
+                        .conflicts_with("steps")
+                        .help("Seconds to simulate"),
+                )
+                .arg(
+                    Arg::with_name("steps")
+                        .long("steps")
+                        .short("n")
+                        .takes_value(true)
+                        .help("Number of timesteps to simulate [default: 1000]"),
+                )
+                .arg(
+                    Arg::with_name("format")
+                        .long("format")
+                        .short("f")
+                        .takes_value(true)
+                        .possible_values(&["csv", "jsonl", "bin"])
+                        .help("Trajectory format, instead of telling it from the output extension"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .short("o")
+                        .takes_value(true)
+                        .help("File to write the trajectory to, instead of stdout"),
+                )
+                .arg(strict_arg),
+        )
+}
+
+/// Log to stderr. Warnings and errors are printed from the parse
+/// report, so nothing is logged unless asked for.
+fn set_up_logging(verbosity: u64) {
+    let level = match verbosity {
+        0 => return,
+        1 => slog::Level::Info,
+        _ => slog::Level::Debug,
+    };
+    let decorator = slog_term::TermDecorator::new().stderr().build();
+    let drain = slog_term::FullFormat::new(decorator).build();
+    let drain = Mutex::new(drain).fuse().filter_level(level).fuse();
+    mjcf_parser::set_root_logger(slog::Logger::root(drain, o!()));
+}
+
+fn parse_options(args: &ArgMatches) -> ParseOptions {
+    if args.is_present("strict") {
+        ParseOptions::new().strictness(Strictness::Strict)
+    } else {
+        ParseOptions::new()
+    }
+}
+
+/// Print the warnings and errors of a report, if there are any
+fn print_report(report: &ParseReport) {
+    if !report.is_clean() {
+        eprintln!("{}", report);
+    }
+}
+
+/// Parse and compile a model, failing when it has errors
+fn load_model_desc(args: &ArgMatches) -> Result<MJCFModelDesc<Real>, Error> {
+    let path = args.value_of("MODEL").unwrap();
+    let (model_desc, report) = MJCFModelDesc::load_file_with_report(path, &parse_options(args))?;
+    print_report(&report);
+    if report.has_errors() {
+        bail!("{} has errors", path);
+    }
+    Ok(model_desc)
+}
+
+fn validate(args: &ArgMatches) -> Result<i32, Error> {
+    let path = args.value_of("MODEL").unwrap();
+    let (_, report) = MJCFModelDesc::<Real>::load_file_with_report(path, &parse_options(args))?;
+    eprintln!("{}", report);
+
+    let failed =
+        report.has_errors() || (args.is_present("deny-warnings") && !report.warnings().is_empty());
+    Ok(if failed { 1 } else { 0 })
+}
+
+fn info(args: &ArgMatches) -> Result<i32, Error> {
+    let mut model_desc = load_model_desc(args)?;
+    let model = model_desc.model();
+
+    let bodies = model.bodies();
+    let geom_count =
+        model.worldbody.geoms.len() + bodies.iter().map(|body| body.geoms.len()).sum::<usize>();
+    let mut joint_counts = BTreeMap::new();
+    for joint in bodies.iter().flat_map(|body| &body.joints) {
+        *joint_counts
+            .entry(joint.joint_type.to_string())
+            .or_insert(0) += 1;
+    }
+    let joint_count: usize = joint_counts.values().sum();
+
+    println!("model: {}", model.name.as_ref().map_or("-", String::as_str));
+    if let Some(format) = ModelFormat::from_path(args.value_of("MODEL").unwrap()) {
+        println!("format: {}", format);
+    }
+    println!("bodies: {}", bodies.len());
+    println!("geoms: {}", geom_count);
+    let joint_types: Vec<String> = joint_counts
+        .iter()
+        .map(|(joint_type, count)| format!("{} {}", count, joint_type))
+        .collect();
+    if joint_types.is_empty() {
+        println!("joints: 0");
+    } else {
+        println!("joints: {} ({})", joint_count, joint_types.join(", "));
+    }
+
+    let mut world = World::new();
+    model_desc.build(&mut world);
+
+    let mut mass = 0.0;
+    for body in world.bodies() {
+        for index in 0..body.num_parts() {
+            if let Some(part) = body.part(index) {
+                mass += part.inertia().linear;
+            }
+        }
+    }
+    println!("total mass: {}", mass);
+
+    // planes are unbounded, so they are left out
+    let mut bounding_box: Option<AABB<Real>> = None;
+    for collider in world.colliders() {
+        let aabb = collider.shape().aabb(collider.position());
+        let is_bounded = aabb.mins().coords.iter().all(|value| value.is_finite())
+            && aabb.maxs().coords.iter().all(|value| value.is_finite());
+        if !is_bounded {
+            continue;
+        }
+        bounding_box = Some(match bounding_box {
+            Some(bounding_box) => bounding_box.merged(&aabb),
+            None => aabb,
+        });
+    }
+    match bounding_box {
+        Some(bounding_box) => println!(
+            "bounding box: [{}] to [{}]",
+            coordinates(&bounding_box.mins().coords),
+            coordinates(&bounding_box.maxs().coords)
+        ),
+        None => println!("bounding box: -"),
+    }
+    Ok(0)
+}
+
+fn coordinates(vector: &na::Vector3<Real>) -> String {
+    vector
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn convert(args: &ArgMatches) -> Result<i32, Error> {
+    let path = args.value_of("MODEL").unwrap();
+    let output = args.value_of("OUTPUT").unwrap();
+    let format = match args.value_of("to") {
+        Some(format) => format.to_string(),
+        None => match Path::new(output)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("json") => String::from("json"),
+            Some(extension) => match ModelFormat::from_path(output) {
+                Some(ModelFormat::Mjcf) => String::from("mjcf"),
+                Some(ModelFormat::Urdf) => String::from("urdf"),
+                _ => bail!(
+                    "Can not write .{} files. Use --to to choose a format",
+                    extension
+                ),
+            },
+            None => bail!("{} has no extension. Use --to to choose a format", output),
+        },
+    };
+
+    let (model, report) = Model::<Real>::load_file_with_report(path, &parse_options(args))?;
+    print_report(&report);
+    if report.has_errors() {
+        bail!("{} has errors", path);
+    }
+
+    match format.as_str() {
+        "mjcf" => model.write_file(output)?,
+        "urdf" => print_report(&model.write_urdf_file(output)?),
+        "json" => serde_json::to_writer_pretty(BufWriter::new(File::create(output)?), &model)?,
+        _ => unreachable!("clap checks the format"),
+    }
+    Ok(0)
+}
+
+fn simulate(args: &ArgMatches) -> Result<i32, Error> {
+    let mut model_desc = load_model_desc(args)?;
+    let mut simulation = Simulation::new(&mut model_desc);
+    for sensor in simulation.unsupported_sensors() {
+        eprintln!("warning: sensor {} is not recorded", sensor);
+    }
+
+    let steps = match (args.value_of("duration"), args.value_of("steps")) {
+        (Some(duration), _) => match duration.parse::<Real>() {
+            Ok(duration) => simulation.steps_for(duration),
+            Err(_) => bail!("--duration must be a number of seconds"),
+        },
+        (None, Some(steps)) => match steps.parse() {
+            Ok(steps) => steps,
+            Err(_) => bail!("--steps must be a whole number"),
+        },
+        (None, None) => DEFAULT_STEPS,
+    };
+
+    let output_path = args.value_of("output");
+    let format = match args.value_of("format") {
+        Some(format) => format.parse().expect("clap checks the format"),
+        None => output_path
+            .and_then(TrajectoryFormat::from_path)
+            .unwrap_or(TrajectoryFormat::Csv),
+    };
+    let output: Box<dyn Write> = match output_path {
+        Some(output) => Box::new(BufWriter::new(File::create(output)?)),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+
+    let mut writer = TrajectoryWriter::new(output, format, &simulation)?;
+    for _ in 0..steps {
+        simulation.step();
+        writer.write_frame(&simulation.frame())?;
+    }
+    writer.finish()?;
+    Ok(0)
+}
@@ -0,0 +1,467 @@
+//! Fluent construction of models in Rust.
+//!
+//! The builders produce the same `Model` the parser produces from
+//! the equivalent XML, so procedural scenes can be compiled, built
+//! and written out like parsed ones.
+//!
+//! ```
+//! use mjcf_parser::{BodyBuilder, GeomBuilder, JointBuilder, Model, ModelBuilder};
+//! use nalgebra as na;
+//!
+//! let rod = GeomBuilder::capsule_fromto(
+//!     0.05,
+//!     na::Point3::origin(),
+//!     na::Point3::new(0.0, 0.0, -0.5),
+//! );
+//! let model: Model<f32> = ModelBuilder::new("pendulum")
+//!     .geom(GeomBuilder::plane().name("floor"))
+//!     .body(
+//!         BodyBuilder::new("link")
+//!             .pos(na::Vector3::new(0.0, 0.0, 1.0))
+//!             .joint(JointBuilder::hinge(na::Vector3::y()).range(-90.0, 90.0))
+//!             .geom(rod),
+//!     )
+//!     .build();
+//!
+//! assert_eq!(model.worldbody.bodies[0].joints.len(), 1);
+//! ```
+
+use crate::model::{
+    Angle, Body, Geom, GeomType, Inertial, Joint, JointType, Material, Mesh, Model, Site,
+};
+use crate::tags::custom::{CustomNumeric, CustomText};
+use nalgebra as na;
+
+/// Builder of a whole model
+pub struct ModelBuilder<N: na::RealField> {
+    model: Model<N>,
+}
+
+impl<N: na::RealField> ModelBuilder<N> {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        let mut model = Model::default();
+        model.name = Some(name.into());
+        ModelBuilder { model }
+    }
+
+    /// Unit of joint ranges. Defaults to degrees, like MuJoCo.
+    pub fn angle(mut self, angle: Angle) -> Self {
+        self.model.compiler.angle = angle;
+        self
+    }
+
+    pub fn timestep(mut self, timestep: N) -> Self {
+        self.model.option.timestep = Some(timestep);
+        self
+    }
+
+    pub fn gravity(mut self, gravity: na::Vector3<N>) -> Self {
+        self.model.option.gravity = Some(gravity);
+        self
+    }
+
+    pub fn material(mut self, material: Material) -> Self {
+        self.model.asset.materials.push(material);
+        self
+    }
+
+    pub fn mesh(mut self, mesh: Mesh<N>) -> Self {
+        self.model.asset.meshes.push(mesh);
+        self
+    }
+
+    /// Add a static geom to the world body
+    pub fn geom<G: Into<Geom<N>>>(mut self, geom: G) -> Self {
+        self.model.worldbody.geoms.push(geom.into());
+        self
+    }
+
+    /// Add a site to the world body
+    pub fn site(mut self, site: Site<N>) -> Self {
+        self.model.worldbody.sites.push(site);
+        self
+    }
+
+    /// Add a tree of bodies to the world body
+    pub fn body<B: Into<Body<N>>>(mut self, body: B) -> Self {
+        self.model.worldbody.bodies.push(body.into());
+        self
+    }
+
+    /// Add a `<numeric>` custom entry
+    pub fn numeric<S: Into<String>>(mut self, name: S, data: Vec<N>) -> Self {
+        self.model.custom.numerics.push(CustomNumeric {
+            name: name.into(),
+            data,
+        });
+        self
+    }
+
+    /// Add a `<text>` custom entry
+    pub fn text<S: Into<String>, T: Into<String>>(mut self, name: S, data: T) -> Self {
+        self.model.custom.texts.push(CustomText {
+            name: name.into(),
+            data: data.into(),
+        });
+        self
+    }
+
+    pub fn build(self) -> Model<N> {
+        self.model
+    }
+}
+
+/// Builder of a body and everything nested in it
+pub struct BodyBuilder<N: na::RealField> {
+    body: Body<N>,
+}
+
+impl<N: na::RealField> BodyBuilder<N> {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        BodyBuilder {
+            body: Body::new(name),
+        }
+    }
+
+    /// Position in the parent body's frame
+    pub fn pos(mut self, pos: na::Vector3<N>) -> Self {
+        self.body.pos = pos;
+        self
+    }
+
+    /// Orientation in the parent body's frame
+    pub fn quat(mut self, quat: na::UnitQuaternion<N>) -> Self {
+        self.body.quat = quat;
+        self
+    }
+
+    pub fn childclass<S: Into<String>>(mut self, childclass: S) -> Self {
+        self.body.childclass = Some(childclass.into());
+        self
+    }
+
+    pub fn mocap(mut self, mocap: bool) -> Self {
+        self.body.mocap = Some(mocap);
+        self
+    }
+
+    /// Mass and diagonal inertia about the center of mass at `pos`.
+    /// Overrides the mass computed from the body's geoms.
+    pub fn inertial(mut self, mass: N, pos: na::Vector3<N>, diaginertia: na::Vector3<N>) -> Self {
+        self.body.inertial = Some(Inertial {
+            mass,
+            pos,
+            quat: na::UnitQuaternion::identity(),
+            diaginertia: Some(diaginertia),
+            fullinertia: None,
+        });
+        self
+    }
+
+    /// Add a joint. Only the first joint of a body is built.
+    pub fn joint<J: Into<Joint<N>>>(mut self, joint: J) -> Self {
+        self.body.joints.push(joint.into());
+        self
+    }
+
+    pub fn geom<G: Into<Geom<N>>>(mut self, geom: G) -> Self {
+        self.body.geoms.push(geom.into());
+        self
+    }
+
+    pub fn site(mut self, site: Site<N>) -> Self {
+        self.body.sites.push(site);
+        self
+    }
+
+    /// Add a child body
+    pub fn body<B: Into<Body<N>>>(mut self, body: B) -> Self {
+        self.body.bodies.push(body.into());
+        self
+    }
+
+    pub fn build(self) -> Body<N> {
+        self.body
+    }
+}
+
+impl<N: na::RealField> From<BodyBuilder<N>> for Body<N> {
+    fn from(builder: BodyBuilder<N>) -> Body<N> {
+        builder.build()
+    }
+}
+
+/// Builder of a joint
+pub struct JointBuilder<N: na::RealField> {
+    joint: Joint<N>,
+}
+
+impl<N: na::RealField> JointBuilder<N> {
+    /// Rotation about the axis
+    pub fn hinge(axis: na::Vector3<N>) -> Self {
+        JointBuilder {
+            joint: Joint {
+                axis,
+                ..Joint::new(JointType::Hinge)
+            },
+        }
+    }
+
+    /// Translation along the axis
+    pub fn slide(axis: na::Vector3<N>) -> Self {
+        JointBuilder {
+            joint: Joint {
+                axis,
+                ..Joint::new(JointType::Slide)
+            },
+        }
+    }
+
+    pub fn ball() -> Self {
+        JointBuilder {
+            joint: Joint::new(JointType::Ball),
+        }
+    }
+
+    pub fn free() -> Self {
+        JointBuilder {
+            joint: Joint::new(JointType::Free),
+        }
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.joint.name = Some(name.into());
+        self
+    }
+
+    /// Position of the joint anchor in the body's frame
+    pub fn pos(mut self, pos: na::Vector3<N>) -> Self {
+        self.joint.pos = pos;
+        self
+    }
+
+    /// Limit the joint to the range, in the model's angle unit for
+    /// hinges
+    pub fn range(mut self, min: N, max: N) -> Self {
+        self.joint.limited = Some(true);
+        self.joint.range = Some(na::Vector2::new(min, max));
+        self
+    }
+
+    pub fn damping(mut self, damping: N) -> Self {
+        self.joint.damping = Some(damping);
+        self
+    }
+
+    pub fn stiffness(mut self, stiffness: N) -> Self {
+        self.joint.stiffness = Some(stiffness);
+        self
+    }
+
+    pub fn build(self) -> Joint<N> {
+        self.joint
+    }
+}
+
+impl<N: na::RealField> From<JointBuilder<N>> for Joint<N> {
+    fn from(builder: JointBuilder<N>) -> Joint<N> {
+        builder.build()
+    }
+}
+
+/// Builder of a geom
+pub struct GeomBuilder<N: na::RealField> {
+    geom: Geom<N>,
+}
+
+impl<N: na::RealField> GeomBuilder<N> {
+    fn with_type(geom_type: GeomType, size: Vec<N>) -> Self {
+        GeomBuilder {
+            geom: Geom::new(geom_type, size),
+        }
+    }
+
+    fn with_fromto(
+        geom_type: GeomType,
+        size: Vec<N>,
+        from: na::Point3<N>,
+        to: na::Point3<N>,
+    ) -> Self {
+        let mut builder = Self::with_type(geom_type, size);
+        builder.geom.fromto = Some(na::Vector6::new(from.x, from.y, from.z, to.x, to.y, to.z));
+        builder
+    }
+
+    /// Infinite plane through the origin, with the z axis as its
+    /// normal
+    pub fn plane() -> Self {
+        Self::with_type(GeomType::Plane, vec![])
+    }
+
+    pub fn sphere(radius: N) -> Self {
+        Self::with_type(GeomType::Sphere, vec![radius])
+    }
+
+    /// Capsule along the z axis
+    pub fn capsule(radius: N, half_length: N) -> Self {
+        Self::with_type(GeomType::Capsule, vec![radius, half_length])
+    }
+
+    /// Capsule whose axis goes from one point to the other
+    pub fn capsule_fromto(radius: N, from: na::Point3<N>, to: na::Point3<N>) -> Self {
+        Self::with_fromto(GeomType::Capsule, vec![radius], from, to)
+    }
+
+    /// Cylinder along the z axis
+    pub fn cylinder(radius: N, half_length: N) -> Self {
+        Self::with_type(GeomType::Cylinder, vec![radius, half_length])
+    }
+
+    /// Cylinder whose axis goes from one point to the other
+    pub fn cylinder_fromto(radius: N, from: na::Point3<N>, to: na::Point3<N>) -> Self {
+        Self::with_fromto(GeomType::Cylinder, vec![radius], from, to)
+    }
+
+    /// Box with the given half extents
+    pub fn cuboid(half_extents: na::Vector3<N>) -> Self {
+        Self::with_type(
+            GeomType::Box,
+            vec![half_extents.x, half_extents.y, half_extents.z],
+        )
+    }
+
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.geom.name = Some(name.into());
+        self
+    }
+
+    pub fn class<S: Into<String>>(mut self, class: S) -> Self {
+        self.geom.class = Some(class.into());
+        self
+    }
+
+    /// Position in the body's frame. Unused by geoms placed with
+    /// `fromto`.
+    pub fn pos(mut self, pos: na::Vector3<N>) -> Self {
+        self.geom.pos = pos;
+        self
+    }
+
+    /// Orientation in the body's frame. Unused by geoms placed with
+    /// `fromto`.
+    pub fn quat(mut self, quat: na::UnitQuaternion<N>) -> Self {
+        self.geom.quat = quat;
+        self
+    }
+
+    pub fn rgba(mut self, rgba: na::Vector4<f32>) -> Self {
+        self.geom.rgba = Some(rgba);
+        self
+    }
+
+    /// Sliding, torsional and rolling friction
+    pub fn friction(mut self, friction: na::Vector3<N>) -> Self {
+        self.geom.friction = Some(friction);
+        self
+    }
+
+    pub fn density(mut self, density: N) -> Self {
+        self.geom.density = Some(density);
+        self
+    }
+
+    pub fn margin(mut self, margin: N) -> Self {
+        self.geom.margin = Some(margin);
+        self
+    }
+
+    pub fn group(mut self, group: i32) -> Self {
+        self.geom.group = Some(group);
+        self
+    }
+
+    pub fn material<S: Into<String>>(mut self, material: S) -> Self {
+        self.geom.material = Some(material.into());
+        self
+    }
+
+    pub fn build(self) -> Geom<N> {
+        self.geom
+    }
+}
+
+impl<N: na::RealField> From<GeomBuilder<N>> for Geom<N> {
+    fn from(builder: GeomBuilder<N>) -> Geom<N> {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mjcf_model::MJCFModelDesc;
+    use crate::options::ParseOptions;
+    use nphysics3d::world::World;
+
+    fn pendulum(length: f32) -> ModelBuilder<f32> {
+        ModelBuilder::new("pendulum")
+            .timestep(0.01)
+            .geom(
+                GeomBuilder::plane()
+                    .name("floor")
+                    .rgba(na::Vector4::new(0.5, 0.5, 0.5, 1.0)),
+            )
+            .body(
+                BodyBuilder::new("link")
+                    .pos(na::Vector3::new(0.0, 0.0, 2.0))
+                    .joint(
+                        JointBuilder::hinge(na::Vector3::y())
+                            .name("pivot")
+                            .range(-90.0, 90.0),
+                    )
+                    .geom(GeomBuilder::capsule_fromto(
+                        0.05,
+                        na::Point3::origin(),
+                        na::Point3::new(0.0, 0.0, -length),
+                    ))
+                    .body(
+                        BodyBuilder::new("bob")
+                            .pos(na::Vector3::new(0.0, 0.0, -length))
+                            .geom(GeomBuilder::sphere(0.1).name("bob")),
+                    ),
+            )
+    }
+
+    #[test]
+    fn builder_matches_parser() {
+        let xml = "<mujoco model=\"pendulum\">
+                     <option timestep=\"0.01\"/>
+                     <worldbody>
+                       <geom name=\"floor\" type=\"plane\" rgba=\"0.5 0.5 0.5 1\"/>
+                       <body name=\"link\" pos=\"0 0 2\">
+                         <joint name=\"pivot\" axis=\"0 1 0\" limited=\"true\" range=\"-90 90\"/>
+                         <geom type=\"capsule\" size=\"0.05\" fromto=\"0 0 0 0 0 -1\"/>
+                         <body name=\"bob\" pos=\"0 0 -1\">
+                           <geom name=\"bob\" size=\"0.1\"/>
+                         </body>
+                       </body>
+                     </worldbody>
+                   </mujoco>";
+
+        let parsed = Model::<f32>::parse_xml_string(xml).unwrap();
+        assert_eq!(pendulum(1.0).build(), parsed);
+    }
+
+    #[test]
+    fn build_parameter_sweep() {
+        for length in &[0.5, 1.0, 1.5] {
+            let model = pendulum(*length).build();
+            let mut model_desc =
+                MJCFModelDesc::from_model(model, &ParseOptions::default()).unwrap();
+            let mut world = World::new();
+            let handles = model_desc.build(&mut world);
+            assert!(handles.body("bob").is_some());
+            assert!(handles.collider("floor").is_some());
+        }
+    }
+}
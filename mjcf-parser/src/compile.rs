@@ -0,0 +1,696 @@
+//! Lowering of the typed model into nphysics descriptors.
+//!
+//! Everything the nphysics world cannot represent was already
+//! reported by the parser, and meshes that failed to load by the
+//! mesh loader. Geoms of hand built models that cannot be built, e.g.
+//! because they have too few sizes, are recorded as errors and left
+//! out.
+
+use crate::error::MJCFParseError;
+use crate::mesh::Meshes;
+use crate::model::{Body, Geom, GeomType, JointType, Model};
+use crate::report::ParseReport;
+use crate::tags::geom::GeomError;
+use nalgebra as na;
+use ncollide3d::shape;
+use ncollide3d::shape::ShapeHandle;
+use ncollide3d::transformation::ToTriMesh;
+use nphysics3d::joint::{BallJoint, FixedJoint, FreeJoint, PrismaticJoint, RevoluteJoint};
+use nphysics3d::material::{BasicMaterial, MaterialHandle};
+use nphysics3d::math::Inertia;
+use nphysics3d::object::{ColliderDesc, MultibodyDesc};
+use nphysics_user_data::ColliderUserData;
+
+/// Density MuJoCo gives geoms without an explicit density
+const DEFAULT_DENSITY: f32 = 1000.0;
+
+/// A tree of bodies attached to the world, compiled into one
+/// multibody
+pub struct CompiledMultibody<N: na::RealField> {
+    pub desc: MultibodyDesc<'static, N>,
+    /// Colliders of each link, keyed by the link's depth-first index
+    /// in the multibody
+    pub link_colliders: Vec<(usize, ColliderDesc<N>)>,
+    /// MJCF name of each link, in depth-first order
+    pub link_names: Vec<Option<String>>,
+}
+
+/// nphysics descriptors of a whole model
+pub struct CompiledModel<N: na::RealField> {
+    pub world_colliders: Vec<ColliderDesc<N>>,
+    pub multibodies: Vec<CompiledMultibody<N>>,
+}
+
+/// Joint connecting a link to its parent
+enum LinkJoint<N: na::RealField> {
+    Fixed(FixedJoint<N>),
+    Revolute(RevoluteJoint<N>),
+    Prismatic(PrismaticJoint<N>),
+    Ball(BallJoint<N>),
+    Free(FreeJoint<N>),
+}
+
+impl<N: na::RealField> LinkJoint<N> {
+    fn into_root(self) -> MultibodyDesc<'static, N> {
+        match self {
+            LinkJoint::Fixed(joint) => MultibodyDesc::new(joint),
+            LinkJoint::Revolute(joint) => MultibodyDesc::new(joint),
+            LinkJoint::Prismatic(joint) => MultibodyDesc::new(joint),
+            LinkJoint::Ball(joint) => MultibodyDesc::new(joint),
+            LinkJoint::Free(joint) => MultibodyDesc::new(joint),
+        }
+    }
+
+    fn add_to<'p>(
+        self,
+        parent: &'p mut MultibodyDesc<'static, N>,
+    ) -> &'p mut MultibodyDesc<'static, N> {
+        match self {
+            LinkJoint::Fixed(joint) => parent.add_child(joint),
+            LinkJoint::Revolute(joint) => parent.add_child(joint),
+            LinkJoint::Prismatic(joint) => parent.add_child(joint),
+            LinkJoint::Ball(joint) => parent.add_child(joint),
+            LinkJoint::Free(joint) => parent.add_child(joint),
+        }
+    }
+}
+
+/// What compiling a body needs from the whole model
+struct CompileContext<'a, N: na::RealField> {
+    model: &'a Model<N>,
+    meshes: &'a Meshes<N>,
+    tesselation: u32,
+}
+
+/// Pose of the link built from a body
+struct LinkPose<N: na::RealField> {
+    /// Position of the joint in the parent link's frame
+    parent_shift: na::Vector3<N>,
+    /// Pose of the body's frame in the link's frame
+    link_from_body: na::Isometry3<N>,
+}
+
+/// Compile the model's world geoms and body trees.
+///
+/// Mesh geoms whose mesh is missing from `meshes` are left out, and
+/// so are geoms that cannot be built, which are recorded in `report`.
+pub fn compile<N: na::RealField + From<f32>>(
+    model: &Model<N>,
+    meshes: &Meshes<N>,
+    tesselation: u32,
+    report: &mut ParseReport,
+) -> CompiledModel<N> {
+    let ctx = CompileContext {
+        model,
+        meshes,
+        tesselation,
+    };
+    let mut world_colliders = vec![];
+    for geom in &model.worldbody.geoms {
+        match geom_collider_desc(geom, meshes, tesselation) {
+            Ok(Some(collider_desc)) => world_colliders.push(collider_desc),
+            Ok(None) => {}
+            Err(error) => report.push_error(MJCFParseError::from(error)),
+        }
+    }
+
+    let multibodies = model
+        .worldbody
+        .bodies
+        .iter()
+        .map(|body| {
+            let (joint, pose) = link(model, body, &na::Isometry3::identity());
+            let mut desc = joint.into_root();
+            let mut link_colliders = vec![];
+            let mut link_names = vec![];
+            compile_link(
+                body,
+                &mut desc,
+                &pose,
+                &mut link_colliders,
+                &mut link_names,
+                &ctx,
+                report,
+            );
+            CompiledMultibody {
+                desc,
+                link_colliders,
+                link_names,
+            }
+        })
+        .collect();
+
+    CompiledModel {
+        world_colliders,
+        multibodies,
+    }
+}
+
+/// Fill in the link built from a body, and add its descendants as
+/// child links.
+fn compile_link<N: na::RealField + From<f32>>(
+    body: &Body<N>,
+    desc: &mut MultibodyDesc<'static, N>,
+    pose: &LinkPose<N>,
+    link_colliders: &mut Vec<(usize, ColliderDesc<N>)>,
+    link_names: &mut Vec<Option<String>>,
+    ctx: &CompileContext<N>,
+    report: &mut ParseReport,
+) {
+    let link_index = link_names.len();
+    link_names.push(body.name.clone());
+
+    desc.set_parent_shift(pose.parent_shift);
+    if let Some(name) = &body.name {
+        desc.set_name(name.clone());
+    }
+    if let Some(inertial) = &body.inertial {
+        let rotation = pose.link_from_body.rotation.to_rotation_matrix();
+        let angular = rotation.matrix() * inertial.inertia_matrix() * rotation.matrix().transpose();
+        desc.set_local_inertia(Inertia::new(inertial.mass, angular));
+        desc.set_local_center_of_mass(pose.link_from_body * na::Point3::from(inertial.pos));
+    }
+
+    for geom in &body.geoms {
+        let mut collider_desc = match geom_collider_desc_in_frame(
+            geom,
+            &pose.link_from_body,
+            ctx.meshes,
+            ctx.tesselation,
+        ) {
+            Ok(Some(collider_desc)) => collider_desc,
+            Ok(None) => continue,
+            Err(error) => {
+                report.push_error(MJCFParseError::from(error));
+                continue;
+            }
+        };
+        // the inertial tag overrides the mass of the geoms, and
+        // visual only geoms have no mass
+        if body.inertial.is_some() || collider_desc.is_sensor() {
+            collider_desc.set_density(N::zero());
+        } else if geom.density.is_none() {
+            collider_desc.set_density(N::from(DEFAULT_DENSITY));
+        }
+        link_colliders.push((link_index, collider_desc));
+    }
+
+    for child in &body.bodies {
+        let (joint, child_pose) = link(ctx.model, child, &pose.link_from_body);
+        let child_desc = joint.add_to(desc);
+        compile_link(
+            child,
+            child_desc,
+            &child_pose,
+            link_colliders,
+            link_names,
+            ctx,
+            report,
+        );
+    }
+}
+
+/// Place the link built from a body.
+///
+/// Links are placed at their joint's anchor, with their parent's
+/// orientation, so joint axes and limits can be expressed directly.
+/// Bodies without a joint, and mocap bodies, are welded to their
+/// parent.
+fn link<N: na::RealField>(
+    model: &Model<N>,
+    body: &Body<N>,
+    parent_link_from_parent_body: &na::Isometry3<N>,
+) -> (LinkJoint<N>, LinkPose<N>) {
+    let parent_link_from_body = parent_link_from_parent_body * body.pose();
+    let joint = if body.mocap.unwrap_or(false) {
+        None
+    } else {
+        body.joints.first()
+    };
+
+    let anchor = match joint {
+        Some(joint) => parent_link_from_body * na::Point3::from(joint.pos),
+        None => na::Point3::from(parent_link_from_body.translation.vector),
+    };
+    let link_from_body = na::Translation3::from(-anchor.coords) * parent_link_from_body;
+
+    let joint = match joint {
+        None => LinkJoint::Fixed(FixedJoint::new(na::Isometry3::identity())),
+        Some(joint) => {
+            let axis = na::Unit::new_normalize(parent_link_from_body.rotation * joint.axis);
+            let range = if joint.is_limited() {
+                joint.range
+            } else {
+                None
+            };
+            match joint.joint_type {
+                JointType::Hinge => {
+                    let mut revolute = RevoluteJoint::new(axis, N::zero());
+                    if let Some(range) = range {
+                        revolute.enable_min_angle(model.compiler.angle.to_radians(range[0]));
+                        revolute.enable_max_angle(model.compiler.angle.to_radians(range[1]));
+                    }
+                    LinkJoint::Revolute(revolute)
+                }
+                JointType::Slide => {
+                    let mut prismatic = PrismaticJoint::new(axis, N::zero());
+                    if let Some(range) = range {
+                        prismatic.enable_min_offset(range[0]);
+                        prismatic.enable_max_offset(range[1]);
+                    }
+                    LinkJoint::Prismatic(prismatic)
+                }
+                JointType::Ball => LinkJoint::Ball(BallJoint::new(na::UnitQuaternion::identity())),
+                JointType::Free => LinkJoint::Free(FreeJoint::new(na::Isometry3::from_parts(
+                    na::Translation3::from(anchor.coords),
+                    na::UnitQuaternion::identity(),
+                ))),
+            }
+        }
+    };
+
+    // free joints hold the link's pose themselves, and ignore the
+    // parent shift
+    let parent_shift = match joint {
+        LinkJoint::Free(_) => na::Vector3::zeros(),
+        _ => anchor.coords,
+    };
+    (
+        joint,
+        LinkPose {
+            parent_shift,
+            link_from_body,
+        },
+    )
+}
+
+/// Collider of a geom, posed in the geom's body frame.
+///
+/// Geoms that collide with nothing, i.e. with both contype and
+/// conaffinity 0, become sensors. `Ok(None)` is returned for mesh
+/// geoms whose mesh is not in `meshes`, and an error for geoms with
+/// too few sizes, or a type nphysics has no shape for.
+pub fn geom_collider_desc<N: na::RealField + From<f32>>(
+    geom: &Geom<N>,
+    meshes: &Meshes<N>,
+    tesselation: u32,
+) -> Result<Option<ColliderDesc<N>>, GeomError> {
+    geom_collider_desc_in_frame(geom, &na::Isometry3::identity(), meshes, tesselation)
+}
+
+/// Collider of a geom, posed in a frame given by the pose of the
+/// geom's body in it
+fn geom_collider_desc_in_frame<N: na::RealField + From<f32>>(
+    geom: &Geom<N>,
+    frame_from_body: &na::Isometry3<N>,
+    meshes: &Meshes<N>,
+    tesselation: u32,
+) -> Result<Option<ColliderDesc<N>>, GeomError> {
+    let axis = geom.fromto.map(|fromto| {
+        let p0 = na::Point3::from(fromto.fixed_rows::<na::U3>(0).into_owned());
+        let p1 = na::Point3::from(fromto.fixed_rows::<na::U3>(3).into_owned());
+        (p0, p1)
+    });
+
+    let expected_sizes = match (geom.geom_type, axis) {
+        (GeomType::Sphere, _) | (GeomType::Capsule, Some(_)) | (GeomType::Cylinder, Some(_)) => 1,
+        (GeomType::Capsule, None) | (GeomType::Cylinder, None) | (GeomType::Box, Some(_)) => 2,
+        (GeomType::Box, None) => 3,
+        _ => 0,
+    };
+    if geom.size.len() < expected_sizes {
+        return Err(GeomError::MissingSizes {
+            geom_type: geom.geom_type.to_string(),
+            expected: expected_sizes,
+            found: geom.size.len(),
+        });
+    }
+    let degenerate = || GeomError::DegenerateShape {
+        geom_type: geom.geom_type.to_string(),
+    };
+
+    let (shape_handle, position): (ShapeHandle<N>, na::Isometry3<N>) = match geom.geom_type {
+        GeomType::Plane => (
+            ShapeHandle::new(shape::Plane::new(na::Vector3::z_axis())),
+            na::Isometry3::from_parts(na::Translation3::from(geom.pos), geom.quat),
+        ),
+        GeomType::Capsule | GeomType::Cylinder => {
+            let radius = geom.size[0];
+            // ncollide's capsules and cylinders are along y, MuJoCo's
+            // are along z
+            let (half_length, position) = match axis {
+                Some((p0, p1)) => {
+                    let dir = p1 - p0;
+                    let rotation = na::UnitQuaternion::rotation_between(&na::Vector3::y(), &dir)
+                        .unwrap_or_else(|| {
+                            na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), N::pi())
+                        });
+                    (
+                        dir.norm() / N::from(2.0),
+                        na::Isometry3::from_parts(
+                            na::Translation3::from(na::center(&p0, &p1).coords),
+                            rotation,
+                        ),
+                    )
+                }
+                None => (
+                    geom.size[1],
+                    na::Isometry3::from_parts(
+                        na::Translation3::from(geom.pos),
+                        geom.quat
+                            * na::UnitQuaternion::from_axis_angle(
+                                &na::Vector3::x_axis(),
+                                N::frac_pi_2(),
+                            ),
+                    ),
+                ),
+            };
+
+            // ncollide panics on these, e.g. for a fromto with the same
+            // start and end point
+            let has_volume = radius > N::zero() && half_length > N::zero();
+            if !has_volume {
+                return Err(degenerate());
+            }
+
+            let shape_handle = if geom.geom_type == GeomType::Capsule {
+                ShapeHandle::new(shape::Capsule::new(half_length, radius))
+            } else {
+                let cyl_trimesh = shape::Cylinder::new(half_length, radius).to_trimesh(tesselation);
+                let hull = shape::ConvexHull::try_from_points(&cyl_trimesh.coords)
+                    .ok_or_else(degenerate)?;
+                ShapeHandle::new(hull)
+            };
+            (shape_handle, position)
+        }
+        GeomType::Box => {
+            let (half_extents, position) = match axis {
+                Some((p0, p1)) => {
+                    let dir = p1 - p0;
+                    let rotation = na::UnitQuaternion::rotation_between(&na::Vector3::z(), &dir)
+                        .unwrap_or_else(|| {
+                            na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), N::pi())
+                        });
+                    (
+                        na::Vector3::new(geom.size[0], geom.size[1], dir.norm() / N::from(2.0)),
+                        na::Isometry3::from_parts(
+                            na::Translation3::from(na::center(&p0, &p1).coords),
+                            rotation,
+                        ),
+                    )
+                }
+                None => (
+                    na::Vector3::new(geom.size[0], geom.size[1], geom.size[2]),
+                    na::Isometry3::from_parts(na::Translation3::from(geom.pos), geom.quat),
+                ),
+            };
+            // ncollide panics on negative half extents
+            if !half_extents
+                .iter()
+                .all(|half_extent| *half_extent >= N::zero())
+            {
+                return Err(degenerate());
+            }
+            (ShapeHandle::new(shape::Cuboid::new(half_extents)), position)
+        }
+        GeomType::Mesh => {
+            let mesh = match geom.mesh.as_ref().and_then(|mesh| meshes.get(mesh)) {
+                Some(mesh) => mesh,
+                None => return Ok(None),
+            };
+            // degenerate meshes, e.g. flat ones, have no convex hull
+            let hull = match shape::ConvexHull::try_from_points(&mesh.vertices) {
+                Some(hull) => hull,
+                None => return Ok(None),
+            };
+            (
+                ShapeHandle::new(hull),
+                na::Isometry3::from_parts(na::Translation3::from(geom.pos), geom.quat),
+            )
+        }
+        GeomType::Sphere => {
+            // ncollide panics on balls without volume
+            let radius = geom.size[0];
+            let has_volume = radius > N::zero();
+            if !has_volume {
+                return Err(degenerate());
+            }
+            (
+                ShapeHandle::new(shape::Ball::new(radius)),
+                na::Isometry3::from_parts(na::Translation3::from(geom.pos), geom.quat),
+            )
+        }
+        // the parser rejects these types
+        geom_type => {
+            return Err(GeomError::UnsupportedType {
+                geom_type: geom_type.to_string(),
+            })
+        }
+    };
+
+    let mut collider_desc = ColliderDesc::new(shape_handle);
+    let mut user_data: ColliderUserData<N> = Default::default();
+
+    if let Some(name) = &geom.name {
+        collider_desc.set_name(name.clone());
+    }
+    collider_desc.set_position(frame_from_body * position);
+    if let Some(density) = geom.density {
+        collider_desc.set_density(density);
+    }
+    if let Some(margin) = geom.margin {
+        collider_desc.set_margin(margin);
+    }
+    if geom.contype == Some(0) && geom.conaffinity == Some(0) {
+        collider_desc.set_is_sensor(true);
+    }
+
+    // default sliding friction from mujoco xml reference
+    let sliding_friction = match geom.friction {
+        Some(friction) => {
+            user_data.torsional_friction = friction[1];
+            user_data.rolling_friction = friction[2];
+            friction[0]
+        }
+        None => N::from(1.0),
+    };
+    collider_desc.set_material(MaterialHandle::new(BasicMaterial::new(
+        N::from(0.0),
+        sliding_friction,
+    )));
+
+    user_data.rgba = geom.rgba.map(na::Point4::from);
+    user_data.geom_type = Some(geom.geom_type.as_str().to_string());
+    user_data.group = geom.group.unwrap_or(0);
+    collider_desc.set_user_data(Some(user_data));
+    Ok(Some(collider_desc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MJCFParseErrorKind;
+    use crate::mesh::MeshData;
+    use crate::model::{Inertial, Joint};
+
+    fn pendulum() -> Model<f32> {
+        let mut link = Body::new("link");
+        link.pos = na::Vector3::new(0.0, 0.0, 1.0);
+        let mut hinge = Joint::new(JointType::Hinge);
+        hinge.axis = na::Vector3::y();
+        hinge.limited = Some(true);
+        hinge.range = Some(na::Vector2::new(-90.0, 90.0));
+        link.joints.push(hinge);
+        let mut rod = Geom::new(GeomType::Capsule, vec![0.05]);
+        rod.fromto = Some(na::Vector6::new(0.0, 0.0, 0.0, 0.0, 0.0, -0.5));
+        link.geoms.push(rod);
+
+        let mut tip = Body::new("tip");
+        tip.pos = na::Vector3::new(0.0, 0.0, -0.5);
+        tip.inertial = Some(Inertial {
+            mass: 2.0,
+            pos: na::Vector3::zeros(),
+            quat: na::UnitQuaternion::identity(),
+            diaginertia: Some(na::Vector3::new(0.1, 0.1, 0.1)),
+            fullinertia: None,
+        });
+        tip.geoms.push(Geom::new(GeomType::Sphere, vec![0.1]));
+        link.bodies.push(tip);
+
+        let mut model = Model::default();
+        model
+            .worldbody
+            .geoms
+            .push(Geom::new(GeomType::Plane, vec![]));
+        model.worldbody.bodies.push(link);
+        model
+    }
+
+    #[test]
+    fn compile_body_tree_into_multibody() {
+        let mut report = ParseReport::default();
+        let compiled = compile(&pendulum(), &Meshes::new(), 8, &mut report);
+        assert!(report.is_clean());
+
+        assert_eq!(compiled.world_colliders.len(), 1);
+        assert_eq!(compiled.multibodies.len(), 1);
+        let multibody = &compiled.multibodies[0];
+        assert_eq!(
+            multibody.link_names,
+            vec![Some(String::from("link")), Some(String::from("tip"))]
+        );
+        assert_eq!(multibody.link_colliders.len(), 2);
+        assert_eq!(multibody.link_colliders[0].0, 0);
+        assert_eq!(multibody.link_colliders[1].0, 1);
+        // the tip's inertial overrides its geom's mass
+        assert_eq!(multibody.link_colliders[1].1.get_density(), 0.0);
+        assert_eq!(multibody.link_colliders[0].1.get_density(), 1000.0);
+    }
+
+    #[test]
+    fn capsule_fromto_is_centered_on_its_axis() {
+        let mut capsule = Geom::new(GeomType::Capsule, vec![0.1]);
+        capsule.fromto = Some(na::Vector6::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0));
+
+        let collider_desc = geom_collider_desc(&capsule, &Meshes::new(), 8)
+            .unwrap()
+            .unwrap();
+        let shape: &shape::Capsule<f32> = collider_desc.get_shape().downcast_ref().unwrap();
+        assert_eq!(shape.half_height(), 0.5);
+        assert_eq!(
+            *collider_desc.get_translation(),
+            na::Vector3::new(0.5, 0.0, 0.0)
+        );
+        let axis = collider_desc.get_rotation() * na::Vector3::y();
+        assert!((axis - na::Vector3::x()).norm() < 1.0e-6);
+    }
+
+    #[test]
+    fn capsule_size_is_along_z() {
+        let capsule = Geom::new(GeomType::Capsule, vec![0.1, 0.5]);
+
+        let collider_desc = geom_collider_desc(&capsule, &Meshes::new(), 8)
+            .unwrap()
+            .unwrap();
+        let axis = collider_desc.get_rotation() * na::Vector3::y();
+        assert!((axis - na::Vector3::z()).norm() < 1.0e-6);
+    }
+
+    #[test]
+    fn mesh_geoms_use_the_loaded_mesh() {
+        let mut meshes = Meshes::new();
+        meshes.insert(
+            String::from("tetrahedron"),
+            MeshData {
+                vertices: vec![
+                    na::Point3::new(0.0, 0.0, 0.0),
+                    na::Point3::new(1.0, 0.0, 0.0),
+                    na::Point3::new(0.0, 1.0, 0.0),
+                    na::Point3::new(0.0, 0.0, 1.0),
+                ],
+                triangles: vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]],
+            },
+        );
+
+        let mut geom = Geom::new(GeomType::Mesh, vec![]);
+        geom.mesh = Some(String::from("tetrahedron"));
+        let collider_desc = geom_collider_desc(&geom, &meshes, 8).unwrap().unwrap();
+        let hull: &shape::ConvexHull<f32> = collider_desc.get_shape().downcast_ref().unwrap();
+        assert_eq!(hull.points().len(), 4);
+
+        geom.mesh = Some(String::from("missing"));
+        assert!(geom_collider_desc(&geom, &meshes, 8).unwrap().is_none());
+    }
+
+    #[test]
+    fn visual_geoms_are_massless_sensors() {
+        let mut model = pendulum();
+        let rod = &mut model.worldbody.bodies[0].geoms[0];
+        rod.contype = Some(0);
+        rod.conaffinity = Some(0);
+
+        let compiled = compile(&model, &Meshes::new(), 8, &mut ParseReport::default());
+        let rod = &compiled.multibodies[0].link_colliders[0].1;
+        assert!(rod.is_sensor());
+        assert_eq!(rod.get_density(), 0.0);
+    }
+
+    #[test]
+    fn invalid_geoms_are_reported() {
+        let mut model = pendulum();
+        let link = &mut model.worldbody.bodies[0];
+        link.geoms.push(Geom::new(GeomType::Box, vec![0.1, 0.1]));
+        link.geoms
+            .push(Geom::new(GeomType::Ellipsoid, vec![0.1, 0.2, 0.3]));
+        link.geoms
+            .push(Geom::new(GeomType::Cylinder, vec![0.0, 0.5]));
+        model
+            .worldbody
+            .geoms
+            .push(Geom::new(GeomType::Sphere, vec![]));
+
+        let mut report = ParseReport::default();
+        let compiled = compile(&model, &Meshes::new(), 8, &mut report);
+        let errors: Vec<GeomError> = report
+            .errors()
+            .iter()
+            .map(|error| match error.kind() {
+                MJCFParseErrorKind::GeomError(error) => error,
+                kind => panic!("Unexpected error {:?}", kind),
+            })
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                GeomError::MissingSizes {
+                    geom_type: String::from("sphere"),
+                    expected: 1,
+                    found: 0,
+                },
+                GeomError::MissingSizes {
+                    geom_type: String::from("box"),
+                    expected: 3,
+                    found: 2,
+                },
+                GeomError::UnsupportedType {
+                    geom_type: String::from("ellipsoid"),
+                },
+                GeomError::DegenerateShape {
+                    geom_type: String::from("cylinder"),
+                },
+            ]
+        );
+        // the valid geoms are still built
+        assert_eq!(compiled.world_colliders.len(), 1);
+        assert_eq!(compiled.multibodies[0].link_colliders.len(), 2);
+    }
+
+    #[test]
+    fn spheres_need_a_positive_radius() {
+        for radius in &[0.0, -0.1, std::f32::NAN] {
+            let sphere = Geom::new(GeomType::Sphere, vec![*radius]);
+            match geom_collider_desc(&sphere, &Meshes::new(), 8) {
+                Err(GeomError::DegenerateShape { geom_type }) => assert_eq!(geom_type, "sphere"),
+                _ => panic!("Built a sphere of radius {}", radius),
+            }
+        }
+    }
+
+    #[test]
+    fn boxes_need_non_negative_sizes() {
+        let flat = Geom::new(GeomType::Box, vec![0.1, 0.1, 0.0]);
+        assert!(geom_collider_desc(&flat, &Meshes::new(), 8)
+            .unwrap()
+            .is_some());
+
+        let inverted = Geom::new(GeomType::Box, vec![0.1, -0.1, 0.1]);
+        match geom_collider_desc(&inverted, &Meshes::new(), 8) {
+            Err(GeomError::DegenerateShape { geom_type }) => assert_eq!(geom_type, "box"),
+            _ => panic!("Built a box with a negative size"),
+        }
+
+        let mut inverted_fromto = Geom::new(GeomType::Box, vec![-0.1, 0.1]);
+        inverted_fromto.fromto = Some(na::Vector6::new(0.0, 0.0, 0.0, 0.0, 0.0, 1.0));
+        assert!(geom_collider_desc(&inverted_fromto, &Meshes::new(), 8).is_err());
+    }
+}
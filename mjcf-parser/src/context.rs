@@ -0,0 +1,62 @@
+use crate::error::MJCFParseError;
+use crate::location::{Source, SourceLocation};
+use crate::model::Angle;
+use crate::options::ParseOptions;
+use crate::report::{ParseReport, ParseWarning, WarningCode};
+use roxmltree;
+use slog::{error, warn};
+
+/// State shared by the tag parsers while parsing a single document
+pub struct ParseContext<'a> {
+    pub logger: slog::Logger,
+    pub source: Source<'a>,
+    pub options: &'a ParseOptions,
+    /// Unit of angles in the document, from the `<compiler>` tag
+    pub angle: Angle,
+    pub report: ParseReport,
+}
+
+impl<'a> ParseContext<'a> {
+    pub fn new(source: Source<'a>, options: &'a ParseOptions) -> Self {
+        ParseContext {
+            logger: options.get_logger(),
+            source,
+            options,
+            angle: Angle::default(),
+            report: ParseReport::default(),
+        }
+    }
+
+    /// Record a warning about an element
+    pub fn warn<S: Into<String>>(&mut self, code: WarningCode, node: &roxmltree::Node, message: S) {
+        let location = self.source.location(node);
+        let message = message.into();
+        warn!(self.logger, "{}", message;
+              "code" => code.as_str(),
+              "location" => %location,
+              "element" => location.element_path.clone().unwrap_or_default());
+
+        self.report.push_warning(ParseWarning {
+            code,
+            message,
+            location: Some(location),
+        });
+    }
+
+    /// Record a recoverable error about an element
+    pub fn error<E: Into<MJCFParseError>>(&mut self, node: &roxmltree::Node, error: E) {
+        let location = self.source.location(node);
+        self.error_at(location, error);
+    }
+
+    /// Record a recoverable error found after the element it is
+    /// about was parsed
+    pub fn error_at<E: Into<MJCFParseError>>(&mut self, location: SourceLocation, error: E) {
+        let error = error.into().with_location(location);
+        error!(self.logger, "{}", error.kind();
+               "code" => error.kind().code(),
+               "location" => ?error.location().map(ToString::to_string));
+
+        self.report.push_error(error);
+    }
+}
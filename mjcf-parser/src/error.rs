@@ -1,4 +1,9 @@
+use crate::location::SourceLocation;
+use crate::names::Namespace;
+use crate::schema::SchemaViolation;
+use crate::sdf;
 use crate::tags;
+use crate::urdf;
 use failure::{Backtrace, Context, Fail};
 use std::fmt;
 use std::fmt::Display;
@@ -6,6 +11,7 @@ use std::fmt::Display;
 #[derive(Debug)]
 pub struct MJCFParseError {
     inner: Context<MJCFParseErrorKind>,
+    location: Option<SourceLocation>,
 }
 
 #[derive(Clone, PartialEq, Debug, Fail)]
@@ -13,14 +19,73 @@ pub enum MJCFParseErrorKind {
     // data contains the displayed roxmltree::Error
     #[fail(display = "{}", 0)]
     BadXML(String),
+    #[fail(display = "Failed to read {}. {}", path, reason)]
+    ReadFailed { path: String, reason: String },
+    #[fail(display = "Failed to load mesh {}. {}", path, reason)]
+    MeshLoadFailed { path: String, reason: String },
+    #[fail(
+        display = "Unknown model format of {}. Expected .xml, .urdf, .sdf or .world",
+        0
+    )]
+    UnknownFormat(String),
     #[fail(display = "{}", tag_name)]
     MissingRequiredTag { tag_name: String },
     #[fail(display = "worldbody tag has attributes")]
     WorldBodyHasAttributes,
     #[fail(display = "worldbody has invalid children")]
     WorldBodyInvalidChildren,
+    #[fail(display = "Duplicate {} name \"{}\"", namespace, name)]
+    DuplicateName { namespace: Namespace, name: String },
     #[fail(display = "{}", 0)]
     GeomError(#[fail(cause)] tags::geom::GeomError),
+    #[fail(display = "{}", 0)]
+    BodyError(#[fail(cause)] tags::body::BodyError),
+    #[fail(display = "{}", 0)]
+    AssetError(#[fail(cause)] tags::asset::AssetError),
+    #[fail(display = "{}", 0)]
+    OptionError(#[fail(cause)] tags::option::OptionError),
+    #[fail(display = "{}", 0)]
+    CustomError(#[fail(cause)] tags::custom::CustomError),
+    #[fail(display = "{}", 0)]
+    UrdfError(#[fail(cause)] urdf::UrdfError),
+    #[fail(display = "{}", 0)]
+    SdfError(#[fail(cause)] sdf::SdfError),
+    #[fail(display = "{}", 0)]
+    SchemaViolation(#[fail(cause)] SchemaViolation),
+    #[fail(
+        display = "Value \"{}\" of attribute {} of {} is not finite",
+        value, attribute, tag
+    )]
+    NonFiniteValue {
+        tag: String,
+        attribute: String,
+        value: String,
+    },
+}
+
+impl MJCFParseErrorKind {
+    /// Short, stable identifier of the error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            MJCFParseErrorKind::BadXML(_) => "bad-xml",
+            MJCFParseErrorKind::ReadFailed { .. } => "read-failed",
+            MJCFParseErrorKind::MeshLoadFailed { .. } => "mesh-load-failed",
+            MJCFParseErrorKind::UnknownFormat(_) => "unknown-format",
+            MJCFParseErrorKind::MissingRequiredTag { .. } => "missing-required-tag",
+            MJCFParseErrorKind::WorldBodyHasAttributes => "worldbody-has-attributes",
+            MJCFParseErrorKind::WorldBodyInvalidChildren => "worldbody-invalid-children",
+            MJCFParseErrorKind::DuplicateName { .. } => "duplicate-name",
+            MJCFParseErrorKind::GeomError(_) => "invalid-geom",
+            MJCFParseErrorKind::BodyError(_) => "invalid-body",
+            MJCFParseErrorKind::AssetError(_) => "invalid-asset",
+            MJCFParseErrorKind::OptionError(_) => "invalid-option",
+            MJCFParseErrorKind::CustomError(_) => "invalid-custom",
+            MJCFParseErrorKind::UrdfError(_) => "invalid-urdf",
+            MJCFParseErrorKind::SdfError(_) => "invalid-sdf",
+            MJCFParseErrorKind::SchemaViolation(violation) => violation.warning_code().as_str(),
+            MJCFParseErrorKind::NonFiniteValue { .. } => "non-finite-value",
+        }
+    }
 }
 
 impl Fail for MJCFParseError {
@@ -35,7 +100,11 @@ impl Fail for MJCFParseError {
 
 impl Display for MJCFParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Display::fmt(&self.inner, f)
+        Display::fmt(&self.inner, f)?;
+        if let Some(location) = &self.location {
+            write!(f, "\n{}", location.snippet())?;
+        }
+        Ok(())
     }
 }
 
@@ -43,19 +112,37 @@ impl MJCFParseError {
     pub fn kind(&self) -> MJCFParseErrorKind {
         self.inner.get_context().clone()
     }
+
+    /// Where in the model the error occurred, if known
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.location.as_ref()
+    }
+
+    /// Attach a location to the error, keeping any location it
+    /// already has since that will be the more specific one.
+    pub fn with_location(mut self, location: SourceLocation) -> MJCFParseError {
+        if self.location.is_none() {
+            self.location = Some(location);
+        }
+        self
+    }
 }
 
 impl From<MJCFParseErrorKind> for MJCFParseError {
     fn from(kind: MJCFParseErrorKind) -> MJCFParseError {
         MJCFParseError {
             inner: Context::new(kind),
+            location: None,
         }
     }
 }
 
 impl From<Context<MJCFParseErrorKind>> for MJCFParseError {
     fn from(inner: Context<MJCFParseErrorKind>) -> MJCFParseError {
-        MJCFParseError { inner }
+        MJCFParseError {
+            inner,
+            location: None,
+        }
     }
 }
 
@@ -65,4 +152,40 @@ impl From<tags::geom::GeomError> for MJCFParseError {
     }
 }
 
+impl From<tags::body::BodyError> for MJCFParseError {
+    fn from(body_error: tags::body::BodyError) -> MJCFParseError {
+        MJCFParseError::from(MJCFParseErrorKind::BodyError(body_error))
+    }
+}
+
+impl From<tags::asset::AssetError> for MJCFParseError {
+    fn from(asset_error: tags::asset::AssetError) -> MJCFParseError {
+        MJCFParseError::from(MJCFParseErrorKind::AssetError(asset_error))
+    }
+}
+
+impl From<tags::option::OptionError> for MJCFParseError {
+    fn from(option_error: tags::option::OptionError) -> MJCFParseError {
+        MJCFParseError::from(MJCFParseErrorKind::OptionError(option_error))
+    }
+}
+
+impl From<tags::custom::CustomError> for MJCFParseError {
+    fn from(custom_error: tags::custom::CustomError) -> MJCFParseError {
+        MJCFParseError::from(MJCFParseErrorKind::CustomError(custom_error))
+    }
+}
+
+impl From<urdf::UrdfError> for MJCFParseError {
+    fn from(urdf_error: urdf::UrdfError) -> MJCFParseError {
+        MJCFParseError::from(MJCFParseErrorKind::UrdfError(urdf_error))
+    }
+}
+
+impl From<sdf::SdfError> for MJCFParseError {
+    fn from(sdf_error: sdf::SdfError) -> MJCFParseError {
+        MJCFParseError::from(MJCFParseErrorKind::SdfError(sdf_error))
+    }
+}
+
 pub type MJCFParseResult<T> = Result<T, MJCFParseError>;
@@ -0,0 +1,63 @@
+use std::fmt;
+use std::path::Path;
+
+/// File formats a model can be read from
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ModelFormat {
+    /// MuJoCo's MJCF XML
+    Mjcf,
+    /// ROS's Unified Robot Description Format
+    Urdf,
+    /// Gazebo's SDFormat, for models and worlds
+    Sdf,
+}
+
+impl ModelFormat {
+    /// Format of a model file, from its extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<ModelFormat> {
+        let extension = path.as_ref().extension()?.to_string_lossy().to_lowercase();
+        match extension.as_str() {
+            "xml" | "mjcf" => Some(ModelFormat::Mjcf),
+            "urdf" => Some(ModelFormat::Urdf),
+            "sdf" | "world" => Some(ModelFormat::Sdf),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ModelFormat::Mjcf => "mjcf",
+            ModelFormat::Urdf => "urdf",
+            ModelFormat::Sdf => "sdf",
+        }
+    }
+}
+
+impl fmt::Display for ModelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            ModelFormat::from_path("models/humanoid.xml"),
+            Some(ModelFormat::Mjcf)
+        );
+        assert_eq!(
+            ModelFormat::from_path("robots/arm.URDF"),
+            Some(ModelFormat::Urdf)
+        );
+        assert_eq!(
+            ModelFormat::from_path("worlds/empty.world"),
+            Some(ModelFormat::Sdf)
+        );
+        assert_eq!(ModelFormat::from_path("robots/arm.dae"), None);
+        assert_eq!(ModelFormat::from_path("robots/arm"), None);
+    }
+}
@@ -1,11 +1,40 @@
 mod attributes;
+mod builder;
 pub mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
+mod compile;
+mod context;
 pub mod error;
+mod format;
+mod location;
 mod log;
+mod mesh;
 mod mjcf_model;
+pub mod model;
+mod names;
+mod options;
+mod parse;
+mod report;
+mod schema;
+mod sdf;
+mod simulation;
 mod tags;
+mod trajectory;
+mod urdf;
+mod urdf_writer;
+mod writer;
 
+pub use builder::{BodyBuilder, GeomBuilder, JointBuilder, ModelBuilder};
+pub use format::ModelFormat;
+pub use location::SourceLocation;
 pub use log::{drop_root_logger, set_root_logger};
 pub use mjcf_model::MJCFModelDesc;
+pub use model::Model;
+pub use names::{ModelHandles, Namespace};
+pub use options::{FileResolver, FilesystemResolver, ParseOptions, ScalarConversion, Strictness};
+pub use report::{ParseReport, ParseWarning, WarningCode};
+pub use schema::SchemaViolation;
+pub use simulation::{BodyState, ContactState, Frame, SensorReading, Simulation};
+pub use tags::custom::{Custom, CustomNumeric, CustomText, CustomTuple, CustomTupleElement};
+pub use trajectory::{TrajectoryFormat, TrajectoryWriter};
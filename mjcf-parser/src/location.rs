@@ -0,0 +1,196 @@
+use roxmltree;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Text of the document being parsed and the file it was read from
+#[derive(Clone, Copy, Debug)]
+pub struct Source<'a> {
+    pub file: Option<&'a Path>,
+    pub text: &'a str,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(file: Option<&'a Path>, text: &'a str) -> Self {
+        Source { file, text }
+    }
+
+    /// Location of the start of an element
+    pub fn location(&self, node: &roxmltree::Node) -> SourceLocation {
+        let pos = node.document().text_pos_at(node.range().start);
+        let mut location = self.location_at(pos);
+        location.element_path = Some(element_path(node));
+        location
+    }
+
+    /// Location of a position in the text that is not necessarily
+    /// an element, e.g. an XML syntax error
+    pub fn location_at(&self, pos: roxmltree::TextPos) -> SourceLocation {
+        SourceLocation {
+            file: self.file.map(Path::to_path_buf),
+            line: pos.row,
+            column: pos.col,
+            element_path: None,
+            source_line: self
+                .text
+                .lines()
+                .nth((pos.row as usize).saturating_sub(1))
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Where in the model file an error or warning originated.
+///
+/// Lines and columns start at 1.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SourceLocation {
+    pub file: Option<PathBuf>,
+    pub line: u32,
+    pub column: u32,
+    /// Path of the element from the `<mujoco>` tag, e.g.
+    /// `worldbody/body[name=arm]/geom[3]`
+    pub element_path: Option<String>,
+    /// Text of the line containing the location
+    pub source_line: Option<String>,
+}
+
+impl SourceLocation {
+    /// Multi-line excerpt of the source pointing at the location
+    pub fn snippet(&self) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        let mut snippet = format!("{}--> {}\n", gutter, self);
+        if let Some(source_line) = &self.source_line {
+            let marker_offset = " ".repeat((self.column as usize).saturating_sub(1));
+            snippet.push_str(&format!("{} |\n", gutter));
+            snippet.push_str(&format!("{} | {}\n", line_number, source_line));
+            snippet.push_str(&format!("{} | {}^\n", gutter, marker_offset));
+        }
+        if let Some(element_path) = &self.element_path {
+            snippet.push_str(&format!("{} = in {}\n", gutter, element_path));
+        }
+        snippet
+    }
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file.display(), self.line, self.column),
+            None => write!(f, "<string>:{}:{}", self.line, self.column),
+        }
+    }
+}
+
+/// Path of an element relative to the document's root element.
+///
+/// Each element is identified by its name attribute when it has
+/// one. Otherwise elements that share their tag with a sibling are
+/// identified by their zero-based index among those siblings.
+pub fn element_path(node: &roxmltree::Node) -> String {
+    let mut segments: Vec<String> = vec![];
+
+    let mut current = Some(*node);
+    while let Some(element) = current {
+        // stop at the root element, e.g. <mujoco>
+        if element.parent_element().is_none() {
+            break;
+        }
+        segments.push(path_segment(&element));
+        current = element.parent_element();
+    }
+
+    segments.reverse();
+    segments.join("/")
+}
+
+fn path_segment(node: &roxmltree::Node) -> String {
+    let tag = node.tag_name().name();
+    if let Some(name) = node.attribute("name") {
+        return format!("{}[name={}]", tag, name);
+    }
+
+    let parent = match node.parent_element() {
+        Some(parent) => parent,
+        None => return tag.to_string(),
+    };
+    let same_tag_siblings: Vec<roxmltree::Node> = parent
+        .children()
+        .filter(|sibling| sibling.is_element() && sibling.tag_name().name() == tag)
+        .collect();
+    if same_tag_siblings.len() <= 1 {
+        return tag.to_string();
+    }
+
+    let index = same_tag_siblings
+        .iter()
+        .position(|sibling| sibling == node)
+        .unwrap_or(0);
+    format!("{}[{}]", tag, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roxmltree;
+
+    const XML: &str = "<mujoco>
+  <worldbody>
+    <body name=\"arm\">
+      <geom size=\"1\"/>
+      <geom size=\"2\"/>
+    </body>
+  </worldbody>
+</mujoco>";
+
+    #[test]
+    fn element_path_uses_names_and_indices() {
+        let doc = roxmltree::Document::parse(XML).unwrap();
+        let geom = doc
+            .descendants()
+            .filter(|node| node.has_tag_name("geom"))
+            .nth(1)
+            .unwrap();
+
+        assert_eq!(element_path(&geom), "worldbody/body[name=arm]/geom[1]");
+    }
+
+    #[test]
+    fn location_of_node() {
+        let doc = roxmltree::Document::parse(XML).unwrap();
+        let geom = doc
+            .descendants()
+            .find(|node| node.has_tag_name("geom"))
+            .unwrap();
+
+        let source = Source::new(Some(Path::new("arm.xml")), XML);
+        let location = source.location(&geom);
+
+        assert_eq!(location.line, 4);
+        assert_eq!(location.column, 7);
+        assert_eq!(location.to_string(), "arm.xml:4:7");
+        assert_eq!(
+            location.source_line.as_ref().map(String::as_str),
+            Some("      <geom size=\"1\"/>")
+        );
+    }
+
+    #[test]
+    fn snippet_points_at_column() {
+        let doc = roxmltree::Document::parse(XML).unwrap();
+        let body = doc
+            .descendants()
+            .find(|node| node.has_tag_name("body"))
+            .unwrap();
+
+        let snippet = Source::new(None, XML).location(&body).snippet();
+        let expected = " --> <string>:3:5
+  |
+3 |     <body name=\"arm\">
+  |     ^
+  = in worldbody/body[name=arm]
+";
+        assert_eq!(snippet, expected);
+    }
+}
@@ -0,0 +1,323 @@
+//! Loading of the mesh files referenced by `<mesh>` assets.
+//!
+//! STL (ASCII and binary) and Wavefront OBJ files are supported. Only
+//! vertex positions and faces are read.
+
+use crate::error::{MJCFParseError, MJCFParseErrorKind};
+use crate::model::Model;
+use crate::options::ParseOptions;
+use crate::report::ParseReport;
+use failure::Fail;
+use nalgebra as na;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Vertices and triangles of a mesh file
+#[derive(Clone, PartialEq, Debug)]
+pub struct MeshData<N: na::RealField> {
+    pub vertices: Vec<na::Point3<N>>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+/// Loaded meshes, keyed by the name geoms refer to them by
+pub type Meshes<N> = HashMap<String, MeshData<N>>;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum MeshError {
+    #[fail(display = "Unsupported mesh format \"{}\". Expected stl or obj", 0)]
+    UnsupportedFormat(String),
+    #[fail(display = "Malformed STL file. {}", 0)]
+    BadStl(String),
+    #[fail(display = "Malformed OBJ file on line {}. {}", line, reason)]
+    BadObj { line: usize, reason: String },
+}
+
+/// Parse the contents of a mesh file, using the file's extension to
+/// tell its format
+pub fn parse_mesh<N: na::RealField>(file: &str, bytes: &[u8]) -> Result<MeshData<N>, MeshError> {
+    let extension = Path::new(file)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    match extension.as_ref().map(String::as_str) {
+        Some("stl") => parse_stl(bytes),
+        Some("obj") => parse_obj(&String::from_utf8_lossy(bytes)),
+        _ => Err(MeshError::UnsupportedFormat(file.to_string())),
+    }
+}
+
+fn parse_stl<N: na::RealField>(bytes: &[u8]) -> Result<MeshData<N>, MeshError> {
+    // binary files may also start with "solid", so check the size
+    // implied by the triangle count first
+    if bytes.len() >= 84 {
+        let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+        if bytes.len() == 84 + 50 * count {
+            return Ok(parse_binary_stl(&bytes[84..], count));
+        }
+    }
+    if bytes.starts_with(b"solid") {
+        parse_ascii_stl(&String::from_utf8_lossy(bytes))
+    } else {
+        Err(MeshError::BadStl(String::from(
+            "file is neither ASCII nor binary STL",
+        )))
+    }
+}
+
+fn parse_binary_stl<N: na::RealField>(bytes: &[u8], count: usize) -> MeshData<N> {
+    let read_f32 = |offset: usize| {
+        f32::from_bits(u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]))
+    };
+
+    let mut mesh = MeshData {
+        vertices: Vec::with_capacity(3 * count),
+        triangles: Vec::with_capacity(count),
+    };
+    for triangle in 0..count {
+        // skip the normal
+        let start = triangle * 50 + 12;
+        for vertex in 0..3 {
+            let offset = start + vertex * 12;
+            mesh.vertices.push(na::Point3::new(
+                na::convert(f64::from(read_f32(offset))),
+                na::convert(f64::from(read_f32(offset + 4))),
+                na::convert(f64::from(read_f32(offset + 8))),
+            ));
+        }
+        mesh.triangles
+            .push([3 * triangle, 3 * triangle + 1, 3 * triangle + 2]);
+    }
+    mesh
+}
+
+fn parse_ascii_stl<N: na::RealField>(text: &str) -> Result<MeshData<N>, MeshError> {
+    let mut mesh = MeshData {
+        vertices: vec![],
+        triangles: vec![],
+    };
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        if words.next() != Some("vertex") {
+            continue;
+        }
+        mesh.vertices
+            .push(parse_point(words).map_err(MeshError::BadStl)?);
+    }
+    if mesh.vertices.len() % 3 != 0 {
+        return Err(MeshError::BadStl(format!(
+            "{} vertices do not make whole triangles",
+            mesh.vertices.len()
+        )));
+    }
+    mesh.triangles = (0..mesh.vertices.len() / 3)
+        .map(|triangle| [3 * triangle, 3 * triangle + 1, 3 * triangle + 2])
+        .collect();
+    Ok(mesh)
+}
+
+fn parse_obj<N: na::RealField>(text: &str) -> Result<MeshData<N>, MeshError> {
+    let mut mesh = MeshData {
+        vertices: vec![],
+        triangles: vec![],
+    };
+    for (index, line) in text.lines().enumerate() {
+        let bad_obj = |reason: String| MeshError::BadObj {
+            line: index + 1,
+            reason,
+        };
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("v") => mesh.vertices.push(parse_point(words).map_err(bad_obj)?),
+            Some("f") => {
+                let mut face = vec![];
+                for word in words {
+                    // faces may also reference texture coordinates
+                    // and normals, e.g. "1/2/3"
+                    let vertex = word.split('/').next().unwrap_or_default();
+                    let vertex: i64 = vertex
+                        .parse()
+                        .map_err(|_| bad_obj(format!("bad vertex index \"{}\"", word)))?;
+                    // indices start at 1, and negative indices count
+                    // back from the last vertex
+                    let vertex = if vertex < 0 {
+                        mesh.vertices.len() as i64 + vertex
+                    } else {
+                        vertex - 1
+                    };
+                    if vertex < 0 || vertex as usize >= mesh.vertices.len() {
+                        return Err(bad_obj(format!("vertex index {} is out of range", word)));
+                    }
+                    face.push(vertex as usize);
+                }
+                if face.len() < 3 {
+                    return Err(bad_obj(String::from("face has fewer than 3 vertices")));
+                }
+                for corner in 1..face.len() - 1 {
+                    mesh.triangles
+                        .push([face[0], face[corner], face[corner + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(mesh)
+}
+
+fn parse_point<'a, N: na::RealField, I: Iterator<Item = &'a str>>(
+    words: I,
+) -> Result<na::Point3<N>, String> {
+    let coords = words
+        .take(3)
+        .map(|word| {
+            word.parse::<f64>()
+                .map_err(|_| format!("bad coordinate \"{}\"", word))
+        })
+        .collect::<Result<Vec<f64>, String>>()?;
+    if coords.len() != 3 {
+        return Err(format!("expected 3 coordinates, got {}", coords.len()));
+    }
+    Ok(na::Point3::new(
+        na::convert(coords[0]),
+        na::convert(coords[1]),
+        na::convert(coords[2]),
+    ))
+}
+
+/// Load every mesh asset of the model with the options' file
+/// resolver. Files are relative to the compiler's meshdir, which is
+/// relative to the model file at `base`.
+///
+/// Meshes that fail to load are recorded as errors, and left out.
+pub(crate) fn load_meshes<N: na::RealField>(
+    model: &Model<N>,
+    base: Option<&Path>,
+    options: &ParseOptions,
+    report: &mut ParseReport,
+) -> Meshes<N> {
+    let resolver = options.get_file_resolver();
+    let mut meshes = Meshes::new();
+    for mesh in &model.asset.meshes {
+        let (name, file) = match (mesh.reference_name(), &mesh.file) {
+            (Some(name), Some(file)) => (name, file),
+            _ => continue,
+        };
+        let file = match &model.compiler.meshdir {
+            Some(meshdir) => Path::new(meshdir).join(file).to_string_lossy().into_owned(),
+            None => file.clone(),
+        };
+        let path = resolver.resolve(base, &file);
+
+        let loaded = resolver
+            .read(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| parse_mesh(&file, &bytes).map_err(|error| error.to_string()));
+        match loaded {
+            Ok(mut data) => {
+                if let Some(scale) = &mesh.scale {
+                    for vertex in &mut data.vertices {
+                        vertex.coords.component_mul_assign(scale);
+                    }
+                }
+                meshes.insert(name, data);
+            }
+            Err(reason) => {
+                report.push_error(MJCFParseError::from(MJCFParseErrorKind::MeshLoadFailed {
+                    path: path.display().to_string(),
+                    reason,
+                }))
+            }
+        }
+    }
+    meshes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CUBE_OBJ: &str = "# unit cube
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+v 1 0 1
+v 1 1 1
+v 0 1 1
+f 1 4 3 2
+f 5 6 7 8
+f 1//1 2//1 6//1 5//1
+f -5 -1 -2 -6
+f 1 5 8 4
+f 2 3 7 6
+";
+
+    #[test]
+    fn parse_obj_faces_are_triangulated() {
+        let mesh = parse_mesh::<f32>("cube.obj", CUBE_OBJ.as_bytes()).unwrap();
+        assert_eq!(mesh.vertices.len(), 8);
+        assert_eq!(mesh.triangles.len(), 12);
+        assert_eq!(mesh.triangles[0], [0, 3, 2]);
+        // negative indices count back from the last vertex
+        assert_eq!(mesh.triangles[6], [3, 7, 6]);
+    }
+
+    #[test]
+    fn parse_obj_index_out_of_range() {
+        match parse_mesh::<f32>("bad.obj", b"v 0 0 0\nf 1 2 3\n") {
+            Err(MeshError::BadObj { line, .. }) => assert_eq!(line, 2),
+            result => panic!("Unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parse_ascii_stl() {
+        let stl = "solid triangle
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid triangle
+";
+        let mesh = parse_mesh::<f64>("triangle.STL", stl.as_bytes()).unwrap();
+        assert_eq!(mesh.vertices[1], na::Point3::new(1.0, 0.0, 0.0));
+        assert_eq!(mesh.triangles, vec![[0, 1, 2]]);
+    }
+
+    #[test]
+    fn parse_binary_stl() {
+        let mut stl = vec![0u8; 80];
+        stl.extend_from_slice(&1u32.to_le_bytes());
+        for value in &[
+            0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0, 0.0,
+        ] {
+            stl.extend_from_slice(&value.to_bits().to_le_bytes());
+        }
+        stl.extend_from_slice(&[0, 0]);
+
+        let mesh = parse_mesh::<f32>("triangle.stl", &stl).unwrap();
+        assert_eq!(
+            mesh.vertices,
+            vec![
+                na::Point3::new(0.0, 0.0, 0.0),
+                na::Point3::new(2.0, 0.0, 0.0),
+                na::Point3::new(0.0, 2.0, 0.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_mesh_format() {
+        assert_eq!(
+            parse_mesh::<f32>("part.dae", b""),
+            Err(MeshError::UnsupportedFormat(String::from("part.dae")))
+        );
+    }
+}
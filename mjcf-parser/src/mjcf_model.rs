@@ -1,17 +1,26 @@
-use crate::error::{MJCFParseError, MJCFParseErrorKind, MJCFParseResult};
-use crate::log;
+use crate::compile::{self, CompiledMultibody};
+use crate::error::MJCFParseResult;
+use crate::mesh;
+use crate::model::Model;
+use crate::names::{ModelHandles, WORLD_BODY_NAME};
+use crate::options::ParseOptions;
+use crate::parse::first_error;
+use crate::report::ParseReport;
 use crate::tags;
 use na::RealField;
 use nalgebra as na;
-use nphysics3d::object::ColliderDesc;
+use nphysics3d::object::{BodyPart, BodyPartHandle, ColliderDesc};
 use nphysics3d::world::World;
-use roxmltree;
-use slog::{debug, o, warn};
+use std::path::Path;
 use std::str::FromStr;
 
+/// A parsed model, along with the nphysics descriptors it compiles
+/// to
 pub struct MJCFModelDesc<N: RealField> {
     pub model_name: String,
+    model: Model<N>,
     world_colliders: Vec<ColliderDesc<N>>,
+    multibodies: Vec<CompiledMultibody<N>>,
 }
 
 impl<N: RealField> MJCFModelDesc<N>
@@ -20,98 +29,185 @@ where
     N: FromStr,
     <N as FromStr>::Err: std::fmt::Display,
 {
+    /// Parse an MJCF XML string with the default options, failing on
+    /// the first error.
     pub fn parse_xml_string(text: &str) -> MJCFParseResult<MJCFModelDesc<N>> {
-        let logger = log::LOG.read().unwrap().new(o!());
+        Self::parse_xml_string_with_options(text, &ParseOptions::default())
+    }
 
-        let mut mjcf_model = MJCFModelDesc {
-            model_name: String::from("MuJoCo Model"),
-            world_colliders: vec![],
-        };
+    /// Read and parse an MJCF XML file with the default options,
+    /// failing on the first error.
+    ///
+    /// Error locations refer to the file by the given path.
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> MJCFParseResult<MJCFModelDesc<N>> {
+        Self::parse_file_with_options(path, &ParseOptions::default())
+    }
 
-        debug!(logger, "Parsing XML string");
-        let doc = match roxmltree::Document::parse(text) {
-            Ok(doc) => doc,
-            Err(error) => {
-                return Err(MJCFParseError::from(MJCFParseErrorKind::BadXML(format!(
-                    "{}",
-                    error
-                ))));
-            }
-        };
+    /// Parse an MJCF XML string, failing on the first error.
+    pub fn parse_xml_string_with_options(
+        text: &str,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<MJCFModelDesc<N>> {
+        first_error(Self::parse_xml_string_with_report(text, options)?)
+    }
 
-        let root = doc.root_element();
+    /// Read and parse an MJCF XML file, failing on the first error.
+    ///
+    /// The file is read with the options' file resolver.
+    pub fn parse_file_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<MJCFModelDesc<N>> {
+        first_error(Self::parse_file_with_report(path, options)?)
+    }
 
-        // TODO(dschwab): change this to a proper error
-        if !root.has_tag_name("mujoco") {
-            return Err(MJCFParseError::from(
-                MJCFParseErrorKind::MissingRequiredTag {
-                    tag_name: String::from("mujoco"),
-                },
-            ));
-        }
-        if let Some(model_name) = root.attribute("model") {
-            mjcf_model.model_name = model_name.to_string();
-            debug!(logger, "Changed model name";
-                   "model_name" => &mjcf_model.model_name);
-        }
+    /// Parse an MJCF XML string, collecting every warning and
+    /// recoverable error.
+    ///
+    /// Only errors that prevent parsing the rest of the document,
+    /// e.g. malformed XML, are returned as `Err`. Elements that fail
+    /// to parse are left out of the returned model.
+    pub fn parse_xml_string_with_report(
+        text: &str,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(MJCFModelDesc<N>, ParseReport)> {
+        let (model, mut report) = Model::parse_xml_string_with_report(text, options)?;
+        let model_desc = Self::compile_model(model, None, options, &mut report);
+        Ok((model_desc, report))
+    }
 
-        for child in root.children() {
-            if let "worldbody" = child.tag_name().name() {
-                mjcf_model.parse_worldbody(&logger, &child)?;
-            }
-        }
+    /// Read and parse an MJCF XML file, collecting every warning and
+    /// recoverable error.
+    pub fn parse_file_with_report<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(MJCFModelDesc<N>, ParseReport)> {
+        let path = path.as_ref();
+        let (model, mut report) = Model::parse_file_with_report(path, options)?;
+        let model_desc = Self::compile_model(model, Some(path), options, &mut report);
+        Ok((model_desc, report))
+    }
 
-        Ok(mjcf_model)
+    /// Read and parse an MJCF, URDF or SDFormat file with the default
+    /// options, telling the format from the file's extension. Fails
+    /// on the first error.
+    pub fn load_file<P: AsRef<Path>>(path: P) -> MJCFParseResult<MJCFModelDesc<N>> {
+        first_error(Self::load_file_with_report(path, &ParseOptions::default())?)
     }
 
-    fn parse_worldbody(
-        &mut self,
-        logger: &slog::Logger,
-        worldbody_node: &roxmltree::Node,
-    ) -> Result<(), MJCFParseError> {
-        debug!(logger, "Parsing worldbody tag");
-        if !worldbody_node.attributes().is_empty() {
-            return Err(MJCFParseError::from(
-                MJCFParseErrorKind::WorldBodyHasAttributes,
-            ));
-        }
+    /// Read and parse an MJCF, URDF or SDFormat file, telling the
+    /// format from the file's extension, and collecting every warning
+    /// and recoverable error.
+    pub fn load_file_with_report<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(MJCFModelDesc<N>, ParseReport)> {
+        let path = path.as_ref();
+        let (model, mut report) = Model::load_file_with_report(path, options)?;
+        let model_desc = Self::compile_model(model, Some(path), options, &mut report);
+        Ok((model_desc, report))
+    }
 
-        for child in worldbody_node.children() {
-            // skip non-element tags
-            if !child.is_element() {
-                continue;
-            }
-            match child.tag_name().name() {
-                "inertial" | "joint" | "freejoint" => {
-                    return Err(MJCFParseError::from(
-                        MJCFParseErrorKind::WorldBodyInvalidChildren,
-                    ));
-                }
-                "body" => {} // TODO(dschwab): Parse me
-                "geom" => {
-                    self.world_colliders
-                        .push(tags::geom::parse_geom_node::<N>(logger, &child)?);
-                }
-                "site" => {}   // TODO(dschwab): Parse me
-                "camera" => {} // TODO(dschwab): Parse me
-                "light" => {}  // TODO(dschwab): Parse me
-                tag => warn!(logger, "Ignorning unsupported tag"; "child" => tag),
-            };
+    /// Compile a parsed, or programmatically built, model.
+    ///
+    /// Mesh files are relative to the working directory, and fail on
+    /// the first one that cannot be loaded.
+    pub fn from_model(
+        model: Model<N>,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<MJCFModelDesc<N>> {
+        let mut report = ParseReport::default();
+        let model_desc = Self::compile_model(model, None, options, &mut report);
+        first_error((model_desc, report))
+    }
+
+    /// Load the model's meshes relative to the model file at `base`,
+    /// and compile it
+    fn compile_model(
+        model: Model<N>,
+        base: Option<&Path>,
+        options: &ParseOptions,
+        report: &mut ParseReport,
+    ) -> MJCFModelDesc<N> {
+        let meshes = mesh::load_meshes(&model, base, options, report);
+        let compiled = compile::compile(&model, &meshes, options.get_tesselation(), report);
+        MJCFModelDesc {
+            model_name: model
+                .name
+                .clone()
+                .unwrap_or_else(|| String::from("MuJoCo Model")),
+            model,
+            world_colliders: compiled.world_colliders,
+            multibodies: compiled.multibodies,
         }
+    }
 
-        Ok(())
+    /// The typed model the descriptors were compiled from
+    pub fn model(&self) -> &Model<N> {
+        &self.model
     }
 
-    pub fn build<'w>(&mut self, world: &'w mut World<N>) {
+    /// User data from the model's `<custom>` sections
+    pub fn custom(&self) -> &tags::custom::Custom<N> {
+        &self.model.custom
+    }
+
+    /// Add the model's bodies and colliders to the world, and set the
+    /// world's gravity and timestep from the model's options.
+    ///
+    /// Each tree of bodies below the worldbody is built as one
+    /// multibody. Returns the handles of every named element that was
+    /// built, keyed by its MJCF name.
+    pub fn build<'w>(&mut self, world: &'w mut World<N>) -> ModelHandles {
+        let mut handles = ModelHandles::default();
+        handles
+            .bodies
+            .insert(WORLD_BODY_NAME.to_string(), BodyPartHandle::ground());
+
+        world.set_gravity(self.model.option.gravity_or_default());
+        world.set_timestep(self.model.option.timestep_or_default());
+
         for world_collider in &self.world_colliders {
-            world_collider.build(world);
+            let collider = world_collider.build(world);
+            if !collider.name().is_empty() {
+                handles
+                    .colliders
+                    .insert(collider.name().to_string(), collider.handle());
+            }
         }
+
+        for compiled in &self.multibodies {
+            let multibody = compiled.desc.build(world);
+            let link_handles: Vec<BodyPartHandle> = (0..compiled.link_names.len())
+                .map(|index| multibody.link(index).unwrap().part_handle())
+                .collect();
+
+            for (name, handle) in compiled.link_names.iter().zip(&link_handles) {
+                if let Some(name) = name {
+                    handles.bodies.insert(name.clone(), *handle);
+                }
+            }
+            for (index, collider_desc) in &compiled.link_colliders {
+                if let Some(collider) = collider_desc.build_with_parent(link_handles[*index], world)
+                {
+                    if !collider.name().is_empty() {
+                        handles
+                            .colliders
+                            .insert(collider.name().to_string(), collider.handle());
+                    }
+                }
+            }
+        }
+
+        handles
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::MJCFParseErrorKind;
+    use crate::report::WarningCode;
 
     #[test]
     fn parse_malformed_xml() {
@@ -198,4 +294,225 @@ mod tests {
             _ => panic!("Model parse successfully when worldbody has freejoint child"),
         };
     }
+
+    #[test]
+    fn geom_error_has_location() {
+        let xml = "<mujoco>
+  <worldbody>
+    <geom type=\"box\"/>
+  </worldbody>
+</mujoco>";
+
+        let error = match MJCFModelDesc::<f32>::parse_xml_string(xml) {
+            Err(error) => error,
+            _ => panic!("Model parsed successfully with missing geom size"),
+        };
+        let location = error.location().expect("Error is missing its location");
+        assert_eq!(location.line, 3);
+        assert_eq!(location.column, 5);
+        assert_eq!(
+            location.element_path.as_ref().map(String::as_str),
+            Some("worldbody/geom")
+        );
+        assert!(format!("{}", error).contains("3 |     <geom type=\"box\"/>"));
+    }
+
+    #[test]
+    fn bad_xml_error_has_location() {
+        let model_result = MJCFModelDesc::<f32>::parse_xml_string("<mujoco>\n<worldbody>");
+        match model_result {
+            Err(error) => assert!(error.location().is_some()),
+            _ => panic!("Model parsed successfully with bad xml"),
+        };
+    }
+
+    #[test]
+    fn parse_missing_file() {
+        let model_result = MJCFModelDesc::<f32>::parse_file("does/not/exist.xml");
+        match model_result {
+            Err(error) => match error.kind() {
+                MJCFParseErrorKind::ReadFailed { path, .. } => {
+                    assert_eq!(path, "does/not/exist.xml")
+                }
+                _ => panic!("Got unexpected error type {}", error),
+            },
+            _ => panic!("Parsed a file that does not exist"),
+        };
+    }
+
+    #[test]
+    fn report_collects_all_errors_and_warnings() {
+        let xml = "<mujoco>
+                     <actuator/>
+                     <worldbody>
+                       <geom type=\"box\"/>
+                       <geom size=\"1\" contype=\"0\"/>
+                       <geom type=\"ellipsoid\" size=\"1 2 3\"/>
+                       <light/>
+                     </worldbody>
+                   </mujoco>";
+
+        let (model, report) =
+            MJCFModelDesc::<f32>::parse_xml_string_with_report(xml, &ParseOptions::default())
+                .unwrap();
+
+        assert_eq!(model.world_colliders.len(), 1);
+        assert_eq!(report.errors().len(), 2);
+        assert_eq!(report.warning_count(WarningCode::UnsupportedAttribute), 1);
+        assert_eq!(report.warning_count(WarningCode::IgnoredTag), 2);
+        for error in report.errors() {
+            assert!(error.location().is_some());
+        }
+        for warning in report.warnings() {
+            assert!(warning.location.is_some());
+        }
+    }
+
+    #[test]
+    fn clean_model_has_empty_report() {
+        let xml = "<mujoco><worldbody><geom size=\"1\"/></worldbody></mujoco>";
+
+        let (_, report) =
+            MJCFModelDesc::<f32>::parse_xml_string_with_report(xml, &ParseOptions::default())
+                .unwrap();
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn duplicate_geom_name_is_invalid() {
+        let xml = "<mujoco>
+                     <worldbody>
+                       <geom name=\"plane\" type=\"plane\"/>
+                       <geom name=\"plane\" type=\"plane\"/>
+                     </worldbody>
+                   </mujoco>";
+
+        let model_result = MJCFModelDesc::<f32>::parse_xml_string(xml);
+        match model_result {
+            Err(error) => match error.kind() {
+                MJCFParseErrorKind::DuplicateName { namespace, name } => {
+                    assert_eq!(namespace, crate::names::Namespace::Geom);
+                    assert_eq!(name, "plane");
+                }
+                _ => panic!("Got unexpected error type {}", error),
+            },
+            _ => panic!("Model parse successfully with duplicate geom names"),
+        };
+    }
+
+    #[test]
+    fn build_returns_named_handles() {
+        let xml = "<mujoco>
+                     <worldbody>
+                       <geom name=\"ball\" size=\"1\"/>
+                       <geom size=\"2\"/>
+                     </worldbody>
+                   </mujoco>";
+
+        let mut model = MJCFModelDesc::<f32>::parse_xml_string(xml).unwrap();
+        let mut world = World::new();
+        let handles = model.build(&mut world);
+
+        let ball_handle = handles.collider("ball").unwrap();
+        assert_eq!(world.collider(ball_handle).unwrap().name(), "ball");
+        assert_eq!(handles.colliders().count(), 1);
+        assert_eq!(
+            handles.body(WORLD_BODY_NAME),
+            Some(BodyPartHandle::ground())
+        );
+    }
+
+    #[test]
+    fn parse_custom_section() {
+        let xml = "<mujoco>
+                     <custom>
+                       <numeric name=\"goal\" data=\"0.5 0.5 1\"/>
+                       <text name=\"task\" data=\"reach\"/>
+                     </custom>
+                     <custom>
+                       <tuple name=\"targets\"><element objtype=\"geom\" objname=\"ball\"/></tuple>
+                     </custom>
+                   </mujoco>";
+
+        let model = MJCFModelDesc::<f32>::parse_xml_string(xml).unwrap();
+        assert_eq!(model.custom().numeric("goal"), Some(&[0.5, 0.5, 1.0][..]));
+        assert_eq!(model.custom().text("task"), Some("reach"));
+        assert_eq!(model.custom().tuple("targets").unwrap()[0].objname, "ball");
+    }
+
+    #[test]
+    fn misspelled_attribute_is_a_warning_by_default() {
+        let xml = "<mujoco><worldbody><geom size=\"1\" rgab=\"1 0 0 1\"/></worldbody></mujoco>";
+
+        let (model, report) =
+            MJCFModelDesc::<f32>::parse_xml_string_with_report(xml, &ParseOptions::default())
+                .unwrap();
+        assert_eq!(model.world_colliders.len(), 1);
+        assert!(!report.has_errors());
+        assert_eq!(report.warning_count(WarningCode::UnknownAttribute), 1);
+    }
+
+    #[test]
+    fn misspelled_attribute_is_an_error_when_strict() {
+        let xml = "<mujoco><worldbody><geom size=\"1\" rgab=\"1 0 0 1\"/></worldbody></mujoco>";
+
+        let (_, report) =
+            MJCFModelDesc::<f32>::parse_xml_string_with_report(xml, &ParseOptions::strict())
+                .unwrap();
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].kind().code(), "unknown-attribute");
+    }
+
+    #[test]
+    fn unknown_tag_is_not_reported_as_unsupported() {
+        let xml = "<mujoco><worldbody><goem size=\"1\"/></worldbody></mujoco>";
+
+        let (_, report) =
+            MJCFModelDesc::<f32>::parse_xml_string_with_report(xml, &ParseOptions::default())
+                .unwrap();
+        assert_eq!(report.warning_count(WarningCode::UnknownElement), 1);
+        assert_eq!(report.warning_count(WarningCode::IgnoredTag), 0);
+    }
+
+    #[test]
+    fn parse_with_custom_logger() {
+        let logger = slog::Logger::root(slog::Discard, slog::o!());
+        let options = ParseOptions::new().logger(logger).tesselation(8);
+        let xml = "<mujoco><worldbody><geom type=\"cylinder\" size=\"1 2\"/></worldbody></mujoco>";
+
+        let (_, report) =
+            MJCFModelDesc::<f32>::parse_xml_string_with_report(xml, &options).unwrap();
+        let warning = &report.warnings()[0];
+        assert_eq!(warning.code, WarningCode::ApproximatedShape);
+        assert!(warning.message.contains("8 subdivisions"));
+    }
+
+    #[test]
+    fn build_body_tree_as_multibody() {
+        let xml = "<mujoco>
+                     <option gravity=\"0 0 -1\"/>
+                     <worldbody>
+                       <body name=\"upper\" pos=\"0 0 1\">
+                         <joint type=\"hinge\" axis=\"0 1 0\"/>
+                         <geom name=\"upper_arm\" type=\"capsule\" size=\"0.05\" fromto=\"0 0 0 0 0 -0.5\"/>
+                         <body name=\"lower\" pos=\"0 0 -0.5\">
+                           <joint type=\"hinge\" axis=\"0 1 0\"/>
+                           <geom type=\"sphere\" size=\"0.1\"/>
+                         </body>
+                       </body>
+                     </worldbody>
+                   </mujoco>";
+
+        let mut model = MJCFModelDesc::<f32>::parse_xml_string(xml).unwrap();
+        let mut world = World::new();
+        let handles = model.build(&mut world);
+
+        assert_eq!(*world.gravity(), na::Vector3::new(0.0, 0.0, -1.0));
+        let upper = handles.body("upper").unwrap();
+        let lower = handles.body("lower").unwrap();
+        assert_ne!(upper, lower);
+        assert!(world
+            .collider(handles.collider("upper_arm").unwrap())
+            .is_some());
+    }
 }
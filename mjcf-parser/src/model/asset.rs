@@ -0,0 +1,131 @@
+use super::RawElement;
+use nalgebra as na;
+use serde::{Deserialize, Serialize};
+
+/// A `<mesh>` asset
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Mesh<N: na::RealField> {
+    pub name: Option<String>,
+    pub class: Option<String>,
+    /// Path of the mesh file, relative to the compiler's meshdir
+    pub file: Option<String>,
+    pub scale: Option<na::Vector3<N>>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+}
+
+impl<N: na::RealField> Mesh<N> {
+    /// Name geoms use to refer to the mesh. MuJoCo falls back to the
+    /// file name without its extension.
+    pub fn reference_name(&self) -> Option<String> {
+        match (&self.name, &self.file) {
+            (Some(name), _) => Some(name.clone()),
+            (None, Some(file)) => std::path::Path::new(file)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// A `<material>` asset
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Material {
+    pub name: Option<String>,
+    pub class: Option<String>,
+    pub texture: Option<String>,
+    pub rgba: Option<na::Vector4<f32>>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+}
+
+/// A `<texture>` asset
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Texture {
+    pub name: Option<String>,
+    pub texture_type: Option<String>,
+    pub file: Option<String>,
+    pub builtin: Option<String>,
+    pub rgb1: Option<na::Vector3<f32>>,
+    pub rgb2: Option<na::Vector3<f32>>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+}
+
+/// Contents of the model's `<asset>` sections
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Asset<N: na::RealField> {
+    pub meshes: Vec<Mesh<N>>,
+    pub materials: Vec<Material>,
+    pub textures: Vec<Texture>,
+    /// Assets without a typed representation, e.g. height fields and
+    /// skins
+    pub raw_assets: Vec<RawElement>,
+}
+
+impl<N: na::RealField> Default for Asset<N> {
+    fn default() -> Self {
+        Asset {
+            meshes: vec![],
+            materials: vec![],
+            textures: vec![],
+            raw_assets: vec![],
+        }
+    }
+}
+
+impl<N: na::RealField> Asset<N> {
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty()
+            && self.materials.is_empty()
+            && self.textures.is_empty()
+            && self.raw_assets.is_empty()
+    }
+
+    pub fn mesh(&self, name: &str) -> Option<&Mesh<N>> {
+        self.meshes
+            .iter()
+            .find(|mesh| mesh.reference_name().as_ref().map(String::as_str) == Some(name))
+    }
+
+    pub fn material(&self, name: &str) -> Option<&Material> {
+        self.materials
+            .iter()
+            .find(|material| material.name.as_ref().map(String::as_str) == Some(name))
+    }
+
+    /// Name of the mesh asset for a file and scale, adding the asset
+    /// if there is none yet. Used by importers, whose geoms refer to
+    /// mesh files directly.
+    pub(crate) fn add_mesh_file(&mut self, file: &str, scale: Option<na::Vector3<N>>) -> String {
+        let existing = self.meshes.iter().find(|mesh| {
+            mesh.file.as_ref().map(String::as_str) == Some(file) && mesh.scale == scale
+        });
+        if let Some(name) = existing.and_then(Mesh::reference_name) {
+            return name;
+        }
+
+        // the same file may be used with different scales
+        let stem = std::path::Path::new(file)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| String::from("mesh"));
+        let mut name = stem.clone();
+        let mut suffix = 1;
+        while self.mesh(&name).is_some() {
+            name = format!("{}_{}", stem, suffix);
+            suffix += 1;
+        }
+
+        self.meshes.push(Mesh {
+            name: Some(name.clone()),
+            class: None,
+            file: Some(file.to_string()),
+            scale,
+            raw_attributes: vec![],
+        });
+        name
+    }
+}
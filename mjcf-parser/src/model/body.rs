@@ -0,0 +1,235 @@
+use super::{Geom, GeomType, RawElement};
+use nalgebra as na;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Mass properties of a body from its `<inertial>` tag
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Inertial<N: na::RealField> {
+    pub mass: N,
+    /// Center of mass in the body's frame
+    pub pos: na::Vector3<N>,
+    /// Orientation of the inertia frame in the body's frame
+    pub quat: na::UnitQuaternion<N>,
+    /// Moments of inertia about the axes of the inertia frame
+    pub diaginertia: Option<na::Vector3<N>>,
+    /// Full inertia matrix as M(1,1), M(2,2), M(3,3), M(1,2),
+    /// M(1,3), M(2,3)
+    pub fullinertia: Option<na::Vector6<N>>,
+}
+
+impl<N: na::RealField> Inertial<N> {
+    /// Inertia matrix in the body's frame
+    pub fn inertia_matrix(&self) -> na::Matrix3<N> {
+        let local = match (&self.fullinertia, &self.diaginertia) {
+            (Some(full), _) => na::Matrix3::new(
+                full[0], full[3], full[4], full[3], full[1], full[5], full[4], full[5], full[2],
+            ),
+            (None, Some(diagonal)) => na::Matrix3::from_diagonal(diagonal),
+            (None, None) => na::Matrix3::zeros(),
+        };
+        let rotation = self.quat.to_rotation_matrix();
+        rotation.matrix() * local * rotation.matrix().transpose()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum JointType {
+    Free,
+    Ball,
+    Slide,
+    Hinge,
+}
+
+impl Default for JointType {
+    fn default() -> Self {
+        JointType::Hinge
+    }
+}
+
+impl JointType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JointType::Free => "free",
+            JointType::Ball => "ball",
+            JointType::Slide => "slide",
+            JointType::Hinge => "hinge",
+        }
+    }
+}
+
+impl FromStr for JointType {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "free" => Ok(JointType::Free),
+            "ball" => Ok(JointType::Ball),
+            "slide" => Ok(JointType::Slide),
+            "hinge" => Ok(JointType::Hinge),
+            _ => Err(text.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for JointType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A `<joint>` or `<freejoint>` element
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Joint<N: na::RealField> {
+    pub name: Option<String>,
+    pub class: Option<String>,
+    pub joint_type: JointType,
+    /// Position of the joint anchor in the body's frame
+    pub pos: na::Vector3<N>,
+    /// Rotation or translation axis in the body's frame
+    pub axis: na::Vector3<N>,
+    pub limited: Option<bool>,
+    /// Joint limits, in the model's angle unit for hinges
+    pub range: Option<na::Vector2<N>>,
+    pub damping: Option<N>,
+    pub stiffness: Option<N>,
+    pub armature: Option<N>,
+    pub frictionloss: Option<N>,
+    pub group: Option<i32>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+}
+
+impl<N: na::RealField> Default for Joint<N> {
+    fn default() -> Self {
+        Joint {
+            name: None,
+            class: None,
+            joint_type: JointType::default(),
+            pos: na::Vector3::zeros(),
+            axis: na::Vector3::z(),
+            limited: None,
+            range: None,
+            damping: None,
+            stiffness: None,
+            armature: None,
+            frictionloss: None,
+            group: None,
+            raw_attributes: vec![],
+        }
+    }
+}
+
+impl<N: na::RealField> Joint<N> {
+    pub fn new(joint_type: JointType) -> Self {
+        Joint {
+            joint_type,
+            ..Default::default()
+        }
+    }
+
+    /// True when the joint's range should be enforced. MuJoCo only
+    /// enforces ranges on joints marked as limited.
+    pub fn is_limited(&self) -> bool {
+        self.limited.unwrap_or(false) && self.range.is_some()
+    }
+}
+
+/// A `<site>` element. Sites mark locations of interest, and have
+/// no physical effect.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Site<N: na::RealField> {
+    pub name: Option<String>,
+    pub class: Option<String>,
+    pub site_type: GeomType,
+    pub size: Vec<N>,
+    pub fromto: Option<na::Vector6<N>>,
+    pub pos: na::Vector3<N>,
+    pub quat: na::UnitQuaternion<N>,
+    pub rgba: Option<na::Vector4<f32>>,
+    pub group: Option<i32>,
+    pub material: Option<String>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+}
+
+impl<N: na::RealField> Default for Site<N> {
+    fn default() -> Self {
+        Site {
+            name: None,
+            class: None,
+            site_type: GeomType::Sphere,
+            size: vec![],
+            fromto: None,
+            pos: na::Vector3::zeros(),
+            quat: na::UnitQuaternion::identity(),
+            rgba: None,
+            group: None,
+            material: None,
+            raw_attributes: vec![],
+        }
+    }
+}
+
+/// A `<body>` element, or the `<worldbody>`
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Body<N: na::RealField> {
+    pub name: Option<String>,
+    pub childclass: Option<String>,
+    /// Position in the parent body's frame
+    pub pos: na::Vector3<N>,
+    /// Orientation in the parent body's frame
+    pub quat: na::UnitQuaternion<N>,
+    pub mocap: Option<bool>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+    pub inertial: Option<Inertial<N>>,
+    pub joints: Vec<Joint<N>>,
+    pub geoms: Vec<Geom<N>>,
+    pub sites: Vec<Site<N>>,
+    pub bodies: Vec<Body<N>>,
+    /// Children without a typed representation, e.g. cameras and
+    /// lights
+    pub raw_children: Vec<RawElement>,
+}
+
+impl<N: na::RealField> Default for Body<N> {
+    fn default() -> Self {
+        Body {
+            name: None,
+            childclass: None,
+            pos: na::Vector3::zeros(),
+            quat: na::UnitQuaternion::identity(),
+            mocap: None,
+            raw_attributes: vec![],
+            inertial: None,
+            joints: vec![],
+            geoms: vec![],
+            sites: vec![],
+            bodies: vec![],
+            raw_children: vec![],
+        }
+    }
+}
+
+impl<N: na::RealField> Body<N> {
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Body {
+            name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Pose of the body in its parent's frame
+    pub fn pose(&self) -> na::Isometry3<N> {
+        na::Isometry3::from_parts(na::Translation3::from(self.pos), self.quat)
+    }
+
+    pub(crate) fn collect_descendants<'a>(&'a self, bodies: &mut Vec<&'a Body<N>>) {
+        for body in &self.bodies {
+            bodies.push(body);
+            body.collect_descendants(bodies);
+        }
+    }
+}
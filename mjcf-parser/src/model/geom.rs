@@ -0,0 +1,143 @@
+use nalgebra as na;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum GeomType {
+    Plane,
+    Hfield,
+    Sphere,
+    Capsule,
+    Ellipsoid,
+    Cylinder,
+    Box,
+    Mesh,
+}
+
+impl Default for GeomType {
+    fn default() -> Self {
+        GeomType::Sphere
+    }
+}
+
+impl GeomType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            GeomType::Plane => "plane",
+            GeomType::Hfield => "hfield",
+            GeomType::Sphere => "sphere",
+            GeomType::Capsule => "capsule",
+            GeomType::Ellipsoid => "ellipsoid",
+            GeomType::Cylinder => "cylinder",
+            GeomType::Box => "box",
+            GeomType::Mesh => "mesh",
+        }
+    }
+
+    /// True for the types whose size and pose can be given by a
+    /// `fromto` attribute
+    pub fn allows_fromto(self) -> bool {
+        match self {
+            GeomType::Capsule | GeomType::Cylinder | GeomType::Box | GeomType::Ellipsoid => true,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for GeomType {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "plane" => Ok(GeomType::Plane),
+            "hfield" => Ok(GeomType::Hfield),
+            "sphere" => Ok(GeomType::Sphere),
+            "capsule" => Ok(GeomType::Capsule),
+            "ellipsoid" => Ok(GeomType::Ellipsoid),
+            "cylinder" => Ok(GeomType::Cylinder),
+            "box" => Ok(GeomType::Box),
+            "mesh" => Ok(GeomType::Mesh),
+            _ => Err(text.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for GeomType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A `<geom>` element.
+///
+/// Optional values are `None` when the attribute was not written,
+/// so MuJoCo's defaults can be told apart from explicit values.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Geom<N: na::RealField> {
+    pub name: Option<String>,
+    pub class: Option<String>,
+    pub geom_type: GeomType,
+    /// Size values as written. The number of values depends on the
+    /// type, e.g. a radius for spheres and half sizes for boxes.
+    pub size: Vec<N>,
+    /// Start and end points of the geom's axis. When set, `pos` and
+    /// `quat` are unused.
+    pub fromto: Option<na::Vector6<N>>,
+    /// Position in the parent body's frame
+    pub pos: na::Vector3<N>,
+    /// Orientation in the parent body's frame
+    pub quat: na::UnitQuaternion<N>,
+    pub rgba: Option<na::Vector4<f32>>,
+    /// Sliding, torsional and rolling friction
+    pub friction: Option<na::Vector3<N>>,
+    pub density: Option<N>,
+    pub mass: Option<N>,
+    pub margin: Option<N>,
+    pub group: Option<i32>,
+    pub contype: Option<i32>,
+    pub conaffinity: Option<i32>,
+    pub condim: Option<i32>,
+    pub material: Option<String>,
+    pub mesh: Option<String>,
+    pub hfield: Option<String>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+}
+
+impl<N: na::RealField> Default for Geom<N> {
+    fn default() -> Self {
+        Geom {
+            name: None,
+            class: None,
+            geom_type: GeomType::default(),
+            size: vec![],
+            fromto: None,
+            pos: na::Vector3::zeros(),
+            quat: na::UnitQuaternion::identity(),
+            rgba: None,
+            friction: None,
+            density: None,
+            mass: None,
+            margin: None,
+            group: None,
+            contype: None,
+            conaffinity: None,
+            condim: None,
+            material: None,
+            mesh: None,
+            hfield: None,
+            raw_attributes: vec![],
+        }
+    }
+}
+
+impl<N: na::RealField> Geom<N> {
+    pub fn new(geom_type: GeomType, size: Vec<N>) -> Self {
+        Geom {
+            geom_type,
+            size,
+            ..Default::default()
+        }
+    }
+}
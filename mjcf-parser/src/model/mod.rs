@@ -0,0 +1,184 @@
+//! Typed description of a parsed model.
+//!
+//! This mirrors the structure of the MJCF document, keeping values
+//! as they were written (names, classes, fromto, asset references)
+//! instead of the nphysics descriptors they are compiled to. Every
+//! type can be serialized with serde, so models can be inspected,
+//! diffed and stored.
+//!
+//! Sections the parser does not have a typed representation for are
+//! kept as `RawElement`s.
+
+mod asset;
+mod body;
+mod geom;
+
+pub use self::asset::{Asset, Material, Mesh, Texture};
+pub use self::body::{Body, Inertial, Joint, JointType, Site};
+pub use self::geom::{Geom, GeomType};
+
+use crate::tags::custom::Custom;
+use nalgebra as na;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Unit of the angles in the model. MuJoCo defaults to degrees.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Angle {
+    Degree,
+    Radian,
+}
+
+impl Default for Angle {
+    fn default() -> Self {
+        Angle::Degree
+    }
+}
+
+impl Angle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Angle::Degree => "degree",
+            Angle::Radian => "radian",
+        }
+    }
+
+    /// Convert an angle in this unit to radians
+    pub fn to_radians<N: na::RealField>(self, value: N) -> N {
+        match self {
+            Angle::Degree => value * N::pi() / na::convert(180.0),
+            Angle::Radian => value,
+        }
+    }
+}
+
+impl FromStr for Angle {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "degree" => Ok(Angle::Degree),
+            "radian" => Ok(Angle::Radian),
+            _ => Err(text.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Angle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Settings from the `<compiler>` tag that affect how the rest of
+/// the model is interpreted
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Compiler {
+    pub angle: Angle,
+    pub meshdir: Option<String>,
+    pub texturedir: Option<String>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+}
+
+/// Simulation settings from the `<option>` tag
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SimOption<N: na::RealField> {
+    pub timestep: Option<N>,
+    pub gravity: Option<na::Vector3<N>>,
+    pub density: Option<N>,
+    pub viscosity: Option<N>,
+    /// Attributes without a typed representation, as written
+    pub raw_attributes: Vec<(String, String)>,
+    /// Children without a typed representation, e.g. `<flag>`
+    pub raw_children: Vec<RawElement>,
+}
+
+impl<N: na::RealField> Default for SimOption<N> {
+    fn default() -> Self {
+        SimOption {
+            timestep: None,
+            gravity: None,
+            density: None,
+            viscosity: None,
+            raw_attributes: vec![],
+            raw_children: vec![],
+        }
+    }
+}
+
+impl<N: na::RealField> SimOption<N> {
+    /// Timestep, or the MuJoCo default when it was not set
+    pub fn timestep_or_default(&self) -> N {
+        self.timestep.unwrap_or_else(|| na::convert(0.002))
+    }
+
+    /// Gravity, or the MuJoCo default when it was not set
+    pub fn gravity_or_default(&self) -> na::Vector3<N> {
+        self.gravity
+            .unwrap_or_else(|| na::Vector3::new(N::zero(), N::zero(), na::convert(-9.81)))
+    }
+}
+
+/// An element kept as it was written in the document
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct RawElement {
+    pub tag: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<RawElement>,
+}
+
+impl RawElement {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A complete model, as described by a `<mujoco>` document
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Model<N: na::RealField> {
+    /// Value of the `model` attribute
+    pub name: Option<String>,
+    pub compiler: Compiler,
+    pub option: SimOption<N>,
+    pub asset: Asset<N>,
+    /// The implicit world body. Its geoms and sites are static.
+    pub worldbody: Body<N>,
+    pub custom: Custom<N>,
+    /// Top level sections without a typed representation, e.g.
+    /// `<actuator>` and `<sensor>`, in document order
+    pub raw_sections: Vec<RawElement>,
+}
+
+impl<N: na::RealField> Default for Model<N> {
+    fn default() -> Self {
+        Model {
+            name: None,
+            compiler: Compiler::default(),
+            option: SimOption::default(),
+            asset: Asset::default(),
+            worldbody: Body::default(),
+            custom: Custom::default(),
+            raw_sections: vec![],
+        }
+    }
+}
+
+impl<N: na::RealField> Model<N> {
+    /// Every body in the model, excluding the world body, in
+    /// depth-first document order
+    pub fn bodies(&self) -> Vec<&Body<N>> {
+        let mut bodies = vec![];
+        self.worldbody.collect_descendants(&mut bodies);
+        bodies
+    }
+
+    /// First raw section with the given tag
+    pub fn raw_section(&self, tag: &str) -> Option<&RawElement> {
+        self.raw_sections.iter().find(|section| section.tag == tag)
+    }
+}
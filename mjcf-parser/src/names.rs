@@ -0,0 +1,306 @@
+use crate::context::ParseContext;
+use crate::error::{MJCFParseError, MJCFParseErrorKind, MJCFParseResult};
+use nphysics3d::object::{BodyPartHandle, ColliderHandle};
+use roxmltree;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Name of the implicit body created by the `<worldbody>` tag
+pub const WORLD_BODY_NAME: &str = "world";
+
+/// MJCF names are only required to be unique among elements of the
+/// same kind. E.g. a geom and a body may share a name, but two geoms
+/// may not.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Namespace {
+    Body,
+    Joint,
+    Geom,
+    Site,
+    Camera,
+    Light,
+    Mesh,
+    Skin,
+    Hfield,
+    Texture,
+    Material,
+    Pair,
+    Exclude,
+    Equality,
+    Tendon,
+    Actuator,
+    Sensor,
+    Numeric,
+    Text,
+    Tuple,
+    Key,
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Namespace::Body => "body",
+            Namespace::Joint => "joint",
+            Namespace::Geom => "geom",
+            Namespace::Site => "site",
+            Namespace::Camera => "camera",
+            Namespace::Light => "light",
+            Namespace::Mesh => "mesh",
+            Namespace::Skin => "skin",
+            Namespace::Hfield => "hfield",
+            Namespace::Texture => "texture",
+            Namespace::Material => "material",
+            Namespace::Pair => "pair",
+            Namespace::Exclude => "exclude",
+            Namespace::Equality => "equality",
+            Namespace::Tendon => "tendon",
+            Namespace::Actuator => "actuator",
+            Namespace::Sensor => "sensor",
+            Namespace::Numeric => "numeric",
+            Namespace::Text => "text",
+            Namespace::Tuple => "tuple",
+            Namespace::Key => "key",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Namespace {
+    /// Namespace of an element inside one of the top level sections
+    /// of the model. Returns `None` for elements that can not be
+    /// named.
+    fn of_section_child(section: &str, tag: &str) -> Option<Namespace> {
+        match (section, tag) {
+            ("worldbody", "body") => Some(Namespace::Body),
+            ("worldbody", "joint") | ("worldbody", "freejoint") => Some(Namespace::Joint),
+            ("worldbody", "geom") => Some(Namespace::Geom),
+            ("worldbody", "site") => Some(Namespace::Site),
+            ("worldbody", "camera") => Some(Namespace::Camera),
+            ("worldbody", "light") => Some(Namespace::Light),
+            ("asset", "mesh") => Some(Namespace::Mesh),
+            ("asset", "skin") => Some(Namespace::Skin),
+            ("asset", "hfield") => Some(Namespace::Hfield),
+            ("asset", "texture") => Some(Namespace::Texture),
+            ("asset", "material") => Some(Namespace::Material),
+            ("contact", "pair") => Some(Namespace::Pair),
+            ("contact", "exclude") => Some(Namespace::Exclude),
+            ("equality", _) => Some(Namespace::Equality),
+            ("tendon", "spatial") | ("tendon", "fixed") => Some(Namespace::Tendon),
+            ("actuator", _) => Some(Namespace::Actuator),
+            ("sensor", _) => Some(Namespace::Sensor),
+            ("custom", "numeric") => Some(Namespace::Numeric),
+            ("custom", "text") => Some(Namespace::Text),
+            ("custom", "tuple") => Some(Namespace::Tuple),
+            ("keyframe", "key") => Some(Namespace::Key),
+            _ => None,
+        }
+    }
+}
+
+/// Tracks the names used in each namespace of a model
+#[derive(Clone, Debug)]
+pub struct NameRegistry {
+    names: HashMap<Namespace, HashSet<String>>,
+}
+
+impl Default for NameRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NameRegistry {
+    /// Create a registry containing only the implicit world body
+    pub fn new() -> Self {
+        let mut names = HashMap::new();
+        let mut body_names = HashSet::new();
+        body_names.insert(WORLD_BODY_NAME.to_string());
+        names.insert(Namespace::Body, body_names);
+        NameRegistry { names }
+    }
+
+    /// Collect the names of every element in a `<mujoco>` tag.
+    ///
+    /// Every name that is used twice in the same namespace is
+    /// reported as an error.
+    pub fn from_mujoco_node(ctx: &mut ParseContext, mujoco_node: &roxmltree::Node) -> NameRegistry {
+        let mut registry = NameRegistry::new();
+        for section in mujoco_node.children().filter(|node| node.is_element()) {
+            let section_name = section.tag_name().name();
+            // defaults are templates, not model elements
+            if section_name == "default" {
+                continue;
+            }
+            registry.register_children(ctx, section_name, &section);
+        }
+        registry
+    }
+
+    fn register_children(&mut self, ctx: &mut ParseContext, section: &str, node: &roxmltree::Node) {
+        for child in node.children().filter(|node| node.is_element()) {
+            if let Some(namespace) = Namespace::of_section_child(section, child.tag_name().name()) {
+                if let Some(name) = child.attribute("name") {
+                    if let Err(error) = self.register(namespace, name) {
+                        ctx.error(&child, error);
+                    }
+                }
+            }
+
+            // bodies are the only named elements that nest
+            if section == "worldbody" && child.has_tag_name("body") {
+                self.register_children(ctx, section, &child);
+            }
+        }
+    }
+
+    /// Add a name to a namespace
+    pub fn register(&mut self, namespace: Namespace, name: &str) -> MJCFParseResult<()> {
+        let names = self.names.entry(namespace).or_insert_with(HashSet::new);
+        if !names.insert(name.to_string()) {
+            return Err(MJCFParseError::from(MJCFParseErrorKind::DuplicateName {
+                namespace,
+                name: name.to_string(),
+            }));
+        }
+        Ok(())
+    }
+
+    pub fn contains(&self, namespace: Namespace, name: &str) -> bool {
+        self.names
+            .get(&namespace)
+            .map_or(false, |names| names.contains(name))
+    }
+}
+
+/// Maps the MJCF names of built elements to their nphysics handles
+#[derive(Clone, Debug, Default)]
+pub struct ModelHandles {
+    pub(crate) colliders: HashMap<String, ColliderHandle>,
+    pub(crate) bodies: HashMap<String, BodyPartHandle>,
+}
+
+impl ModelHandles {
+    /// Handle of the collider built from the geom with the given name
+    pub fn collider(&self, name: &str) -> Option<ColliderHandle> {
+        self.colliders.get(name).cloned()
+    }
+
+    /// Handle of the multibody link built from the body with the
+    /// given name
+    pub fn body(&self, name: &str) -> Option<BodyPartHandle> {
+        self.bodies.get(name).cloned()
+    }
+
+    pub fn colliders(&self) -> impl Iterator<Item = (&str, ColliderHandle)> {
+        self.colliders
+            .iter()
+            .map(|(name, handle)| (name.as_str(), *handle))
+    }
+
+    pub fn bodies(&self) -> impl Iterator<Item = (&str, BodyPartHandle)> {
+        self.bodies
+            .iter()
+            .map(|(name, handle)| (name.as_str(), *handle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Source;
+    use crate::options::ParseOptions;
+    use roxmltree;
+
+    fn registry_result(xml: &str) -> MJCFParseResult<NameRegistry> {
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let registry = NameRegistry::from_mujoco_node(&mut ctx, &doc.root_element());
+        match ctx.report.into_errors().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(registry),
+        }
+    }
+
+    fn assert_duplicate(xml: &str, expected_namespace: Namespace, expected_name: &str) {
+        match registry_result(xml) {
+            Err(error) => match error.kind() {
+                MJCFParseErrorKind::DuplicateName { namespace, name } => {
+                    assert_eq!(namespace, expected_namespace);
+                    assert_eq!(name, expected_name);
+                }
+                _ => panic!("Got unexpected error type {}", error),
+            },
+            Ok(_) => panic!("Duplicate name was not detected"),
+        }
+    }
+
+    #[test]
+    fn duplicate_geom_names() {
+        assert_duplicate(
+            "<mujoco><worldbody><geom name=\"a\"/><geom name=\"a\"/></worldbody></mujoco>",
+            Namespace::Geom,
+            "a",
+        );
+    }
+
+    #[test]
+    fn duplicate_nested_body_names() {
+        assert_duplicate(
+            "<mujoco><worldbody><body name=\"arm\"><body name=\"arm\"/></body></worldbody></mujoco>",
+            Namespace::Body,
+            "arm",
+        );
+    }
+
+    #[test]
+    fn body_named_world_is_duplicate() {
+        assert_duplicate(
+            "<mujoco><worldbody><body name=\"world\"/></worldbody></mujoco>",
+            Namespace::Body,
+            "world",
+        );
+    }
+
+    #[test]
+    fn duplicate_names_across_sections() {
+        assert_duplicate(
+            "<mujoco>
+               <actuator><motor name=\"m\" joint=\"j\"/></actuator>
+               <actuator><position name=\"m\" joint=\"j\"/></actuator>
+             </mujoco>",
+            Namespace::Actuator,
+            "m",
+        );
+    }
+
+    #[test]
+    fn same_name_in_different_namespaces() {
+        let xml = "<mujoco>
+                     <worldbody>
+                       <body name=\"arm\">
+                         <joint name=\"arm\"/>
+                         <geom name=\"arm\" size=\"1\"/>
+                       </body>
+                     </worldbody>
+                     <sensor><jointpos name=\"arm\" joint=\"arm\"/></sensor>
+                   </mujoco>";
+        let registry = registry_result(xml).unwrap();
+
+        assert!(registry.contains(Namespace::Body, "arm"));
+        assert!(registry.contains(Namespace::Joint, "arm"));
+        assert!(registry.contains(Namespace::Geom, "arm"));
+        assert!(registry.contains(Namespace::Sensor, "arm"));
+        assert!(!registry.contains(Namespace::Site, "arm"));
+    }
+
+    #[test]
+    fn default_classes_are_not_registered() {
+        let xml = "<mujoco>
+                     <default><geom name=\"a\"/></default>
+                     <worldbody><geom name=\"a\" size=\"1\"/></worldbody>
+                   </mujoco>";
+        assert!(registry_result(xml).is_ok());
+    }
+}
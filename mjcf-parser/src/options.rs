@@ -0,0 +1,302 @@
+use crate::log;
+use slog::o;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Fewest subdivisions of an approximated curved shape that enclose
+/// a volume
+const MIN_TESSELATION: u32 = 3;
+
+/// How to treat elements, attributes and values that are not valid
+/// MJCF according to the schema.
+///
+/// This is independent of valid MJCF that the parser does not
+/// support yet, which is always reported as a warning.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Strictness {
+    /// Schema violations are errors. As in lenient mode, only the
+    /// offending attributes and elements are ignored, and the rest of
+    /// their parent is still parsed.
+    Strict,
+    /// Schema violations are warnings, and the offending elements
+    /// and attributes are ignored
+    Lenient,
+}
+
+impl Default for Strictness {
+    fn default() -> Self {
+        Strictness::Lenient
+    }
+}
+
+/// How real values in the model are converted to the scalar type
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScalarConversion {
+    /// Accept values that are infinite or NaN once converted, e.g.
+    /// `inf` or `1e40` when parsing to `f32`
+    pub allow_non_finite: bool,
+}
+
+impl Default for ScalarConversion {
+    fn default() -> Self {
+        ScalarConversion {
+            allow_non_finite: true,
+        }
+    }
+}
+
+/// Locates and reads the files a model refers to.
+///
+/// Paths in a model are relative to the directory of the file that
+/// references them.
+pub trait FileResolver: Send + Sync {
+    /// Path of `file`, as referenced from the model at `base`
+    fn resolve(&self, base: Option<&Path>, file: &str) -> PathBuf {
+        match base.and_then(Path::parent) {
+            Some(directory) => directory.join(file),
+            None => PathBuf::from(file),
+        }
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// Read a binary file, such as a mesh
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.read_to_string(path).map(String::into_bytes)
+    }
+}
+
+/// Reads files from the local filesystem
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FilesystemResolver;
+
+impl FileResolver for FilesystemResolver {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+}
+
+/// Settings that control how a model is parsed.
+///
+/// ```
+/// use mjcf_parser::{ParseOptions, Strictness};
+///
+/// let options = ParseOptions::new()
+///     .strictness(Strictness::Strict)
+///     .tesselation(64);
+/// ```
+#[derive(Clone)]
+pub struct ParseOptions {
+    logger: Option<slog::Logger>,
+    strictness: Strictness,
+    file_resolver: Arc<dyn FileResolver>,
+    tesselation: u32,
+    scalar_conversion: ScalarConversion,
+    package_paths: HashMap<String, PathBuf>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            logger: None,
+            strictness: Strictness::default(),
+            file_resolver: Arc::new(FilesystemResolver),
+            tesselation: 32,
+            scalar_conversion: ScalarConversion::default(),
+            package_paths: HashMap::new(),
+        }
+    }
+}
+
+impl fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("has_logger", &self.logger.is_some())
+            .field("strictness", &self.strictness)
+            .field("tesselation", &self.tesselation)
+            .field("scalar_conversion", &self.scalar_conversion)
+            .field("package_paths", &self.package_paths)
+            .finish()
+    }
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shorthand for options that treat schema violations as errors
+    pub fn strict() -> Self {
+        Self::new().strictness(Strictness::Strict)
+    }
+
+    /// Shorthand for options that report schema violations as
+    /// warnings, the default
+    pub fn lenient() -> Self {
+        Self::new().strictness(Strictness::Lenient)
+    }
+
+    /// Log to this logger instead of the global one set with
+    /// `set_root_logger`
+    pub fn logger(mut self, logger: slog::Logger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.strictness = strictness;
+        self
+    }
+
+    /// Use a custom resolver for the model file and the files it
+    /// references
+    pub fn file_resolver<R: FileResolver + 'static>(mut self, file_resolver: R) -> Self {
+        self.file_resolver = Arc::new(file_resolver);
+        self
+    }
+
+    /// Number of subdivisions used when a curved shape has to be
+    /// approximated by a mesh. Values below 3, which give shapes
+    /// without volume, are raised to 3.
+    pub fn tesselation(mut self, tesselation: u32) -> Self {
+        self.tesselation = tesselation.max(MIN_TESSELATION);
+        self
+    }
+
+    pub fn scalar_conversion(mut self, scalar_conversion: ScalarConversion) -> Self {
+        self.scalar_conversion = scalar_conversion;
+        self
+    }
+
+    /// Directory of a ROS package, used for the `package://` URIs of
+    /// URDF meshes. A relative directory is relative to the URDF file.
+    pub fn package_path<P: Into<PathBuf>>(mut self, package: &str, directory: P) -> Self {
+        self.package_paths
+            .insert(package.to_string(), directory.into());
+        self
+    }
+
+    /// Logger to parse with. Falls back to a child of the global
+    /// logger when none was set.
+    pub fn get_logger(&self) -> slog::Logger {
+        match &self.logger {
+            Some(logger) => logger.new(o!()),
+            None => log::LOG.read().unwrap().new(o!()),
+        }
+    }
+
+    pub fn get_strictness(&self) -> Strictness {
+        self.strictness
+    }
+
+    pub fn get_file_resolver(&self) -> &dyn FileResolver {
+        &*self.file_resolver
+    }
+
+    pub fn get_tesselation(&self) -> u32 {
+        self.tesselation
+    }
+
+    pub fn get_scalar_conversion(&self) -> ScalarConversion {
+        self.scalar_conversion
+    }
+
+    pub fn get_package_path(&self, package: &str) -> Option<&Path> {
+        self.package_paths.get(package).map(PathBuf::as_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct MemoryResolver(HashMap<PathBuf, String>);
+
+    impl FileResolver for MemoryResolver {
+        fn read_to_string(&self, path: &Path) -> io::Result<String> {
+            self.0
+                .get(path)
+                .cloned()
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+        }
+    }
+
+    #[test]
+    fn default_options() {
+        let options = ParseOptions::default();
+        assert_eq!(options.get_strictness(), Strictness::Lenient);
+        assert_eq!(options.get_tesselation(), 32);
+        assert!(options.get_scalar_conversion().allow_non_finite);
+        assert_eq!(options.get_package_path("arm_description"), None);
+    }
+
+    #[test]
+    fn builder_sets_options() {
+        let options = ParseOptions::new()
+            .strictness(Strictness::Strict)
+            .tesselation(8)
+            .scalar_conversion(ScalarConversion {
+                allow_non_finite: false,
+            })
+            .package_path("arm_description", "/opt/ros/arm_description");
+        assert_eq!(options.get_strictness(), Strictness::Strict);
+        assert_eq!(options.get_tesselation(), 8);
+        assert!(!options.get_scalar_conversion().allow_non_finite);
+        assert_eq!(
+            options.get_package_path("arm_description"),
+            Some(Path::new("/opt/ros/arm_description"))
+        );
+    }
+
+    #[test]
+    fn shorthands_set_strictness() {
+        assert_eq!(ParseOptions::strict().get_strictness(), Strictness::Strict);
+        assert_eq!(
+            ParseOptions::lenient().get_strictness(),
+            Strictness::Lenient
+        );
+    }
+
+    #[test]
+    fn tesselation_has_a_minimum() {
+        assert_eq!(ParseOptions::new().tesselation(0).get_tesselation(), 3);
+        assert_eq!(ParseOptions::new().tesselation(2).get_tesselation(), 3);
+        assert_eq!(ParseOptions::new().tesselation(3).get_tesselation(), 3);
+    }
+
+    #[test]
+    fn resolve_relative_to_model() {
+        let resolver = FilesystemResolver;
+        assert_eq!(
+            resolver.resolve(Some(Path::new("models/arm.xml")), "meshes/link.stl"),
+            PathBuf::from("models/meshes/link.stl")
+        );
+        assert_eq!(
+            resolver.resolve(None, "meshes/link.stl"),
+            PathBuf::from("meshes/link.stl")
+        );
+    }
+
+    #[test]
+    fn custom_file_resolver() {
+        let mut files = HashMap::new();
+        files.insert(PathBuf::from("arm.xml"), String::from("<mujoco/>"));
+        let options = ParseOptions::new().file_resolver(MemoryResolver(files));
+
+        let resolver = options.get_file_resolver();
+        assert_eq!(
+            resolver.read_to_string(Path::new("arm.xml")).unwrap(),
+            "<mujoco/>"
+        );
+        assert!(resolver.read_to_string(Path::new("leg.xml")).is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use crate::context::ParseContext;
+use crate::error::{MJCFParseError, MJCFParseErrorKind, MJCFParseResult};
+use crate::format::ModelFormat;
+use crate::location::Source;
+use crate::model::Model;
+use crate::names::NameRegistry;
+use crate::options::ParseOptions;
+use crate::report::{ParseReport, WarningCode};
+use crate::schema;
+use crate::tags;
+use na::RealField;
+use nalgebra as na;
+use roxmltree;
+use slog::debug;
+use std::path::Path;
+use std::str::FromStr;
+
+impl<N: RealField> Model<N>
+where
+    N: From<f32>,
+    N: FromStr,
+    <N as FromStr>::Err: std::fmt::Display,
+{
+    /// Parse an MJCF XML string with the default options, failing on
+    /// the first error.
+    pub fn parse_xml_string(text: &str) -> MJCFParseResult<Model<N>> {
+        first_error(Self::parse_xml_string_with_report(
+            text,
+            &ParseOptions::default(),
+        )?)
+    }
+
+    /// Read and parse an MJCF XML file with the default options,
+    /// failing on the first error.
+    pub fn parse_file<P: AsRef<Path>>(path: P) -> MJCFParseResult<Model<N>> {
+        first_error(Self::parse_file_with_report(
+            path,
+            &ParseOptions::default(),
+        )?)
+    }
+
+    /// Parse an MJCF XML string, collecting every warning and
+    /// recoverable error.
+    ///
+    /// Elements that fail to parse are left out of the returned
+    /// model. Schema violations only leave out the offending attribute
+    /// or element, even in strict mode.
+    pub fn parse_xml_string_with_report(
+        text: &str,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(Model<N>, ParseReport)> {
+        parse_source(Source::new(None, text), options)
+    }
+
+    /// Read and parse an MJCF XML file, collecting every warning and
+    /// recoverable error.
+    ///
+    /// The file is read with the options' file resolver.
+    pub fn parse_file_with_report<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(Model<N>, ParseReport)> {
+        let path = path.as_ref();
+        let text = read_model_file(path, options)?;
+        parse_source(Source::new(Some(path), &text), options)
+    }
+
+    /// Read and parse an MJCF, URDF or SDFormat file, telling the
+    /// format from the file's extension, and collecting every warning
+    /// and recoverable error.
+    pub fn load_file_with_report<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(Model<N>, ParseReport)> {
+        let path = path.as_ref();
+        match ModelFormat::from_path(path) {
+            Some(ModelFormat::Mjcf) => Self::parse_file_with_report(path, options),
+            Some(ModelFormat::Urdf) => Self::parse_urdf_file_with_report(path, options),
+            Some(ModelFormat::Sdf) => Self::parse_sdf_file_with_report(path, options),
+            None => Err(MJCFParseError::from(MJCFParseErrorKind::UnknownFormat(
+                path.display().to_string(),
+            ))),
+        }
+    }
+}
+
+/// Read a model file with the options' file resolver
+pub(crate) fn read_model_file(path: &Path, options: &ParseOptions) -> MJCFParseResult<String> {
+    options
+        .get_file_resolver()
+        .read_to_string(path)
+        .map_err(|error| {
+            MJCFParseError::from(MJCFParseErrorKind::ReadFailed {
+                path: path.display().to_string(),
+                reason: error.to_string(),
+            })
+        })
+}
+
+/// Turn a report's first error into the result
+pub(crate) fn first_error<T>((value, report): (T, ParseReport)) -> MJCFParseResult<T> {
+    match report.into_errors().into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(value),
+    }
+}
+
+fn parse_source<N: RealField>(
+    source: Source,
+    options: &ParseOptions,
+) -> MJCFParseResult<(Model<N>, ParseReport)>
+where
+    N: From<f32>,
+    N: FromStr,
+    <N as FromStr>::Err: std::fmt::Display,
+{
+    let mut ctx = ParseContext::new(source, options);
+    let mut model = Model::default();
+
+    debug!(ctx.logger, "Parsing XML string");
+    let doc = match roxmltree::Document::parse(source.text) {
+        Ok(doc) => doc,
+        Err(error) => {
+            return Err(
+                MJCFParseError::from(MJCFParseErrorKind::BadXML(format!("{}", error)))
+                    .with_location(source.location_at(error.pos())),
+            );
+        }
+    };
+
+    let root = doc.root_element();
+
+    if !root.has_tag_name("mujoco") {
+        return Err(
+            MJCFParseError::from(MJCFParseErrorKind::MissingRequiredTag {
+                tag_name: String::from("mujoco"),
+            })
+            .with_location(source.location(&root)),
+        );
+    }
+    schema::validate::<N>(&mut ctx, &root);
+    NameRegistry::from_mujoco_node(&mut ctx, &root);
+
+    model.name = root.attribute("model").map(str::to_string);
+
+    // the compiler settings apply to the whole document, wherever the
+    // tag is
+    for compiler_node in root
+        .children()
+        .filter(|child| child.has_tag_name("compiler"))
+    {
+        match tags::option::parse_compiler_node(&mut ctx, &compiler_node) {
+            Ok(compiler) => model.compiler = compiler,
+            Err(error) => ctx.error(&compiler_node, error),
+        }
+    }
+    ctx.angle = model.compiler.angle;
+
+    for child in root.children() {
+        if !child.is_element() {
+            continue;
+        }
+        match child.tag_name().name() {
+            "compiler" => {}
+            "option" => match tags::option::parse_option_node(&mut ctx, &child) {
+                Ok(option) => model.option = option,
+                Err(error) => ctx.error(&child, error),
+            },
+            "asset" => tags::asset::parse_asset_node(&mut ctx, &child, &mut model.asset),
+            "worldbody" => {
+                // bodies of every worldbody section are merged
+                let worldbody = tags::body::parse_worldbody_node(&mut ctx, &child);
+                model.worldbody.geoms.extend(worldbody.geoms);
+                model.worldbody.sites.extend(worldbody.sites);
+                model.worldbody.bodies.extend(worldbody.bodies);
+                model.worldbody.raw_children.extend(worldbody.raw_children);
+            }
+            "custom" => {
+                for custom_child in child.children().filter(|node| node.is_element()) {
+                    if let Err(error) =
+                        tags::custom::parse_custom_child(&mut ctx, &custom_child, &mut model.custom)
+                    {
+                        ctx.error(&custom_child, error);
+                    }
+                }
+            }
+            // tags missing from the schema were already reported
+            tag if schema::MUJOCO.child(tag).is_none() => {}
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} section is not currently supported", tag),
+                );
+                model.raw_sections.push(tags::raw::parse_raw_node(&child));
+            }
+        }
+    }
+
+    Ok((model, ctx.report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{GeomType, JointType};
+
+    #[test]
+    fn parse_model_tree() {
+        let xml = "<mujoco model=\"arm\">
+                     <compiler angle=\"radian\"/>
+                     <option timestep=\"0.01\"/>
+                     <asset><material name=\"red\" rgba=\"1 0 0 1\"/></asset>
+                     <worldbody>
+                       <geom name=\"floor\" type=\"plane\" size=\"1 1 0.1\" material=\"red\"/>
+                       <body name=\"upper\" pos=\"0 0 1\" euler=\"0 0 1.5707963\">
+                         <joint name=\"shoulder\" axis=\"0 1 0\"/>
+                         <geom class=\"arm\" type=\"capsule\" size=\"0.05\" fromto=\"0 0 0 0 0 -0.5\"/>
+                       </body>
+                     </worldbody>
+                     <actuator><motor joint=\"shoulder\"/></actuator>
+                   </mujoco>";
+
+        let model = Model::<f32>::parse_xml_string(xml).unwrap();
+        assert_eq!(model.name.as_ref().map(String::as_str), Some("arm"));
+        assert_eq!(model.option.timestep, Some(0.01));
+        assert_eq!(
+            model.worldbody.geoms[0]
+                .material
+                .as_ref()
+                .map(String::as_str),
+            Some("red")
+        );
+
+        let bodies = model.bodies();
+        assert_eq!(bodies.len(), 1);
+        let upper = bodies[0];
+        assert_eq!(upper.joints[0].joint_type, JointType::Hinge);
+        // euler angles are in radians
+        let x = upper.quat * na::Vector3::x();
+        assert!((x - na::Vector3::y()).norm() < 1.0e-6);
+        let geom = &upper.geoms[0];
+        assert_eq!(geom.geom_type, GeomType::Capsule);
+        assert_eq!(geom.class.as_ref().map(String::as_str), Some("arm"));
+        assert_eq!(
+            geom.fromto,
+            Some(na::Vector6::new(0.0, 0.0, 0.0, 0.0, 0.0, -0.5))
+        );
+
+        let actuator = model.raw_section("actuator").unwrap();
+        assert_eq!(actuator.children[0].attribute("joint"), Some("shoulder"));
+    }
+
+    #[test]
+    fn strict_schema_violations_leave_the_element_parsed() {
+        let xml = "<mujoco>
+                     <worldbody>
+                       <geom type=\"sphere\" size=\"0.1\" szie=\"1\"/>
+                       <joint/>
+                     </worldbody>
+                   </mujoco>";
+
+        let (model, report) =
+            Model::<f32>::parse_xml_string_with_report(xml, &ParseOptions::strict()).unwrap();
+        let codes: Vec<&str> = report
+            .errors()
+            .iter()
+            .map(|error| error.kind().code())
+            .collect();
+        assert_eq!(codes, vec!["unknown-attribute", "worldbody-invalid-children"]);
+        assert_eq!(model.worldbody.geoms.len(), 1);
+    }
+
+    #[test]
+    fn model_is_serializable() {
+        fn assert_serde<T: serde::Serialize + serde::de::DeserializeOwned>() {}
+        assert_serde::<Model<f32>>();
+        assert_serde::<Model<f64>>();
+    }
+}
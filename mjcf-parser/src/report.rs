@@ -0,0 +1,143 @@
+use crate::error::MJCFParseError;
+use crate::location::SourceLocation;
+use std::fmt;
+
+/// Kinds of warnings emitted while parsing or converting a model.
+///
+/// Most describe valid MJCF that the parser can not fully represent
+/// in nphysics. The rest describe MJCF schema violations that were
+/// skipped because parsing was lenient, and features lost when
+/// converting to another format.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WarningCode {
+    /// Attribute is valid MJCF but has no effect on the built model
+    UnsupportedAttribute,
+    /// Attribute is valid MJCF, but is ignored for this element
+    IgnoredAttribute,
+    /// Tag is valid MJCF but is skipped by the parser
+    IgnoredTag,
+    /// Shape could not be represented exactly and was approximated
+    ApproximatedShape,
+    /// Tag is not a valid child of its parent in the MJCF schema
+    UnknownElement,
+    /// Attribute is not valid for its element in the MJCF schema
+    UnknownAttribute,
+    /// Attribute value has the wrong type or number of values
+    InvalidAttributeValue,
+    /// Feature can not be expressed in the format a model is
+    /// converted to, and was approximated or left out
+    LossyConversion,
+}
+
+impl WarningCode {
+    /// Short, stable identifier of the warning kind
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WarningCode::UnsupportedAttribute => "unsupported-attribute",
+            WarningCode::IgnoredAttribute => "ignored-attribute",
+            WarningCode::IgnoredTag => "ignored-tag",
+            WarningCode::ApproximatedShape => "approximated-shape",
+            WarningCode::UnknownElement => "unknown-element",
+            WarningCode::UnknownAttribute => "unknown-attribute",
+            WarningCode::InvalidAttributeValue => "invalid-attribute-value",
+            WarningCode::LossyConversion => "lossy-conversion",
+        }
+    }
+}
+
+impl fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseWarning {
+    pub code: WarningCode,
+    pub message: String,
+    pub location: Option<SourceLocation>,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "warning[{}]: {}", self.code, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, "\n{}", location.snippet())?;
+        }
+        Ok(())
+    }
+}
+
+/// Every warning and recoverable error found while parsing a model.
+///
+/// Elements that fail to parse are left out of the model, and
+/// parsing continues with the next element. Schema violations only
+/// leave out the offending attribute or element.
+#[derive(Debug, Default)]
+pub struct ParseReport {
+    warnings: Vec<ParseWarning>,
+    errors: Vec<MJCFParseError>,
+}
+
+impl ParseReport {
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    pub fn errors(&self) -> &[MJCFParseError] {
+        &self.errors
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// True when the model parsed without any warnings or errors
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty() && self.errors.is_empty()
+    }
+
+    /// Number of warnings with the given code
+    pub fn warning_count(&self, code: WarningCode) -> usize {
+        self.warnings
+            .iter()
+            .filter(|warning| warning.code == code)
+            .count()
+    }
+
+    pub fn into_errors(self) -> Vec<MJCFParseError> {
+        self.errors
+    }
+
+    pub(crate) fn push_warning(&mut self, warning: ParseWarning) {
+        self.warnings.push(warning);
+    }
+
+    pub(crate) fn push_error(&mut self, error: MJCFParseError) {
+        self.errors.push(error);
+    }
+
+    /// Add the warnings and errors of another report, e.g. one for
+    /// an included file
+    pub(crate) fn append(&mut self, other: ParseReport) {
+        self.warnings.extend(other.warnings);
+        self.errors.extend(other.errors);
+    }
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "error[{}]: {}", error.kind().code(), error)?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "{}", warning)?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.errors.len(),
+            self.warnings.len()
+        )
+    }
+}
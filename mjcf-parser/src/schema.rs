@@ -0,0 +1,1440 @@
+//! Description of the MJCF XML schema.
+//!
+//! Lists the attributes and children that are valid MJCF for each
+//! element, along with the type and number of values each attribute
+//! takes. This is used to catch typos and invalid values, and is
+//! independent of which features the parser currently supports.
+//!
+//! Based on the MuJoCo XML reference:
+//! http://www.mujoco.org/book/XMLreference.html
+
+use crate::context::ParseContext;
+use crate::error::MJCFParseErrorKind;
+use crate::options::Strictness;
+use crate::report::WarningCode;
+use failure::Fail;
+use nalgebra as na;
+use roxmltree;
+use std::fmt;
+use std::str::FromStr;
+
+/// Number of whitespace separated values an attribute takes
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Arity {
+    Exact(usize),
+    Range(usize, usize),
+    Any,
+}
+
+impl Arity {
+    pub fn accepts(self, len: usize) -> bool {
+        match self {
+            Arity::Exact(expected) => len == expected,
+            Arity::Range(min, max) => len >= min && len <= max,
+            Arity::Any => true,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arity::Exact(expected) => write!(f, "{}", expected),
+            Arity::Range(min, max) => write!(f, "{} to {}", min, max),
+            Arity::Any => write!(f, "any number of"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValueType {
+    Real(Arity),
+    Int(Arity),
+    String,
+    Keyword(&'static [&'static str]),
+}
+
+impl ValueType {
+    /// Check if the text is a valid value of this type
+    pub fn accepts(self, text: &str) -> bool {
+        match self {
+            ValueType::Real(arity) => {
+                let values: Vec<&str> = text.split_whitespace().collect();
+                arity.accepts(values.len())
+                    && values.iter().all(|value| value.parse::<f64>().is_ok())
+            }
+            ValueType::Int(arity) => {
+                let values: Vec<&str> = text.split_whitespace().collect();
+                arity.accepts(values.len())
+                    && values.iter().all(|value| value.parse::<i64>().is_ok())
+            }
+            ValueType::String => true,
+            ValueType::Keyword(keywords) => keywords.contains(&text.trim()),
+        }
+    }
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueType::Real(arity) => write!(f, "{} real value(s)", arity),
+            ValueType::Int(arity) => write!(f, "{} integer value(s)", arity),
+            ValueType::String => write!(f, "a string"),
+            ValueType::Keyword(keywords) => write!(f, "one of [{}]", keywords.join(", ")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AttributeSchema {
+    pub name: &'static str,
+    pub value: ValueType,
+}
+
+#[derive(Debug)]
+pub struct ElementSchema {
+    pub tag: &'static str,
+    pub attributes: &'static [AttributeSchema],
+    pub children: &'static [&'static ElementSchema],
+}
+
+impl ElementSchema {
+    pub fn attribute(&self, name: &str) -> Option<&'static AttributeSchema> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    pub fn child(&self, tag: &str) -> Option<&'static ElementSchema> {
+        self.children.iter().find(|child| child.tag == tag).cloned()
+    }
+}
+
+/// Ways a document can differ from the MJCF schema
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum SchemaViolation {
+    #[fail(display = "{} is not a valid child of {}", tag, parent)]
+    UnknownElement { tag: String, parent: String },
+    #[fail(display = "{} is not a valid attribute of {}", attribute, tag)]
+    UnknownAttribute { tag: String, attribute: String },
+    #[fail(
+        display = "Invalid value \"{}\" for attribute {} of {}. Expected {}",
+        value, attribute, tag, expected
+    )]
+    InvalidAttributeValue {
+        tag: String,
+        attribute: String,
+        value: String,
+        expected: String,
+    },
+}
+
+impl SchemaViolation {
+    pub fn warning_code(&self) -> WarningCode {
+        match self {
+            SchemaViolation::UnknownElement { .. } => WarningCode::UnknownElement,
+            SchemaViolation::UnknownAttribute { .. } => WarningCode::UnknownAttribute,
+            SchemaViolation::InvalidAttributeValue { .. } => WarningCode::InvalidAttributeValue,
+        }
+    }
+}
+
+/// Check a `<mujoco>` element and all of its descendants against
+/// the schema.
+///
+/// Depending on the strictness, violations are reported as errors
+/// or as warnings. Real values that are not finite once converted to
+/// `N` are errors unless the scalar conversion allows them.
+pub fn validate<N: na::RealField + FromStr>(ctx: &mut ParseContext, mujoco_node: &roxmltree::Node) {
+    validate_element::<N>(ctx, &MUJOCO, mujoco_node);
+}
+
+fn report(ctx: &mut ParseContext, node: &roxmltree::Node, violation: SchemaViolation) {
+    match ctx.options.get_strictness() {
+        Strictness::Strict => ctx.error(node, MJCFParseErrorKind::SchemaViolation(violation)),
+        Strictness::Lenient => {
+            let code = violation.warning_code();
+            ctx.warn(code, node, violation.to_string());
+        }
+    }
+}
+
+fn validate_element<N: na::RealField + FromStr>(
+    ctx: &mut ParseContext,
+    schema: &'static ElementSchema,
+    node: &roxmltree::Node,
+) {
+    // the worldbody parser reports its attributes and invalid
+    // children itself
+    let is_world = schema.tag == WORLDBODY.tag;
+    let attributes = if is_world {
+        &[][..]
+    } else {
+        node.attributes()
+    };
+    for attribute in attributes {
+        match schema.attribute(attribute.name()) {
+            Some(attribute_schema) => {
+                if !attribute_schema.value.accepts(attribute.value()) {
+                    let violation = SchemaViolation::InvalidAttributeValue {
+                        tag: schema.tag.to_string(),
+                        attribute: attribute.name().to_string(),
+                        value: attribute.value().to_string(),
+                        expected: attribute_schema.value.to_string(),
+                    };
+                    report(ctx, node, violation);
+                } else if !ctx.options.get_scalar_conversion().allow_non_finite
+                    && is_real(attribute_schema.value)
+                    && !all_finite::<N>(attribute.value())
+                {
+                    let error = MJCFParseErrorKind::NonFiniteValue {
+                        tag: schema.tag.to_string(),
+                        attribute: attribute.name().to_string(),
+                        value: attribute.value().to_string(),
+                    };
+                    ctx.error(node, error);
+                }
+            }
+            None => {
+                let violation = SchemaViolation::UnknownAttribute {
+                    tag: schema.tag.to_string(),
+                    attribute: attribute.name().to_string(),
+                };
+                report(ctx, node, violation);
+            }
+        }
+    }
+
+    for child in node.children().filter(|child| child.is_element()) {
+        let tag = child.tag_name().name();
+        match schema.child(tag) {
+            Some(child_schema) => validate_element::<N>(ctx, child_schema, &child),
+            None if is_world && WORLDBODY_INVALID_CHILDREN.contains(&tag) => {}
+            None => {
+                let violation = SchemaViolation::UnknownElement {
+                    tag: tag.to_string(),
+                    parent: schema.tag.to_string(),
+                };
+                report(ctx, &child, violation);
+            }
+        }
+    }
+}
+
+fn is_real(value: ValueType) -> bool {
+    match value {
+        ValueType::Real(_) => true,
+        _ => false,
+    }
+}
+
+fn all_finite<N: na::RealField + FromStr>(text: &str) -> bool {
+    text.split_whitespace()
+        .all(|value| value.parse::<N>().map_or(true, |value| value.is_finite()))
+}
+
+macro_rules! attributes {
+    ($($name:expr => $value:expr),* $(,)*) => {
+        &[$(AttributeSchema { name: $name, value: $value }),*]
+    };
+}
+
+const STRING: ValueType = ValueType::String;
+const BOOL: ValueType = ValueType::Keyword(&["false", "true"]);
+const BOOL_AUTO: ValueType = ValueType::Keyword(&["false", "true", "auto"]);
+const ENABLE: ValueType = ValueType::Keyword(&["disable", "enable"]);
+const INT: ValueType = ValueType::Int(Arity::Exact(1));
+const INT2: ValueType = ValueType::Int(Arity::Exact(2));
+const INTS: ValueType = ValueType::Int(Arity::Any);
+const REAL: ValueType = ValueType::Real(Arity::Exact(1));
+const REAL2: ValueType = ValueType::Real(Arity::Exact(2));
+const REAL3: ValueType = ValueType::Real(Arity::Exact(3));
+const REAL4: ValueType = ValueType::Real(Arity::Exact(4));
+const REAL5: ValueType = ValueType::Real(Arity::Exact(5));
+const REAL6: ValueType = ValueType::Real(Arity::Exact(6));
+const REAL7: ValueType = ValueType::Real(Arity::Exact(7));
+const REALS: ValueType = ValueType::Real(Arity::Any);
+const SIZE: ValueType = ValueType::Real(Arity::Range(1, 3));
+const SOLIMP: ValueType = ValueType::Real(Arity::Range(3, 5));
+const FRICTION: ValueType = ValueType::Real(Arity::Range(1, 3));
+const GEOM_TYPE: ValueType = ValueType::Keyword(&[
+    "plane",
+    "hfield",
+    "sphere",
+    "capsule",
+    "ellipsoid",
+    "cylinder",
+    "box",
+    "mesh",
+]);
+const SITE_TYPE: ValueType =
+    ValueType::Keyword(&["sphere", "capsule", "ellipsoid", "cylinder", "box"]);
+const JOINT_TYPE: ValueType = ValueType::Keyword(&["free", "ball", "slide", "hinge"]);
+const CAMERA_MODE: ValueType =
+    ValueType::Keyword(&["fixed", "track", "trackcom", "targetbody", "targetbodycom"]);
+const OBJECT_TYPE: ValueType = ValueType::Keyword(&[
+    "body", "xbody", "geom", "site", "camera", "joint", "tendon", "actuator",
+]);
+
+pub static INCLUDE: ElementSchema = ElementSchema {
+    tag: "include",
+    attributes: attributes!["file" => STRING],
+    children: &[],
+};
+
+pub static COMPILER: ElementSchema = ElementSchema {
+    tag: "compiler",
+    attributes: attributes![
+        "boundmass" => REAL,
+        "boundinertia" => REAL,
+        "settotalmass" => REAL,
+        "balanceinertia" => BOOL,
+        "strippath" => BOOL,
+        "coordinate" => ValueType::Keyword(&["local", "global"]),
+        "angle" => ValueType::Keyword(&["degree", "radian"]),
+        "fitaabb" => BOOL,
+        "eulerseq" => STRING,
+        "meshdir" => STRING,
+        "texturedir" => STRING,
+        "discardvisual" => BOOL,
+        "convexhull" => BOOL,
+        "userthread" => BOOL,
+        "fusestatic" => BOOL,
+        "inertiafromgeom" => BOOL_AUTO,
+        "inertiagrouprange" => INT2,
+    ],
+    children: &[],
+};
+
+pub static FLAG: ElementSchema = ElementSchema {
+    tag: "flag",
+    attributes: attributes![
+        "constraint" => ENABLE,
+        "equality" => ENABLE,
+        "frictionloss" => ENABLE,
+        "limit" => ENABLE,
+        "contact" => ENABLE,
+        "passive" => ENABLE,
+        "gravity" => ENABLE,
+        "clampctrl" => ENABLE,
+        "warmstart" => ENABLE,
+        "filterparent" => ENABLE,
+        "actuation" => ENABLE,
+        "refsafe" => ENABLE,
+        "override" => ENABLE,
+        "energy" => ENABLE,
+        "fwdinv" => ENABLE,
+        "sensornoise" => ENABLE,
+    ],
+    children: &[],
+};
+
+pub static OPTION: ElementSchema = ElementSchema {
+    tag: "option",
+    attributes: attributes![
+        "timestep" => REAL,
+        "apirate" => REAL,
+        "impratio" => REAL,
+        "tolerance" => REAL,
+        "noslip_tolerance" => REAL,
+        "mpr_tolerance" => REAL,
+        "gravity" => REAL3,
+        "wind" => REAL3,
+        "magnetic" => REAL3,
+        "density" => REAL,
+        "viscosity" => REAL,
+        "o_margin" => REAL,
+        "o_solref" => REAL2,
+        "o_solimp" => SOLIMP,
+        "integrator" => ValueType::Keyword(&["Euler", "RK4"]),
+        "collision" => ValueType::Keyword(&["all", "predefined", "dynamic"]),
+        "cone" => ValueType::Keyword(&["pyramidal", "elliptic"]),
+        "jacobian" => ValueType::Keyword(&["dense", "sparse", "auto"]),
+        "solver" => ValueType::Keyword(&["PGS", "CG", "Newton"]),
+        "iterations" => INT,
+        "noslip_iterations" => INT,
+        "mpr_iterations" => INT,
+    ],
+    children: &[&FLAG],
+};
+
+pub static SIZE_ELEMENT: ElementSchema = ElementSchema {
+    tag: "size",
+    attributes: attributes![
+        "njmax" => INT,
+        "nconmax" => INT,
+        "nstack" => INT,
+        "nuserdata" => INT,
+        "nkey" => INT,
+        "nuser_body" => INT,
+        "nuser_jnt" => INT,
+        "nuser_geom" => INT,
+        "nuser_site" => INT,
+        "nuser_cam" => INT,
+        "nuser_tendon" => INT,
+        "nuser_actuator" => INT,
+        "nuser_sensor" => INT,
+    ],
+    children: &[],
+};
+
+pub static VISUAL_GLOBAL: ElementSchema = ElementSchema {
+    tag: "global",
+    attributes: attributes![
+        "fovy" => REAL,
+        "ipd" => REAL,
+        "linewidth" => REAL,
+        "glow" => REAL,
+        "offwidth" => INT,
+        "offheight" => INT,
+        "realtime" => REAL,
+    ],
+    children: &[],
+};
+
+pub static VISUAL_QUALITY: ElementSchema = ElementSchema {
+    tag: "quality",
+    attributes: attributes![
+        "shadowsize" => INT,
+        "offsamples" => INT,
+        "numslices" => INT,
+        "numstacks" => INT,
+        "numquads" => INT,
+    ],
+    children: &[],
+};
+
+pub static VISUAL_HEADLIGHT: ElementSchema = ElementSchema {
+    tag: "headlight",
+    attributes: attributes![
+        "ambient" => REAL3,
+        "diffuse" => REAL3,
+        "specular" => REAL3,
+        "active" => INT,
+    ],
+    children: &[],
+};
+
+pub static VISUAL_MAP: ElementSchema = ElementSchema {
+    tag: "map",
+    attributes: attributes![
+        "stiffness" => REAL,
+        "stiffnessrot" => REAL,
+        "force" => REAL,
+        "torque" => REAL,
+        "alpha" => REAL,
+        "fogstart" => REAL,
+        "fogend" => REAL,
+        "znear" => REAL,
+        "zfar" => REAL,
+        "haze" => REAL,
+        "shadowclip" => REAL,
+        "shadowscale" => REAL,
+        "actuatortendon" => REAL,
+    ],
+    children: &[],
+};
+
+pub static VISUAL_SCALE: ElementSchema = ElementSchema {
+    tag: "scale",
+    attributes: attributes![
+        "forcewidth" => REAL,
+        "contactwidth" => REAL,
+        "contactheight" => REAL,
+        "connect" => REAL,
+        "com" => REAL,
+        "camera" => REAL,
+        "light" => REAL,
+        "selectpoint" => REAL,
+        "jointlength" => REAL,
+        "jointwidth" => REAL,
+        "actuatorlength" => REAL,
+        "actuatorwidth" => REAL,
+        "framelength" => REAL,
+        "framewidth" => REAL,
+        "constraint" => REAL,
+        "slidercrank" => REAL,
+    ],
+    children: &[],
+};
+
+pub static VISUAL_RGBA: ElementSchema = ElementSchema {
+    tag: "rgba",
+    attributes: attributes![
+        "fog" => REAL4,
+        "haze" => REAL4,
+        "force" => REAL4,
+        "inertia" => REAL4,
+        "joint" => REAL4,
+        "actuator" => REAL4,
+        "actuatornegative" => REAL4,
+        "actuatorpositive" => REAL4,
+        "com" => REAL4,
+        "camera" => REAL4,
+        "light" => REAL4,
+        "selectpoint" => REAL4,
+        "connect" => REAL4,
+        "contactpoint" => REAL4,
+        "contactforce" => REAL4,
+        "contactfriction" => REAL4,
+        "contacttorque" => REAL4,
+        "contactgap" => REAL4,
+        "rangefinder" => REAL4,
+        "constraint" => REAL4,
+        "slidercrank" => REAL4,
+        "crankbroken" => REAL4,
+    ],
+    children: &[],
+};
+
+pub static VISUAL: ElementSchema = ElementSchema {
+    tag: "visual",
+    attributes: &[],
+    children: &[
+        &VISUAL_GLOBAL,
+        &VISUAL_QUALITY,
+        &VISUAL_HEADLIGHT,
+        &VISUAL_MAP,
+        &VISUAL_SCALE,
+        &VISUAL_RGBA,
+    ],
+};
+
+pub static STATISTIC: ElementSchema = ElementSchema {
+    tag: "statistic",
+    attributes: attributes![
+        "meaninertia" => REAL,
+        "meanmass" => REAL,
+        "meansize" => REAL,
+        "extent" => REAL,
+        "center" => REAL3,
+    ],
+    children: &[],
+};
+
+pub static NUMERIC: ElementSchema = ElementSchema {
+    tag: "numeric",
+    attributes: attributes!["name" => STRING, "size" => INT, "data" => REALS],
+    children: &[],
+};
+
+pub static TEXT: ElementSchema = ElementSchema {
+    tag: "text",
+    attributes: attributes!["name" => STRING, "data" => STRING],
+    children: &[],
+};
+
+pub static TUPLE_ELEMENT: ElementSchema = ElementSchema {
+    tag: "element",
+    attributes: attributes!["objtype" => STRING, "objname" => STRING, "prm" => REAL],
+    children: &[],
+};
+
+pub static TUPLE: ElementSchema = ElementSchema {
+    tag: "tuple",
+    attributes: attributes!["name" => STRING],
+    children: &[&TUPLE_ELEMENT],
+};
+
+pub static CUSTOM: ElementSchema = ElementSchema {
+    tag: "custom",
+    attributes: &[],
+    children: &[&NUMERIC, &TEXT, &TUPLE, &INCLUDE],
+};
+
+pub static TEXTURE: ElementSchema = ElementSchema {
+    tag: "texture",
+    attributes: attributes![
+        "name" => STRING,
+        "type" => ValueType::Keyword(&["2d", "cube", "skybox"]),
+        "file" => STRING,
+        "gridsize" => INT2,
+        "gridlayout" => STRING,
+        "fileright" => STRING,
+        "fileleft" => STRING,
+        "fileup" => STRING,
+        "filedown" => STRING,
+        "filefront" => STRING,
+        "fileback" => STRING,
+        "builtin" => ValueType::Keyword(&["none", "gradient", "checker", "flat"]),
+        "rgb1" => REAL3,
+        "rgb2" => REAL3,
+        "mark" => ValueType::Keyword(&["none", "edge", "cross", "random"]),
+        "markrgb" => REAL3,
+        "random" => REAL,
+        "width" => INT,
+        "height" => INT,
+        "hflip" => BOOL,
+        "vflip" => BOOL,
+    ],
+    children: &[],
+};
+
+pub static HFIELD: ElementSchema = ElementSchema {
+    tag: "hfield",
+    attributes: attributes![
+        "name" => STRING,
+        "file" => STRING,
+        "nrow" => INT,
+        "ncol" => INT,
+        "size" => REAL4,
+    ],
+    children: &[],
+};
+
+pub static MESH: ElementSchema = ElementSchema {
+    tag: "mesh",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "file" => STRING,
+        "scale" => REAL3,
+        "smoothnormal" => BOOL,
+        "vertex" => REALS,
+        "normal" => REALS,
+        "texcoord" => REALS,
+        "face" => INTS,
+        "refpos" => REAL3,
+        "refquat" => REAL4,
+    ],
+    children: &[],
+};
+
+pub static SKIN: ElementSchema = ElementSchema {
+    tag: "skin",
+    attributes: attributes![
+        "name" => STRING,
+        "file" => STRING,
+        "material" => STRING,
+        "rgba" => REAL4,
+        "inflate" => REAL,
+        "vertex" => REALS,
+        "texcoord" => REALS,
+        "face" => INTS,
+        "group" => INT,
+    ],
+    children: &[],
+};
+
+pub static MATERIAL: ElementSchema = ElementSchema {
+    tag: "material",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "texture" => STRING,
+        "texrepeat" => REAL2,
+        "texuniform" => BOOL,
+        "emission" => REAL,
+        "specular" => REAL,
+        "shininess" => REAL,
+        "reflectance" => REAL,
+        "rgba" => REAL4,
+    ],
+    children: &[],
+};
+
+pub static ASSET: ElementSchema = ElementSchema {
+    tag: "asset",
+    attributes: &[],
+    children: &[&TEXTURE, &HFIELD, &MESH, &SKIN, &MATERIAL, &INCLUDE],
+};
+
+pub static INERTIAL: ElementSchema = ElementSchema {
+    tag: "inertial",
+    attributes: attributes![
+        "pos" => REAL3,
+        "quat" => REAL4,
+        "axisangle" => REAL4,
+        "xyaxes" => REAL6,
+        "zaxis" => REAL3,
+        "euler" => REAL3,
+        "mass" => REAL,
+        "diaginertia" => REAL3,
+        "fullinertia" => REAL6,
+    ],
+    children: &[],
+};
+
+pub static JOINT: ElementSchema = ElementSchema {
+    tag: "joint",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "type" => JOINT_TYPE,
+        "group" => INT,
+        "pos" => REAL3,
+        "axis" => REAL3,
+        "springdamper" => REAL2,
+        "limited" => BOOL_AUTO,
+        "solreflimit" => REAL2,
+        "solimplimit" => SOLIMP,
+        "solreffriction" => REAL2,
+        "solimpfriction" => SOLIMP,
+        "stiffness" => REAL,
+        "range" => REAL2,
+        "margin" => REAL,
+        "ref" => REAL,
+        "springref" => REAL,
+        "armature" => REAL,
+        "damping" => REAL,
+        "frictionloss" => REAL,
+        "user" => REALS,
+    ],
+    children: &[],
+};
+
+pub static FREEJOINT: ElementSchema = ElementSchema {
+    tag: "freejoint",
+    attributes: attributes!["name" => STRING, "group" => INT],
+    children: &[],
+};
+
+pub static GEOM: ElementSchema = ElementSchema {
+    tag: "geom",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "type" => GEOM_TYPE,
+        "contype" => INT,
+        "conaffinity" => INT,
+        "condim" => INT,
+        "group" => INT,
+        "priority" => INT,
+        "size" => SIZE,
+        "material" => STRING,
+        "rgba" => REAL4,
+        "friction" => FRICTION,
+        "mass" => REAL,
+        "density" => REAL,
+        "solmix" => REAL,
+        "solref" => REAL2,
+        "solimp" => SOLIMP,
+        "margin" => REAL,
+        "gap" => REAL,
+        "fromto" => REAL6,
+        "pos" => REAL3,
+        "quat" => REAL4,
+        "axisangle" => REAL4,
+        "xyaxes" => REAL6,
+        "zaxis" => REAL3,
+        "euler" => REAL3,
+        "hfield" => STRING,
+        "mesh" => STRING,
+        "fitscale" => REAL,
+        "user" => REALS,
+    ],
+    children: &[],
+};
+
+pub static SITE: ElementSchema = ElementSchema {
+    tag: "site",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "type" => SITE_TYPE,
+        "group" => INT,
+        "material" => STRING,
+        "rgba" => REAL4,
+        "size" => SIZE,
+        "fromto" => REAL6,
+        "pos" => REAL3,
+        "quat" => REAL4,
+        "axisangle" => REAL4,
+        "xyaxes" => REAL6,
+        "zaxis" => REAL3,
+        "euler" => REAL3,
+        "user" => REALS,
+    ],
+    children: &[],
+};
+
+pub static CAMERA: ElementSchema = ElementSchema {
+    tag: "camera",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "mode" => CAMERA_MODE,
+        "target" => STRING,
+        "fovy" => REAL,
+        "ipd" => REAL,
+        "pos" => REAL3,
+        "quat" => REAL4,
+        "axisangle" => REAL4,
+        "xyaxes" => REAL6,
+        "zaxis" => REAL3,
+        "euler" => REAL3,
+        "user" => REALS,
+    ],
+    children: &[],
+};
+
+pub static LIGHT: ElementSchema = ElementSchema {
+    tag: "light",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "mode" => CAMERA_MODE,
+        "target" => STRING,
+        "directional" => BOOL,
+        "castshadow" => BOOL,
+        "active" => BOOL,
+        "pos" => REAL3,
+        "dir" => REAL3,
+        "attenuation" => REAL3,
+        "cutoff" => REAL,
+        "exponent" => REAL,
+        "ambient" => REAL3,
+        "diffuse" => REAL3,
+        "specular" => REAL3,
+    ],
+    children: &[],
+};
+
+pub static BODY: ElementSchema = ElementSchema {
+    tag: "body",
+    attributes: attributes![
+        "name" => STRING,
+        "childclass" => STRING,
+        "mocap" => BOOL,
+        "pos" => REAL3,
+        "quat" => REAL4,
+        "axisangle" => REAL4,
+        "xyaxes" => REAL6,
+        "zaxis" => REAL3,
+        "euler" => REAL3,
+        "user" => REALS,
+    ],
+    children: &[
+        &INERTIAL, &JOINT, &FREEJOINT, &GEOM, &SITE, &CAMERA, &LIGHT, &BODY, &INCLUDE,
+    ],
+};
+
+pub static WORLDBODY: ElementSchema = ElementSchema {
+    tag: "worldbody",
+    attributes: &[],
+    children: &[&GEOM, &SITE, &CAMERA, &LIGHT, &BODY, &INCLUDE],
+};
+
+/// Body children the world cannot have. The worldbody parser reports
+/// them, and any attributes of `<worldbody>`, with specific errors.
+pub const WORLDBODY_INVALID_CHILDREN: [&str; 3] = ["inertial", "joint", "freejoint"];
+
+pub static PAIR: ElementSchema = ElementSchema {
+    tag: "pair",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "geom1" => STRING,
+        "geom2" => STRING,
+        "condim" => INT,
+        "friction" => ValueType::Real(Arity::Range(1, 5)),
+        "solref" => REAL2,
+        "solimp" => SOLIMP,
+        "margin" => REAL,
+        "gap" => REAL,
+    ],
+    children: &[],
+};
+
+pub static EXCLUDE: ElementSchema = ElementSchema {
+    tag: "exclude",
+    attributes: attributes!["name" => STRING, "body1" => STRING, "body2" => STRING],
+    children: &[],
+};
+
+pub static CONTACT: ElementSchema = ElementSchema {
+    tag: "contact",
+    attributes: &[],
+    children: &[&PAIR, &EXCLUDE, &INCLUDE],
+};
+
+pub static EQUALITY_CONNECT: ElementSchema = ElementSchema {
+    tag: "connect",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "body1" => STRING,
+        "body2" => STRING,
+        "anchor" => REAL3,
+        "active" => BOOL,
+        "solref" => REAL2,
+        "solimp" => SOLIMP,
+    ],
+    children: &[],
+};
+
+pub static EQUALITY_WELD: ElementSchema = ElementSchema {
+    tag: "weld",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "body1" => STRING,
+        "body2" => STRING,
+        "relpose" => REAL7,
+        "active" => BOOL,
+        "solref" => REAL2,
+        "solimp" => SOLIMP,
+    ],
+    children: &[],
+};
+
+pub static EQUALITY_JOINT: ElementSchema = ElementSchema {
+    tag: "joint",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "joint1" => STRING,
+        "joint2" => STRING,
+        "polycoef" => REAL5,
+        "active" => BOOL,
+        "solref" => REAL2,
+        "solimp" => SOLIMP,
+    ],
+    children: &[],
+};
+
+pub static EQUALITY_TENDON: ElementSchema = ElementSchema {
+    tag: "tendon",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "tendon1" => STRING,
+        "tendon2" => STRING,
+        "polycoef" => REAL5,
+        "active" => BOOL,
+        "solref" => REAL2,
+        "solimp" => SOLIMP,
+    ],
+    children: &[],
+};
+
+pub static EQUALITY_DISTANCE: ElementSchema = ElementSchema {
+    tag: "distance",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "geom1" => STRING,
+        "geom2" => STRING,
+        "distance" => REAL,
+        "active" => BOOL,
+        "solref" => REAL2,
+        "solimp" => SOLIMP,
+    ],
+    children: &[],
+};
+
+pub static EQUALITY: ElementSchema = ElementSchema {
+    tag: "equality",
+    attributes: &[],
+    children: &[
+        &EQUALITY_CONNECT,
+        &EQUALITY_WELD,
+        &EQUALITY_JOINT,
+        &EQUALITY_TENDON,
+        &EQUALITY_DISTANCE,
+        &INCLUDE,
+    ],
+};
+
+pub static SPATIAL_SITE: ElementSchema = ElementSchema {
+    tag: "site",
+    attributes: attributes!["site" => STRING],
+    children: &[],
+};
+
+pub static SPATIAL_GEOM: ElementSchema = ElementSchema {
+    tag: "geom",
+    attributes: attributes!["geom" => STRING, "sidesite" => STRING],
+    children: &[],
+};
+
+pub static SPATIAL_PULLEY: ElementSchema = ElementSchema {
+    tag: "pulley",
+    attributes: attributes!["divisor" => REAL],
+    children: &[],
+};
+
+pub static SPATIAL: ElementSchema = ElementSchema {
+    tag: "spatial",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "group" => INT,
+        "limited" => BOOL_AUTO,
+        "range" => REAL2,
+        "solreflimit" => REAL2,
+        "solimplimit" => SOLIMP,
+        "solreffriction" => REAL2,
+        "solimpfriction" => SOLIMP,
+        "frictionloss" => REAL,
+        "margin" => REAL,
+        "stiffness" => REAL,
+        "damping" => REAL,
+        "springlength" => REAL,
+        "width" => REAL,
+        "material" => STRING,
+        "rgba" => REAL4,
+        "user" => REALS,
+    ],
+    children: &[&SPATIAL_SITE, &SPATIAL_GEOM, &SPATIAL_PULLEY],
+};
+
+pub static FIXED_JOINT: ElementSchema = ElementSchema {
+    tag: "joint",
+    attributes: attributes!["joint" => STRING, "coef" => REAL],
+    children: &[],
+};
+
+pub static FIXED: ElementSchema = ElementSchema {
+    tag: "fixed",
+    attributes: attributes![
+        "name" => STRING,
+        "class" => STRING,
+        "group" => INT,
+        "limited" => BOOL_AUTO,
+        "range" => REAL2,
+        "solreflimit" => REAL2,
+        "solimplimit" => SOLIMP,
+        "solreffriction" => REAL2,
+        "solimpfriction" => SOLIMP,
+        "frictionloss" => REAL,
+        "margin" => REAL,
+        "stiffness" => REAL,
+        "damping" => REAL,
+        "springlength" => REAL,
+        "user" => REALS,
+    ],
+    children: &[&FIXED_JOINT],
+};
+
+pub static TENDON: ElementSchema = ElementSchema {
+    tag: "tendon",
+    attributes: &[],
+    children: &[&SPATIAL, &FIXED, &INCLUDE],
+};
+
+macro_rules! actuator {
+    ($tag:expr $(, $name:expr => $value:expr)*) => {
+        ElementSchema {
+            tag: $tag,
+            attributes: attributes![
+                "name" => STRING,
+                "class" => STRING,
+                "group" => INT,
+                "ctrllimited" => BOOL_AUTO,
+                "forcelimited" => BOOL_AUTO,
+                "ctrlrange" => REAL2,
+                "forcerange" => REAL2,
+                "lengthrange" => REAL2,
+                "gear" => ValueType::Real(Arity::Range(1, 6)),
+                "cranklength" => REAL,
+                "joint" => STRING,
+                "jointinparent" => STRING,
+                "tendon" => STRING,
+                "cranksite" => STRING,
+                "site" => STRING,
+                "slidersite" => STRING,
+                "user" => REALS,
+                $($name => $value,)*
+            ],
+            children: &[],
+        }
+    };
+}
+
+pub static GENERAL: ElementSchema = actuator!(
+    "general",
+    "dyntype" => ValueType::Keyword(&["none", "integrator", "filter", "muscle", "user"]),
+    "gaintype" => ValueType::Keyword(&["fixed", "muscle", "user"]),
+    "biastype" => ValueType::Keyword(&["none", "affine", "muscle", "user"]),
+    "dynprm" => ValueType::Real(Arity::Range(1, 10)),
+    "gainprm" => ValueType::Real(Arity::Range(1, 10)),
+    "biasprm" => ValueType::Real(Arity::Range(1, 10))
+);
+pub static MOTOR: ElementSchema = actuator!("motor");
+pub static POSITION: ElementSchema = actuator!("position", "kp" => REAL);
+pub static VELOCITY: ElementSchema = actuator!("velocity", "kv" => REAL);
+pub static CYLINDER: ElementSchema = actuator!(
+    "cylinder",
+    "timeconst" => REAL,
+    "area" => REAL,
+    "diameter" => REAL,
+    "bias" => REAL3
+);
+pub static MUSCLE: ElementSchema = actuator!(
+    "muscle",
+    "timeconst" => REAL2,
+    "range" => REAL2,
+    "force" => REAL,
+    "scale" => REAL,
+    "lmin" => REAL,
+    "lmax" => REAL,
+    "vmax" => REAL,
+    "fpmax" => REAL,
+    "fvmax" => REAL
+);
+
+pub static ACTUATOR: ElementSchema = ElementSchema {
+    tag: "actuator",
+    attributes: &[],
+    children: &[
+        &GENERAL, &MOTOR, &POSITION, &VELOCITY, &CYLINDER, &MUSCLE, &INCLUDE,
+    ],
+};
+
+macro_rules! sensor {
+    ($tag:expr $(, $name:expr => $value:expr)*) => {
+        ElementSchema {
+            tag: $tag,
+            attributes: attributes![
+                "name" => STRING,
+                "noise" => REAL,
+                "cutoff" => REAL,
+                "user" => REALS,
+                $($name => $value,)*
+            ],
+            children: &[],
+        }
+    };
+}
+
+pub static TOUCH: ElementSchema = sensor!("touch", "site" => STRING);
+pub static ACCELEROMETER: ElementSchema = sensor!("accelerometer", "site" => STRING);
+pub static VELOCIMETER: ElementSchema = sensor!("velocimeter", "site" => STRING);
+pub static GYRO: ElementSchema = sensor!("gyro", "site" => STRING);
+pub static FORCE: ElementSchema = sensor!("force", "site" => STRING);
+pub static TORQUE: ElementSchema = sensor!("torque", "site" => STRING);
+pub static MAGNETOMETER: ElementSchema = sensor!("magnetometer", "site" => STRING);
+pub static RANGEFINDER: ElementSchema = sensor!("rangefinder", "site" => STRING);
+pub static JOINTPOS: ElementSchema = sensor!("jointpos", "joint" => STRING);
+pub static JOINTVEL: ElementSchema = sensor!("jointvel", "joint" => STRING);
+pub static TENDONPOS: ElementSchema = sensor!("tendonpos", "tendon" => STRING);
+pub static TENDONVEL: ElementSchema = sensor!("tendonvel", "tendon" => STRING);
+pub static ACTUATORPOS: ElementSchema = sensor!("actuatorpos", "actuator" => STRING);
+pub static ACTUATORVEL: ElementSchema = sensor!("actuatorvel", "actuator" => STRING);
+pub static ACTUATORFRC: ElementSchema = sensor!("actuatorfrc", "actuator" => STRING);
+pub static BALLQUAT: ElementSchema = sensor!("ballquat", "joint" => STRING);
+pub static BALLANGVEL: ElementSchema = sensor!("ballangvel", "joint" => STRING);
+pub static JOINTLIMITPOS: ElementSchema = sensor!("jointlimitpos", "joint" => STRING);
+pub static JOINTLIMITVEL: ElementSchema = sensor!("jointlimitvel", "joint" => STRING);
+pub static JOINTLIMITFRC: ElementSchema = sensor!("jointlimitfrc", "joint" => STRING);
+pub static TENDONLIMITPOS: ElementSchema = sensor!("tendonlimitpos", "tendon" => STRING);
+pub static TENDONLIMITVEL: ElementSchema = sensor!("tendonlimitvel", "tendon" => STRING);
+pub static TENDONLIMITFRC: ElementSchema = sensor!("tendonlimitfrc", "tendon" => STRING);
+pub static FRAMEPOS: ElementSchema =
+    sensor!("framepos", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMEQUAT: ElementSchema =
+    sensor!("framequat", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMEXAXIS: ElementSchema =
+    sensor!("framexaxis", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMEYAXIS: ElementSchema =
+    sensor!("frameyaxis", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMEZAXIS: ElementSchema =
+    sensor!("framezaxis", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMELINVEL: ElementSchema =
+    sensor!("framelinvel", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMEANGVEL: ElementSchema =
+    sensor!("frameangvel", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMELINACC: ElementSchema =
+    sensor!("framelinacc", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static FRAMEANGACC: ElementSchema =
+    sensor!("frameangacc", "objtype" => OBJECT_TYPE, "objname" => STRING);
+pub static SUBTREECOM: ElementSchema = sensor!("subtreecom", "body" => STRING);
+pub static SUBTREELINVEL: ElementSchema = sensor!("subtreelinvel", "body" => STRING);
+pub static SUBTREEANGMOM: ElementSchema = sensor!("subtreeangmom", "body" => STRING);
+pub static USER_SENSOR: ElementSchema = sensor!(
+    "user",
+    "objtype" => OBJECT_TYPE,
+    "objname" => STRING,
+    "datatype" => ValueType::Keyword(&["real", "positive", "axis", "quat"]),
+    "needstage" => ValueType::Keyword(&["pos", "vel", "acc"]),
+    "dim" => INT
+);
+
+pub static SENSOR: ElementSchema = ElementSchema {
+    tag: "sensor",
+    attributes: &[],
+    children: &[
+        &TOUCH,
+        &ACCELEROMETER,
+        &VELOCIMETER,
+        &GYRO,
+        &FORCE,
+        &TORQUE,
+        &MAGNETOMETER,
+        &RANGEFINDER,
+        &JOINTPOS,
+        &JOINTVEL,
+        &TENDONPOS,
+        &TENDONVEL,
+        &ACTUATORPOS,
+        &ACTUATORVEL,
+        &ACTUATORFRC,
+        &BALLQUAT,
+        &BALLANGVEL,
+        &JOINTLIMITPOS,
+        &JOINTLIMITVEL,
+        &JOINTLIMITFRC,
+        &TENDONLIMITPOS,
+        &TENDONLIMITVEL,
+        &TENDONLIMITFRC,
+        &FRAMEPOS,
+        &FRAMEQUAT,
+        &FRAMEXAXIS,
+        &FRAMEYAXIS,
+        &FRAMEZAXIS,
+        &FRAMELINVEL,
+        &FRAMEANGVEL,
+        &FRAMELINACC,
+        &FRAMEANGACC,
+        &SUBTREECOM,
+        &SUBTREELINVEL,
+        &SUBTREEANGMOM,
+        &USER_SENSOR,
+        &INCLUDE,
+    ],
+};
+
+pub static KEY: ElementSchema = ElementSchema {
+    tag: "key",
+    attributes: attributes![
+        "name" => STRING,
+        "time" => REAL,
+        "qpos" => REALS,
+        "qvel" => REALS,
+        "act" => REALS,
+        "mocap_pos" => REALS,
+        "mocap_quat" => REALS,
+        "ctrl" => REALS,
+    ],
+    children: &[],
+};
+
+pub static KEYFRAME: ElementSchema = ElementSchema {
+    tag: "keyframe",
+    attributes: &[],
+    children: &[&KEY, &INCLUDE],
+};
+
+/// Defaults for the equality constraints. Only the attributes shared
+/// by every constraint type may be set.
+pub static DEFAULT_EQUALITY: ElementSchema = ElementSchema {
+    tag: "equality",
+    attributes: attributes!["active" => BOOL, "solref" => REAL2, "solimp" => SOLIMP],
+    children: &[],
+};
+
+/// Defaults for spatial and fixed tendons
+pub static DEFAULT_TENDON: ElementSchema = ElementSchema {
+    tag: "tendon",
+    attributes: attributes![
+        "group" => INT,
+        "limited" => BOOL_AUTO,
+        "range" => REAL2,
+        "solreflimit" => REAL2,
+        "solimplimit" => SOLIMP,
+        "solreffriction" => REAL2,
+        "solimpfriction" => SOLIMP,
+        "frictionloss" => REAL,
+        "margin" => REAL,
+        "stiffness" => REAL,
+        "damping" => REAL,
+        "springlength" => REAL,
+        "width" => REAL,
+        "material" => STRING,
+        "rgba" => REAL4,
+        "user" => REALS,
+    ],
+    children: &[],
+};
+
+pub static DEFAULT: ElementSchema = ElementSchema {
+    tag: "default",
+    attributes: attributes!["class" => STRING],
+    children: &[
+        &DEFAULT,
+        &MESH,
+        &MATERIAL,
+        &JOINT,
+        &GEOM,
+        &SITE,
+        &CAMERA,
+        &LIGHT,
+        &PAIR,
+        &DEFAULT_EQUALITY,
+        &DEFAULT_TENDON,
+        &GENERAL,
+        &MOTOR,
+        &POSITION,
+        &VELOCITY,
+        &CYLINDER,
+        &MUSCLE,
+        &INCLUDE,
+    ],
+};
+
+pub static MUJOCO: ElementSchema = ElementSchema {
+    tag: "mujoco",
+    attributes: attributes!["model" => STRING],
+    children: &[
+        &INCLUDE,
+        &COMPILER,
+        &OPTION,
+        &SIZE_ELEMENT,
+        &VISUAL,
+        &STATISTIC,
+        &DEFAULT,
+        &CUSTOM,
+        &ASSET,
+        &WORLDBODY,
+        &CONTACT,
+        &EQUALITY,
+        &TENDON,
+        &ACTUATOR,
+        &SENSOR,
+        &KEYFRAME,
+    ],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Source;
+    use crate::options::{ParseOptions, ScalarConversion};
+    use crate::report::ParseReport;
+    use roxmltree;
+
+    fn validate_xml_with_options(xml: &str, options: &ParseOptions) -> ParseReport {
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let mut ctx = ParseContext::new(Source::new(None, xml), options);
+
+        validate::<f32>(&mut ctx, &doc.root_element());
+        ctx.report
+    }
+
+    fn validate_xml(xml: &str, strictness: Strictness) -> ParseReport {
+        validate_xml_with_options(xml, &ParseOptions::new().strictness(strictness))
+    }
+
+    #[test]
+    fn arity_accepts() {
+        assert!(Arity::Exact(3).accepts(3));
+        assert!(!Arity::Exact(3).accepts(2));
+        assert!(Arity::Range(3, 5).accepts(4));
+        assert!(!Arity::Range(3, 5).accepts(6));
+        assert!(Arity::Any.accepts(0));
+    }
+
+    #[test]
+    fn value_type_accepts() {
+        assert!(REAL3.accepts("1 2.5 -3e2"));
+        assert!(!REAL3.accepts("1 2"));
+        assert!(!REAL3.accepts("1 two 3"));
+        assert!(INT.accepts("4"));
+        assert!(!INT.accepts("4.5"));
+        assert!(GEOM_TYPE.accepts("capsule"));
+        assert!(!GEOM_TYPE.accepts("capsul"));
+        assert!(STRING.accepts(""));
+    }
+
+    #[test]
+    fn valid_model_has_no_violations() {
+        let xml = "<mujoco model=\"arm\">
+                     <option timestep=\"0.002\" gravity=\"0 0 -9.81\"/>
+                     <default><geom rgba=\"1 0 0 1\"/></default>
+                     <worldbody>
+                       <geom type=\"plane\" size=\"1 1 0.1\"/>
+                       <body name=\"arm\" pos=\"0 0 1\">
+                         <joint type=\"hinge\" axis=\"0 1 0\"/>
+                         <geom type=\"capsule\" fromto=\"0 0 0 1 0 0\" size=\"0.1\"/>
+                       </body>
+                     </worldbody>
+                     <actuator><motor joint=\"j\" gear=\"10\"/></actuator>
+                   </mujoco>";
+
+        let report = validate_xml(xml, Strictness::Strict);
+        assert!(report.is_clean(), "{}", report);
+    }
+
+    #[test]
+    fn misspelled_attribute_warns_when_lenient() {
+        let xml = "<mujoco><worldbody><geom szie=\"1\"/></worldbody></mujoco>";
+
+        let report = validate_xml(xml, Strictness::Lenient);
+        assert!(!report.has_errors());
+        assert_eq!(report.warning_count(WarningCode::UnknownAttribute), 1);
+    }
+
+    #[test]
+    fn misspelled_attribute_errors_when_strict() {
+        let xml = "<mujoco><worldbody><geom szie=\"1\"/></worldbody></mujoco>";
+
+        let report = validate_xml(xml, Strictness::Strict);
+        assert_eq!(report.errors().len(), 1);
+        match report.errors()[0].kind() {
+            MJCFParseErrorKind::SchemaViolation(SchemaViolation::UnknownAttribute {
+                tag,
+                attribute,
+            }) => {
+                assert_eq!(tag, "geom");
+                assert_eq!(attribute, "szie");
+            }
+            kind => panic!("Got unexpected error type {}", kind),
+        }
+    }
+
+    #[test]
+    fn unknown_element_is_reported() {
+        let xml = "<mujoco><worldbody><body><geoms/></body></worldbody></mujoco>";
+
+        let report = validate_xml(xml, Strictness::Lenient);
+        assert_eq!(report.warning_count(WarningCode::UnknownElement), 1);
+    }
+
+    #[test]
+    fn invalid_attribute_value_is_reported() {
+        let xml = "<mujoco><worldbody><geom type=\"sphere\" pos=\"0 0\"/></worldbody></mujoco>";
+
+        let report = validate_xml(xml, Strictness::Lenient);
+        assert_eq!(report.warning_count(WarningCode::InvalidAttributeValue), 1);
+    }
+
+    #[test]
+    fn context_dependent_elements() {
+        // joint means different things inside bodies, equality
+        // constraints and fixed tendons
+        let xml = "<mujoco>
+                     <worldbody><body><joint axis=\"1 0 0\"/></body></worldbody>
+                     <equality><joint joint1=\"a\" joint2=\"b\"/></equality>
+                     <tendon><fixed><joint joint=\"a\" coef=\"1\"/></fixed></tendon>
+                   </mujoco>";
+
+        let report = validate_xml(xml, Strictness::Strict);
+        assert!(report.is_clean(), "{}", report);
+    }
+
+    #[test]
+    fn worldbody_violations_are_left_to_the_parser() {
+        let xml = "<mujoco>
+                     <worldbody name=\"world\"><joint/><inertial mass=\"1\"/></worldbody>
+                   </mujoco>";
+
+        let report = validate_xml(xml, Strictness::Strict);
+        assert!(report.is_clean(), "{}", report);
+    }
+
+    #[test]
+    fn non_finite_values() {
+        let xml = "<mujoco><worldbody><geom size=\"1e40\"/></worldbody></mujoco>";
+
+        let report = validate_xml(xml, Strictness::Strict);
+        assert!(report.is_clean(), "{}", report);
+
+        let options = ParseOptions::new().scalar_conversion(ScalarConversion {
+            allow_non_finite: false,
+        });
+        let report = validate_xml_with_options(xml, &options);
+        assert_eq!(report.errors().len(), 1);
+        assert_eq!(report.errors()[0].kind().code(), "non-finite-value");
+    }
+}
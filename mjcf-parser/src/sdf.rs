@@ -0,0 +1,1174 @@
+//! Import of SDFormat models and worlds into the typed model.
+//!
+//! Every link becomes a body, named by its scoped name, e.g.
+//! `robot::arm::upper` for a link of a nested model. Links are placed
+//! as Gazebo places them:
+//!
+//! - a link that is the child of a joint is attached to the joint's
+//!   parent link, or to the world body for joints to `world`.
+//! - links of static models are welded to the world. Static links
+//!   without children, e.g. ground planes, become world geoms.
+//! - other links float freely, so they get a free joint.
+//!
+//! `revolute` and `continuous` joints become hinges, `prismatic`
+//! joints slides and `ball` joints balls. `fixed` joints weld their
+//! child, and so do the joint types without an equivalent.
+//!
+//! `model://` URIs, of includes and meshes, are relative to the
+//! directory of the top level file. Other relative URIs are relative
+//! to the file they are written in. Included files are read with the
+//! options' `FileResolver`.
+
+use crate::attributes;
+use crate::context::ParseContext;
+use crate::error::{MJCFParseError, MJCFParseErrorKind, MJCFParseResult};
+use crate::location::{Source, SourceLocation};
+use crate::model::{Angle, Body, Geom, GeomType, Inertial, Joint, JointType, Model};
+use crate::options::ParseOptions;
+use crate::parse::{first_error, read_model_file};
+use crate::report::{ParseReport, WarningCode};
+use failure::Fail;
+use na::RealField;
+use nalgebra as na;
+use roxmltree;
+use slog::debug;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum SdfError {
+    #[fail(display = "Required tag <{}> missing in {} tag", tag, parent)]
+    RequiredTagMissing { parent: String, tag: String },
+    #[fail(
+        display = "Required attribute \"{}\" missing on {} tag",
+        attribute, tag
+    )]
+    RequiredAttributeMissing { tag: String, attribute: String },
+    #[fail(display = "Bad values. {}", 0)]
+    BadRealValue(#[fail(cause)] attributes::ParseRealAttributeError),
+    #[fail(display = "Invalid joint type {}", 0)]
+    InvalidJointType(String),
+    #[fail(display = "{} tag needs a geometry", 0)]
+    MissingGeometry(String),
+    #[fail(display = "Invalid geometry {}", 0)]
+    InvalidGeometry(String),
+    #[fail(display = "Duplicate link name \"{}\"", 0)]
+    DuplicateLink(String),
+    #[fail(display = "Joint {} refers to unknown link \"{}\"", joint, link)]
+    UnknownLink { joint: String, link: String },
+    #[fail(display = "Link \"{}\" is the child of more than one joint", 0)]
+    MultipleParents(String),
+    #[fail(display = "Link \"{}\" is part of a kinematic loop", 0)]
+    KinematicLoop(String),
+    #[fail(display = "Failed to include {}. {}", uri, reason)]
+    IncludeFailed { uri: String, reason: String },
+}
+
+impl From<attributes::ParseRealAttributeError> for SdfError {
+    fn from(error: attributes::ParseRealAttributeError) -> SdfError {
+        SdfError::BadRealValue(error)
+    }
+}
+
+/// Name joints use for the world
+const WORLD_LINK_NAME: &str = "world";
+
+/// Scope separator of nested model and link names
+const SCOPE_SEPARATOR: &str = "::";
+
+/// Includes nested deeper than this are assumed to be recursive
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+impl<N: RealField> Model<N>
+where
+    N: From<f32>,
+    N: FromStr,
+    <N as FromStr>::Err: std::fmt::Display,
+{
+    /// Parse an SDFormat string with the default options, failing on
+    /// the first error.
+    pub fn parse_sdf_string(text: &str) -> MJCFParseResult<Model<N>> {
+        first_error(Self::parse_sdf_string_with_report(
+            text,
+            &ParseOptions::default(),
+        )?)
+    }
+
+    /// Parse an SDFormat string, collecting every warning and
+    /// recoverable error.
+    ///
+    /// Included files are relative to the working directory.
+    pub fn parse_sdf_string_with_report(
+        text: &str,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(Model<N>, ParseReport)> {
+        parse_sdf_source(Source::new(None, text), options)
+    }
+
+    /// Read and parse an SDFormat model or world file, collecting
+    /// every warning and recoverable error.
+    ///
+    /// The file, and the files it includes, are read with the
+    /// options' file resolver.
+    pub fn parse_sdf_file_with_report<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(Model<N>, ParseReport)> {
+        let path = path.as_ref();
+        let text = read_model_file(path, options)?;
+        parse_sdf_source(Source::new(Some(path), &text), options)
+    }
+}
+
+/// A link, posed in the world
+struct SdfLink<N: RealField> {
+    /// Body without its pose, named by the link's scoped name
+    body: Body<N>,
+    pose: na::Isometry3<N>,
+    is_static: bool,
+    location: SourceLocation,
+}
+
+/// A joint between two links, named by their scoped names
+struct SdfJoint<N: RealField> {
+    name: String,
+    parent: String,
+    child: String,
+    /// Pose of the joint in the child link's frame
+    pose: na::Isometry3<N>,
+    /// `None` for joints that weld the child to its parent
+    joint: Option<Joint<N>>,
+    location: SourceLocation,
+}
+
+/// Everything parsed from the top level file and its includes
+struct Scene<'o, N: RealField> {
+    model: Model<N>,
+    links: Vec<SdfLink<N>>,
+    joints: Vec<SdfJoint<N>>,
+    /// The top level file, which `model://` URIs are relative to
+    top_file: Option<&'o Path>,
+}
+
+/// The model an element is in
+#[derive(Clone)]
+struct Frame<N: RealField> {
+    /// Scoped name of the model, empty outside of models
+    scope: String,
+    /// Pose of the model in the world
+    pose: na::Isometry3<N>,
+    is_static: bool,
+    /// Directory of the file being parsed, relative to the top level
+    /// file's directory
+    directory: String,
+    include_depth: usize,
+}
+
+fn parse_sdf_source<N: RealField>(
+    source: Source,
+    options: &ParseOptions,
+) -> MJCFParseResult<(Model<N>, ParseReport)>
+where
+    N: FromStr,
+{
+    let mut ctx = ParseContext::new(source, options);
+    let mut scene = Scene {
+        model: Model::default(),
+        links: vec![],
+        joints: vec![],
+        top_file: source.file,
+    };
+    // SDFormat angles are always in radians
+    scene.model.compiler.angle = Angle::Radian;
+    ctx.angle = Angle::Radian;
+
+    debug!(ctx.logger, "Parsing SDFormat string");
+    let doc = parse_document(&source)?;
+    let root = doc.root_element();
+    if !root.has_tag_name("sdf") {
+        return Err(
+            MJCFParseError::from(MJCFParseErrorKind::MissingRequiredTag {
+                tag_name: String::from("sdf"),
+            })
+            .with_location(source.location(&root)),
+        );
+    }
+
+    let frame = Frame {
+        scope: String::new(),
+        pose: na::Isometry3::identity(),
+        is_static: false,
+        directory: String::new(),
+        include_depth: 0,
+    };
+    for child in root.children().filter(|node| node.is_element()) {
+        let parsed = match child.tag_name().name() {
+            "world" => parse_world_node(&mut ctx, &child, &mut scene, &frame),
+            "model" => {
+                parse_model_node(&mut ctx, &child, &mut scene, &frame, &Overrides::default())
+            }
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} tag is not currently supported", tag),
+                );
+                Ok(())
+            }
+        };
+        if let Err(error) = parsed {
+            ctx.error(&child, error);
+        }
+    }
+
+    if scene.model.name.is_none() {
+        scene.model.name = root
+            .children()
+            .find(|node| node.has_tag_name("model"))
+            .and_then(|model_node| model_node.attribute("name"))
+            .map(str::to_string);
+    }
+    assemble(&mut ctx, &mut scene);
+
+    Ok((scene.model, ctx.report))
+}
+
+fn parse_document<'t>(source: &Source<'t>) -> MJCFParseResult<roxmltree::Document<'t>> {
+    roxmltree::Document::parse(source.text).map_err(|error| {
+        MJCFParseError::from(MJCFParseErrorKind::BadXML(format!("{}", error)))
+            .with_location(source.location_at(error.pos()))
+    })
+}
+
+fn child_node<'a, 'input>(
+    node: &roxmltree::Node<'a, 'input>,
+    tag: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(tag))
+}
+
+/// Text of a child element, e.g. `<mass>1</mass>`
+fn child_text<'a>(node: &roxmltree::Node<'a, '_>, tag: &str) -> Option<&'a str> {
+    child_node(node, tag).map(|child| child.text().unwrap_or_default())
+}
+
+fn required_child_text<'a>(node: &roxmltree::Node<'a, '_>, tag: &str) -> Result<&'a str, SdfError> {
+    child_text(node, tag).ok_or_else(|| SdfError::RequiredTagMissing {
+        parent: node.tag_name().name().to_string(),
+        tag: tag.to_string(),
+    })
+}
+
+fn child_real<N: RealField>(node: &roxmltree::Node, tag: &str) -> Result<Option<N>, SdfError>
+where
+    N: FromStr,
+{
+    match child_text(node, tag) {
+        Some(text) => Ok(Some(attributes::parse_real_attribute(text)?)),
+        None => Ok(None),
+    }
+}
+
+fn child_vector<N: RealField, D: na::DimName>(
+    node: &roxmltree::Node,
+    tag: &str,
+) -> Result<Option<na::VectorN<N, D>>, SdfError>
+where
+    na::DefaultAllocator: na::allocator::Allocator<N, D>,
+    N: FromStr,
+{
+    match child_text(node, tag) {
+        Some(text) => Ok(Some(attributes::parse_real_vector_attribute(text)?)),
+        None => Ok(None),
+    }
+}
+
+fn required_attribute<'a>(
+    node: &roxmltree::Node<'a, '_>,
+    attribute: &str,
+) -> Result<&'a str, SdfError> {
+    node.attribute(attribute)
+        .ok_or_else(|| SdfError::RequiredAttributeMissing {
+            tag: node.tag_name().name().to_string(),
+            attribute: attribute.to_string(),
+        })
+}
+
+fn is_true(text: &str) -> bool {
+    match text.trim() {
+        "true" | "1" => true,
+        _ => false,
+    }
+}
+
+/// Pose given by an element's `<pose>` child, as `x y z roll pitch
+/// yaw`
+fn parse_pose<N: RealField>(node: &roxmltree::Node) -> Result<Option<na::Isometry3<N>>, SdfError>
+where
+    N: FromStr,
+{
+    let pose: Option<na::Vector6<N>> = child_vector(node, "pose")?;
+    Ok(pose.map(|pose| {
+        na::Isometry3::from_parts(
+            na::Translation3::new(pose[0], pose[1], pose[2]),
+            na::UnitQuaternion::from_euler_angles(pose[3], pose[4], pose[5]),
+        )
+    }))
+}
+
+/// Join a scope and a name relative to it
+fn scoped(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}{}{}", scope, SCOPE_SEPARATOR, name)
+    }
+}
+
+/// Path of a URI relative to the top level file's directory
+fn resolve_uri(frame_directory: &str, uri: &str) -> String {
+    if uri.starts_with("model://") {
+        uri.trim_start_matches("model://").to_string()
+    } else if uri.starts_with("file://") {
+        uri.trim_start_matches("file://").to_string()
+    } else {
+        Path::new(frame_directory)
+            .join(uri)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+fn parse_world_node<N: RealField>(
+    ctx: &mut ParseContext,
+    world_node: &roxmltree::Node,
+    scene: &mut Scene<N>,
+    frame: &Frame<N>,
+) -> Result<(), SdfError>
+where
+    N: FromStr,
+{
+    scene.model.name = world_node.attribute("name").map(str::to_string);
+
+    for child in world_node.children().filter(|node| node.is_element()) {
+        let parsed = match child.tag_name().name() {
+            "gravity" => child_vector(world_node, "gravity").map(|gravity| {
+                scene.model.option.gravity = gravity;
+            }),
+            "physics" => parse_physics_node(ctx, &child, &mut scene.model),
+            "model" => parse_model_node(ctx, &child, scene, frame, &Overrides::default()),
+            "include" => parse_include_node(ctx, &child, scene, frame),
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} tag is not currently supported", tag),
+                );
+                Ok(())
+            }
+        };
+        if let Err(error) = parsed {
+            ctx.error(&child, error);
+        }
+    }
+    Ok(())
+}
+
+fn parse_physics_node<N: RealField>(
+    ctx: &mut ParseContext,
+    physics_node: &roxmltree::Node,
+    model: &mut Model<N>,
+) -> Result<(), SdfError>
+where
+    N: FromStr,
+{
+    for child in physics_node.children().filter(|node| node.is_element()) {
+        match child.tag_name().name() {
+            "max_step_size" => model.option.timestep = child_real(physics_node, "max_step_size")?,
+            // older versions keep the gravity with the physics
+            "gravity" => model.option.gravity = child_vector(physics_node, "gravity")?,
+            tag => ctx.warn(
+                WarningCode::IgnoredTag,
+                &child,
+                format!("physics {} tag is not currently supported", tag),
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Values an `<include>` replaces in the included model
+struct Overrides<'a, N: RealField> {
+    name: Option<&'a str>,
+    pose: Option<na::Isometry3<N>>,
+    is_static: Option<bool>,
+}
+
+impl<'a, N: RealField> Default for Overrides<'a, N> {
+    fn default() -> Self {
+        Overrides {
+            name: None,
+            pose: None,
+            is_static: None,
+        }
+    }
+}
+
+fn parse_include_node<N: RealField>(
+    ctx: &mut ParseContext,
+    include_node: &roxmltree::Node,
+    scene: &mut Scene<N>,
+    frame: &Frame<N>,
+) -> Result<(), SdfError>
+where
+    N: FromStr,
+{
+    let uri = required_child_text(include_node, "uri")?.trim();
+    let include_failed = |reason: String| SdfError::IncludeFailed {
+        uri: uri.to_string(),
+        reason,
+    };
+    if frame.include_depth >= MAX_INCLUDE_DEPTH {
+        return Err(include_failed(String::from(
+            "includes are nested too deeply",
+        )));
+    }
+
+    // model directories hold their description in model.sdf
+    let mut file = resolve_uri(&frame.directory, uri);
+    if !file.ends_with(".sdf") {
+        file = Path::new(&file)
+            .join("model.sdf")
+            .to_string_lossy()
+            .into_owned();
+    }
+    let resolver = ctx.options.get_file_resolver();
+    let path = resolver.resolve(scene.top_file, &file);
+    let text = resolver
+        .read_to_string(&path)
+        .map_err(|error| include_failed(error.to_string()))?;
+
+    let source = Source::new(Some(&path), &text);
+    let doc = parse_document(&source).map_err(|error| include_failed(error.to_string()))?;
+    let model_node = doc
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name("model"))
+        .ok_or_else(|| include_failed(String::from("file has no model")))?;
+
+    let overrides = Overrides {
+        name: child_text(include_node, "name"),
+        pose: parse_pose(include_node)?,
+        is_static: child_text(include_node, "static").map(is_true),
+    };
+    let included_frame = Frame {
+        directory: Path::new(&file)
+            .parent()
+            .map(|directory| directory.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        include_depth: frame.include_depth + 1,
+        ..frame.clone()
+    };
+
+    let mut included_ctx = ParseContext::new(source, ctx.options);
+    included_ctx.angle = Angle::Radian;
+    if let Err(error) = parse_model_node(
+        &mut included_ctx,
+        &model_node,
+        scene,
+        &included_frame,
+        &overrides,
+    ) {
+        included_ctx.error(&model_node, error);
+    }
+    ctx.report.append(included_ctx.report);
+    Ok(())
+}
+
+fn parse_model_node<N: RealField>(
+    ctx: &mut ParseContext,
+    model_node: &roxmltree::Node,
+    scene: &mut Scene<N>,
+    parent: &Frame<N>,
+    overrides: &Overrides<N>,
+) -> Result<(), SdfError>
+where
+    N: FromStr,
+{
+    let name = match overrides.name {
+        Some(name) => name,
+        None => required_attribute(model_node, "name")?,
+    };
+    let pose = match overrides.pose {
+        Some(pose) => pose,
+        None => parse_pose(model_node)?.unwrap_or_else(na::Isometry3::identity),
+    };
+    let is_static = match overrides.is_static {
+        Some(is_static) => is_static,
+        None => child_text(model_node, "static").map_or(false, is_true),
+    };
+    let frame = Frame {
+        scope: scoped(&parent.scope, name),
+        pose: parent.pose * pose,
+        is_static: parent.is_static || is_static,
+        ..parent.clone()
+    };
+
+    for child in model_node.children().filter(|node| node.is_element()) {
+        let parsed = match child.tag_name().name() {
+            "pose" | "static" => Ok(()),
+            "link" => {
+                parse_link_node(ctx, &child, scene, &frame).map(|link| scene.links.push(link))
+            }
+            "joint" => parse_joint_node(ctx, &child, &frame).map(|joint| scene.joints.push(joint)),
+            "model" => parse_model_node(ctx, &child, scene, &frame, &Overrides::default()),
+            "include" => parse_include_node(ctx, &child, scene, &frame),
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} tag is not currently supported", tag),
+                );
+                Ok(())
+            }
+        };
+        if let Err(error) = parsed {
+            ctx.error(&child, error);
+        }
+    }
+    Ok(())
+}
+
+/// Parse a `<link>`. Errors in its visual and collision elements are
+/// recorded, and the elements left out.
+fn parse_link_node<N: RealField>(
+    ctx: &mut ParseContext,
+    link_node: &roxmltree::Node,
+    scene: &mut Scene<N>,
+    frame: &Frame<N>,
+) -> Result<SdfLink<N>, SdfError>
+where
+    N: FromStr,
+{
+    let name = required_attribute(link_node, "name")?;
+    let mut body = Body::new(scoped(&frame.scope, name));
+    let pose = parse_pose(link_node)?.unwrap_or_else(na::Isometry3::identity);
+
+    for child in link_node.children().filter(|node| node.is_element()) {
+        let parsed = match child.tag_name().name() {
+            "pose" => Ok(()),
+            "inertial" => {
+                parse_inertial_node(&child).map(|inertial| body.inertial = Some(inertial))
+            }
+            "collision" => {
+                parse_geom_node(ctx, &child, scene, frame).map(|geom| body.geoms.push(geom))
+            }
+            "visual" => parse_geom_node(ctx, &child, scene, frame).map(|mut geom| {
+                geom.contype = Some(0);
+                geom.conaffinity = Some(0);
+                geom.group = Some(1);
+                body.geoms.push(geom)
+            }),
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} tag is not currently supported", tag),
+                );
+                Ok(())
+            }
+        };
+        if let Err(error) = parsed {
+            ctx.error(&child, error);
+        }
+    }
+
+    Ok(SdfLink {
+        body,
+        pose: frame.pose * pose,
+        is_static: frame.is_static,
+        location: ctx.source.location(link_node),
+    })
+}
+
+fn parse_inertial_node<N: RealField>(
+    inertial_node: &roxmltree::Node,
+) -> Result<Inertial<N>, SdfError>
+where
+    N: FromStr,
+{
+    let pose = parse_pose(inertial_node)?.unwrap_or_else(na::Isometry3::identity);
+    let mass = child_real(inertial_node, "mass")?.unwrap_or_else(N::one);
+
+    let mut fullinertia = na::Vector6::zeros();
+    if let Some(inertia_node) = child_node(inertial_node, "inertia") {
+        for (index, tag) in ["ixx", "iyy", "izz", "ixy", "ixz", "iyz"]
+            .iter()
+            .enumerate()
+        {
+            if let Some(value) = child_real(&inertia_node, tag)? {
+                fullinertia[index] = value;
+            }
+        }
+    }
+
+    Ok(Inertial {
+        mass,
+        pos: pose.translation.vector,
+        quat: pose.rotation,
+        diaginertia: None,
+        fullinertia: Some(fullinertia),
+    })
+}
+
+/// Parse a `<collision>` or `<visual>` element, adding the meshes it
+/// refers to to the model's assets
+fn parse_geom_node<N: RealField>(
+    ctx: &mut ParseContext,
+    node: &roxmltree::Node,
+    scene: &mut Scene<N>,
+    frame: &Frame<N>,
+) -> Result<Geom<N>, SdfError>
+where
+    N: FromStr,
+{
+    let pose = parse_pose(node)?.unwrap_or_else(na::Isometry3::identity);
+    let shape_node = child_node(node, "geometry")
+        .and_then(|geometry_node| geometry_node.children().find(|child| child.is_element()))
+        .ok_or_else(|| SdfError::MissingGeometry(node.tag_name().name().to_string()))?;
+
+    let real = |tag: &str| -> Result<N, SdfError> {
+        Ok(attributes::parse_real_attribute(required_child_text(
+            &shape_node,
+            tag,
+        )?)?)
+    };
+    let two: N = na::convert(2.0);
+    let mut rotation = na::UnitQuaternion::identity();
+    let mut geom = match shape_node.tag_name().name() {
+        "box" => {
+            let size: na::Vector3<N> =
+                attributes::parse_real_vector_attribute(required_child_text(&shape_node, "size")?)?;
+            Geom::new(GeomType::Box, (size / two).iter().cloned().collect())
+        }
+        "sphere" => Geom::new(GeomType::Sphere, vec![real("radius")?]),
+        "capsule" => Geom::new(
+            GeomType::Capsule,
+            vec![real("radius")?, real("length")? / two],
+        ),
+        "cylinder" => {
+            ctx.warn(
+                WarningCode::ApproximatedShape,
+                &shape_node,
+                format!(
+                    "cylinder is approximated by a convex hull with {} subdivisions",
+                    ctx.options.get_tesselation()
+                ),
+            );
+            Geom::new(
+                GeomType::Cylinder,
+                vec![real("radius")?, real("length")? / two],
+            )
+        }
+        "plane" => {
+            let normal = child_vector(&shape_node, "normal")?.unwrap_or_else(na::Vector3::z);
+            rotation = na::UnitQuaternion::rotation_between(&na::Vector3::z(), &normal)
+                .unwrap_or_else(|| {
+                    na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), N::pi())
+                });
+            Geom::new(GeomType::Plane, vec![])
+        }
+        "mesh" => {
+            ctx.warn(
+                WarningCode::ApproximatedShape,
+                &shape_node,
+                "mesh is approximated by its convex hull",
+            );
+            let file = resolve_uri(
+                &frame.directory,
+                required_child_text(&shape_node, "uri")?.trim(),
+            );
+            let scale = child_vector(&shape_node, "scale")?;
+            let mut geom = Geom::new(GeomType::Mesh, vec![]);
+            geom.mesh = Some(scene.model.asset.add_mesh_file(&file, scale));
+            geom
+        }
+        shape => return Err(SdfError::InvalidGeometry(shape.to_string())),
+    };
+    geom.pos = pose.translation.vector;
+    geom.quat = pose.rotation * rotation;
+
+    if let Some(friction_node) =
+        child_node(node, "surface").and_then(|surface| child_node(&surface, "friction"))
+    {
+        let ode_node = child_node(&friction_node, "ode");
+        let sliding = match &ode_node {
+            Some(ode_node) => child_real(ode_node, "mu")?,
+            None => None,
+        };
+        let torsional = match child_node(&friction_node, "torsional") {
+            Some(torsional_node) => child_real(&torsional_node, "coefficient")?,
+            None => None,
+        };
+        if sliding.is_some() || torsional.is_some() {
+            // MuJoCo's defaults for the values SDFormat leaves out
+            geom.friction = Some(na::Vector3::new(
+                sliding.unwrap_or_else(N::one),
+                torsional.unwrap_or_else(|| na::convert(0.005)),
+                na::convert(0.0001),
+            ));
+        }
+    }
+
+    if let Some(material_node) = child_node(node, "material") {
+        let color = match child_text(&material_node, "diffuse") {
+            Some(diffuse) => Some(diffuse),
+            None => child_text(&material_node, "ambient"),
+        };
+        if let Some(color) = color {
+            geom.rgba = Some(attributes::parse_real_vector_attribute(color)?);
+        }
+    }
+    Ok(geom)
+}
+
+fn parse_joint_node<N: RealField>(
+    ctx: &mut ParseContext,
+    joint_node: &roxmltree::Node,
+    frame: &Frame<N>,
+) -> Result<SdfJoint<N>, SdfError>
+where
+    N: FromStr,
+{
+    let name = required_attribute(joint_node, "name")?;
+    let link = |tag: &str| -> Result<String, SdfError> {
+        let link = required_child_text(joint_node, tag)?.trim();
+        if link == WORLD_LINK_NAME {
+            Ok(link.to_string())
+        } else {
+            Ok(scoped(&frame.scope, link))
+        }
+    };
+    let parent = link("parent")?;
+    let child = link("child")?;
+
+    let joint_type = match required_attribute(joint_node, "type")? {
+        "revolute" | "continuous" => Some(JointType::Hinge),
+        "prismatic" => Some(JointType::Slide),
+        "ball" => Some(JointType::Ball),
+        "fixed" => None,
+        joint_type @ "universal"
+        | joint_type @ "revolute2"
+        | joint_type @ "screw"
+        | joint_type @ "gearbox" => {
+            ctx.warn(
+                WarningCode::IgnoredTag,
+                joint_node,
+                format!(
+                    "{} joints are not supported, link {} is welded to its parent",
+                    joint_type, child
+                ),
+            );
+            None
+        }
+        joint_type => return Err(SdfError::InvalidJointType(joint_type.to_string())),
+    };
+
+    let joint = match joint_type {
+        Some(joint_type) => {
+            let mut joint = Joint::new(joint_type);
+            joint.name = Some(scoped(&frame.scope, name));
+            if let Some(axis_node) = child_node(joint_node, "axis") {
+                joint.axis = child_vector(&axis_node, "xyz")?.unwrap_or_else(na::Vector3::z);
+
+                // continuous joints ignore their limits, and the
+                // default limits are effectively infinite
+                let bounded = joint_node.attribute("type") != Some("continuous");
+                if let Some(limit_node) = child_node(&axis_node, "limit").filter(|_| bounded) {
+                    let lower = child_real(&limit_node, "lower")?;
+                    let upper = child_real(&limit_node, "upper")?;
+                    if let (Some(lower), Some(upper)) = (lower, upper) {
+                        let infinite: N = na::convert(1.0e15);
+                        if lower.abs() < infinite && upper.abs() < infinite {
+                            joint.limited = Some(true);
+                            joint.range = Some(na::Vector2::new(lower, upper));
+                        }
+                    }
+                }
+
+                if let Some(dynamics_node) = child_node(&axis_node, "dynamics") {
+                    joint.damping = child_real(&dynamics_node, "damping")?;
+                    joint.frictionloss = child_real(&dynamics_node, "friction")?;
+                    ctx.warn(
+                        WarningCode::UnsupportedAttribute,
+                        &dynamics_node,
+                        "joint damping and friction are currently unsupported",
+                    );
+                }
+            }
+            Some(joint)
+        }
+        None => None,
+    };
+
+    Ok(SdfJoint {
+        name: scoped(&frame.scope, name),
+        parent,
+        child,
+        pose: parse_pose(joint_node)?.unwrap_or_else(na::Isometry3::identity),
+        joint,
+        location: ctx.source.location(joint_node),
+    })
+}
+
+/// Arrange the parsed links into body trees below the world body
+fn assemble<N: RealField>(ctx: &mut ParseContext, scene: &mut Scene<N>) {
+    let mut links: HashMap<String, SdfLink<N>> = HashMap::new();
+    let mut link_order = vec![];
+    for link in scene.links.drain(..) {
+        let name = link.body.name.clone().unwrap_or_default();
+        if links.contains_key(&name) {
+            ctx.error_at(link.location, SdfError::DuplicateLink(name));
+        } else {
+            link_order.push(name.clone());
+            links.insert(name, link);
+        }
+    }
+
+    // each link is placed by the joint it is the child of
+    let mut has_parent: HashMap<String, usize> = HashMap::new();
+    let mut child_joints: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, joint) in scene.joints.iter().enumerate() {
+        let unknown_link = [&joint.parent, &joint.child]
+            .iter()
+            .find(|link| link.as_str() != WORLD_LINK_NAME && !links.contains_key(link.as_str()))
+            .map(|link| link.to_string());
+        let error = if let Some(link) = unknown_link {
+            Some(SdfError::UnknownLink {
+                joint: joint.name.clone(),
+                link,
+            })
+        } else if joint.child == WORLD_LINK_NAME || has_parent.contains_key(&joint.child) {
+            Some(SdfError::MultipleParents(joint.child.clone()))
+        } else {
+            None
+        };
+        match error {
+            Some(error) => ctx.error_at(joint.location.clone(), error),
+            None => {
+                has_parent.insert(joint.child.clone(), index);
+                child_joints
+                    .entry(joint.parent.clone())
+                    .or_default()
+                    .push(index);
+            }
+        }
+    }
+
+    let world_pose = na::Isometry3::identity();
+    let worldbody = &mut scene.model.worldbody;
+    for index in child_joints.get(WORLD_LINK_NAME).into_iter().flatten() {
+        let joint = &scene.joints[*index];
+        let body = attach(
+            &joint.child,
+            &world_pose,
+            &mut links,
+            &scene.joints,
+            &child_joints,
+        );
+        worldbody.bodies.push(body);
+    }
+
+    for name in &link_order {
+        if has_parent.contains_key(name) {
+            continue;
+        }
+        let is_static = match links.get(name) {
+            Some(link) => link.is_static,
+            None => continue,
+        };
+        if is_static && !child_joints.contains_key(name) {
+            let link = links.remove(name).unwrap();
+            for mut geom in link.body.geoms {
+                let pose = link.pose
+                    * na::Isometry3::from_parts(na::Translation3::from(geom.pos), geom.quat);
+                geom.pos = pose.translation.vector;
+                geom.quat = pose.rotation;
+                worldbody.geoms.push(geom);
+            }
+            continue;
+        }
+
+        let mut body = attach(name, &world_pose, &mut links, &scene.joints, &child_joints);
+        if !is_static {
+            // links without a joint float freely
+            body.joints.insert(0, Joint::new(JointType::Free));
+        }
+        worldbody.bodies.push(body);
+    }
+
+    // links every root misses are only reachable through a loop
+    if let Some(name) = link_order.iter().find(|name| links.contains_key(*name)) {
+        let location = links[name].location.clone();
+        ctx.error_at(location, SdfError::KinematicLoop(name.clone()));
+    }
+}
+
+/// Take a link out of `links`, pose it in its parent, and attach its
+/// child links to it
+fn attach<N: RealField>(
+    name: &str,
+    parent_pose: &na::Isometry3<N>,
+    links: &mut HashMap<String, SdfLink<N>>,
+    joints: &[SdfJoint<N>],
+    child_joints: &HashMap<String, Vec<usize>>,
+) -> Body<N> {
+    let link = match links.remove(name) {
+        Some(link) => link,
+        None => return Body::default(),
+    };
+    let mut body = link.body;
+    let pose = parent_pose.inverse() * link.pose;
+    body.pos = pose.translation.vector;
+    body.quat = pose.rotation;
+
+    for index in child_joints.get(name).into_iter().flatten() {
+        let joint = &joints[*index];
+        let mut child = attach(&joint.child, &link.pose, links, joints, child_joints);
+        if let Some(mut child_joint) = joint.joint.clone() {
+            child_joint.pos = joint.pose.translation.vector;
+            child_joint.axis = joint.pose.rotation * child_joint.axis;
+            child.joints.push(child_joint);
+        }
+        body.bodies.push(child);
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::FileResolver;
+    use crate::MJCFModelDesc;
+    use nphysics3d::world::World;
+    use std::io;
+
+    const WORLD_SDF: &str = "<sdf version=\"1.6\">
+  <world name=\"playground\">
+    <gravity>0 0 -9.8</gravity>
+    <physics type=\"ode\">
+      <max_step_size>0.004</max_step_size>
+      <real_time_factor>1</real_time_factor>
+    </physics>
+    <light name=\"sun\" type=\"directional\"/>
+    <model name=\"ground\">
+      <static>true</static>
+      <link name=\"link\">
+        <collision name=\"collision\">
+          <geometry><plane><normal>0 0 1</normal><size>100 100</size></plane></geometry>
+          <surface><friction><ode><mu>0.8</mu><mu2>0.8</mu2></ode></friction></surface>
+        </collision>
+      </link>
+    </model>
+    <model name=\"arm\">
+      <pose>0 0 1 0 0 0</pose>
+      <link name=\"base\">
+        <inertial>
+          <mass>2</mass>
+          <inertia><ixx>0.1</ixx><iyy>0.1</iyy><izz>0.1</izz></inertia>
+        </inertial>
+        <collision name=\"collision\"><geometry><box><size>0.2 0.2 0.2</size></box></geometry></collision>
+        <visual name=\"visual\">
+          <geometry><box><size>0.2 0.2 0.2</size></box></geometry>
+          <material><diffuse>1 0 0 1</diffuse></material>
+        </visual>
+      </link>
+      <link name=\"upper\">
+        <pose>0 0 0.5 0 0 0</pose>
+        <collision name=\"collision\"><geometry><cylinder><radius>0.05</radius><length>0.5</length></cylinder></geometry></collision>
+      </link>
+      <joint name=\"mount\" type=\"fixed\">
+        <parent>world</parent>
+        <child>base</child>
+      </joint>
+      <joint name=\"shoulder\" type=\"revolute\">
+        <parent>base</parent>
+        <child>upper</child>
+        <pose>0 0 -0.25 0 0 0</pose>
+        <axis>
+          <xyz>0 1 0</xyz>
+          <limit><lower>-1</lower><upper>1</upper></limit>
+        </axis>
+      </joint>
+      <model name=\"gripper\">
+        <pose>0 0 1 0 0 0</pose>
+        <link name=\"palm\"/>
+      </model>
+    </model>
+  </world>
+</sdf>";
+
+    #[test]
+    fn parse_sdf_world() {
+        let (model, report) =
+            Model::<f32>::parse_sdf_string_with_report(WORLD_SDF, &ParseOptions::default())
+                .unwrap();
+        assert!(!report.has_errors());
+        // the light and the real time factor
+        assert_eq!(report.warning_count(WarningCode::IgnoredTag), 2);
+        assert_eq!(model.name.as_ref().map(String::as_str), Some("playground"));
+        assert_eq!(model.option.gravity, Some(na::Vector3::new(0.0, 0.0, -9.8)));
+        assert_eq!(model.option.timestep, Some(0.004));
+
+        // the static ground is part of the world
+        let ground = &model.worldbody.geoms[0];
+        assert_eq!(ground.geom_type, GeomType::Plane);
+        assert_eq!(ground.friction.unwrap()[0], 0.8);
+
+        let base = &model.worldbody.bodies[0];
+        assert_eq!(base.name.as_ref().map(String::as_str), Some("arm::base"));
+        assert!(base.joints.is_empty());
+        assert_eq!(base.pos, na::Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(base.inertial.as_ref().unwrap().mass, 2.0);
+        assert_eq!(base.geoms[0].size, vec![0.1, 0.1, 0.1]);
+        assert_eq!(base.geoms[1].contype, Some(0));
+        assert_eq!(
+            base.geoms[1].rgba,
+            Some(na::Vector4::new(1.0, 0.0, 0.0, 1.0))
+        );
+
+        // link poses are relative to the model, so the upper link is
+        // placed relative to the base
+        let upper = &base.bodies[0];
+        assert_eq!(upper.pos, na::Vector3::new(0.0, 0.0, 0.5));
+        let shoulder = &upper.joints[0];
+        assert_eq!(
+            shoulder.name.as_ref().map(String::as_str),
+            Some("arm::shoulder")
+        );
+        assert_eq!(shoulder.pos, na::Vector3::new(0.0, 0.0, -0.25));
+        assert_eq!(shoulder.axis, na::Vector3::y());
+        assert_eq!(shoulder.range, Some(na::Vector2::new(-1.0, 1.0)));
+
+        // links without a joint float freely
+        let palm = &model.worldbody.bodies[1];
+        assert_eq!(
+            palm.name.as_ref().map(String::as_str),
+            Some("arm::gripper::palm")
+        );
+        assert_eq!(palm.joints[0].joint_type, JointType::Free);
+        assert_eq!(palm.pos, na::Vector3::new(0.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn invalid_joints_are_reported() {
+        let sdf = "<sdf version=\"1.6\">
+                     <model name=\"robot\">
+                       <link name=\"a\"/>
+                       <link name=\"b\"/>
+                       <joint name=\"ab\" type=\"revolute\"><parent>a</parent><child>b</child></joint>
+                       <joint name=\"ab2\" type=\"revolute\"><parent>a</parent><child>b</child></joint>
+                       <joint name=\"ac\" type=\"revolute\"><parent>a</parent><child>c</child></joint>
+                       <joint name=\"spring\" type=\"spring\"><parent>a</parent><child>b</child></joint>
+                     </model>
+                   </sdf>";
+
+        let (model, report) =
+            Model::<f32>::parse_sdf_string_with_report(sdf, &ParseOptions::default()).unwrap();
+        assert_eq!(model.name.as_ref().map(String::as_str), Some("robot"));
+        let codes: Vec<MJCFParseErrorKind> =
+            report.errors().iter().map(MJCFParseError::kind).collect();
+        assert_eq!(
+            codes,
+            vec![
+                SdfError::InvalidJointType(String::from("spring")),
+                SdfError::MultipleParents(String::from("robot::b")),
+                SdfError::UnknownLink {
+                    joint: String::from("robot::ac"),
+                    link: String::from("robot::c")
+                },
+            ]
+            .into_iter()
+            .map(MJCFParseErrorKind::SdfError)
+            .collect::<Vec<_>>()
+        );
+        for error in report.errors() {
+            assert_eq!(error.kind().code(), "invalid-sdf");
+            assert!(error.location().is_some());
+        }
+    }
+
+    /// Serves a world that includes a model directory
+    struct WorldResolver;
+
+    impl FileResolver for WorldResolver {
+        fn read_to_string(&self, path: &Path) -> io::Result<String> {
+            let text = match path.to_str() {
+                Some("worlds/boxes.world") => {
+                    "<sdf version=\"1.6\">
+                       <world name=\"boxes\">
+                         <include><uri>model://crate</uri></include>
+                         <include>
+                           <uri>model://crate</uri>
+                           <name>crate_2</name>
+                           <pose>1 0 0 0 0 0</pose>
+                         </include>
+                         <include><uri>model://missing</uri></include>
+                       </world>
+                     </sdf>"
+                }
+                Some("worlds/crate/model.sdf") => {
+                    "<sdf version=\"1.6\">
+                       <model name=\"crate\">
+                         <link name=\"link\">
+                           <collision name=\"collision\">
+                             <geometry><mesh><uri>meshes/crate.obj</uri></mesh></geometry>
+                           </collision>
+                         </link>
+                       </model>
+                     </sdf>"
+                }
+                Some("worlds/crate/meshes/crate.obj") => {
+                    "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 2 3 4\n"
+                }
+                _ => return Err(io::Error::new(io::ErrorKind::NotFound, "not found")),
+            };
+            Ok(text.to_string())
+        }
+    }
+
+    #[test]
+    fn include_models_through_the_resolver() {
+        let options = ParseOptions::new().file_resolver(WorldResolver);
+        let (mut model_desc, report) =
+            MJCFModelDesc::<f32>::load_file_with_report("worlds/boxes.world", &options).unwrap();
+
+        let errors = report.errors();
+        assert_eq!(errors.len(), 1);
+        match errors[0].kind() {
+            MJCFParseErrorKind::SdfError(SdfError::IncludeFailed { uri, .. }) => {
+                assert_eq!(uri, "model://missing")
+            }
+            kind => panic!("Unexpected error {}", kind),
+        }
+
+        let model = model_desc.model();
+        assert_eq!(model.asset.meshes.len(), 1);
+        assert_eq!(
+            model.asset.meshes[0].file.as_ref().map(String::as_str),
+            Some("crate/meshes/crate.obj")
+        );
+        let crate_2 = &model.worldbody.bodies[1];
+        assert_eq!(
+            crate_2.name.as_ref().map(String::as_str),
+            Some("crate_2::link")
+        );
+        assert_eq!(crate_2.pos, na::Vector3::new(1.0, 0.0, 0.0));
+
+        let mut world = World::new();
+        let handles = model_desc.build(&mut world);
+        assert!(handles.body("crate::link").is_some());
+        assert!(handles.body("crate_2::link").is_some());
+    }
+}
@@ -0,0 +1,443 @@
+//! Headless simulation of a compiled model.
+//!
+//! A `Simulation` builds a model into its own nphysics `World` and
+//! steps it at the model's timestep. After each step it can take a
+//! `Frame`: the pose and velocity of every named body, the contacts
+//! between colliders, and the readings of the model's sensors.
+//!
+//! Only frame sensors (`framepos`, `framequat`, `framelinvel` and
+//! `frameangvel`) of named bodies, and of sites on named bodies, are
+//! read. Other sensors are listed by `unsupported_sensors`, and so are
+//! `framequat` sensors of `body` objects: those read the body's
+//! inertial frame, and only its center of mass is known after building.
+
+use crate::mjcf_model::MJCFModelDesc;
+use crate::model::Model;
+use crate::names::{ModelHandles, WORLD_BODY_NAME};
+use alga::general::SupersetOf;
+use nalgebra as na;
+use nphysics3d::object::BodyPartHandle;
+use nphysics3d::world::World;
+use std::str::FromStr;
+
+/// State of a named body after a step
+#[derive(Clone, PartialEq, Debug)]
+pub struct BodyState<N: na::RealField> {
+    pub name: String,
+    /// Position of the body frame in the world
+    pub position: na::Vector3<N>,
+    pub orientation: na::UnitQuaternion<N>,
+    pub linear_velocity: na::Vector3<N>,
+    pub angular_velocity: na::Vector3<N>,
+}
+
+/// A contact point between two colliders, named after their geoms.
+/// Colliders of unnamed geoms have an empty name.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ContactState<N: na::RealField> {
+    pub collider1: String,
+    pub collider2: String,
+    /// Contact point on the first collider, in world coordinates
+    pub point: na::Point3<N>,
+    /// Contact normal, pointing from the first collider to the second
+    pub normal: na::Vector3<N>,
+    pub depth: N,
+}
+
+/// Values of a sensor after a step
+#[derive(Clone, PartialEq, Debug)]
+pub struct SensorReading<N: na::RealField> {
+    pub name: String,
+    pub values: Vec<N>,
+}
+
+/// Everything recorded after a step
+#[derive(Clone, PartialEq, Debug)]
+pub struct Frame<N: na::RealField> {
+    /// Number of steps taken, 0 before the first step
+    pub step: usize,
+    pub time: N,
+    /// States of the named bodies, sorted by name
+    pub bodies: Vec<BodyState<N>>,
+    pub contacts: Vec<ContactState<N>>,
+    /// Readings of the supported sensors, in model order
+    pub sensors: Vec<SensorReading<N>>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum FrameQuantity {
+    Position,
+    Orientation,
+    LinearVelocity,
+    AngularVelocity,
+}
+
+impl FrameQuantity {
+    fn from_sensor_type(sensor_type: &str) -> Option<FrameQuantity> {
+        match sensor_type {
+            "framepos" => Some(FrameQuantity::Position),
+            "framequat" => Some(FrameQuantity::Orientation),
+            "framelinvel" => Some(FrameQuantity::LinearVelocity),
+            "frameangvel" => Some(FrameQuantity::AngularVelocity),
+            _ => None,
+        }
+    }
+
+    fn dimension(self) -> usize {
+        match self {
+            FrameQuantity::Orientation => 4,
+            _ => 3,
+        }
+    }
+}
+
+/// Frame a frame sensor reads, on its body
+enum SensedFrame<N: na::RealField> {
+    /// A frame fixed to the body, e.g. a site or the body frame of an
+    /// `xbody`, posed in the body's frame
+    Fixed(na::Isometry3<N>),
+    /// The body's center of mass, for `body` objects. It has no
+    /// orientation.
+    CenterOfMass,
+}
+
+/// A frame sensor, reading a point fixed to a body
+struct FrameSensor<N: na::RealField> {
+    name: String,
+    quantity: FrameQuantity,
+    body: BodyPartHandle,
+    frame: SensedFrame<N>,
+}
+
+pub struct Simulation<N: na::RealField> {
+    world: World<N>,
+    handles: ModelHandles,
+    /// Named bodies, sorted by name
+    bodies: Vec<(String, BodyPartHandle)>,
+    sensors: Vec<FrameSensor<N>>,
+    unsupported_sensors: Vec<String>,
+    step: usize,
+}
+
+impl<N: na::RealField> Simulation<N>
+where
+    N: From<f32>,
+    N: FromStr,
+    <N as FromStr>::Err: std::fmt::Display,
+{
+    /// Build the model into a new world
+    pub fn new(model_desc: &mut MJCFModelDesc<N>) -> Self {
+        let mut world = World::new();
+        let handles = model_desc.build(&mut world);
+
+        let mut bodies: Vec<(String, BodyPartHandle)> = handles
+            .bodies()
+            .filter(|(name, _)| *name != WORLD_BODY_NAME)
+            .map(|(name, handle)| (name.to_string(), handle))
+            .collect();
+        bodies.sort_by(|(name, _), (other, _)| name.cmp(other));
+
+        let mut simulation = Simulation {
+            world,
+            handles,
+            bodies,
+            sensors: vec![],
+            unsupported_sensors: vec![],
+            step: 0,
+        };
+        simulation.find_sensors(model_desc.model());
+        simulation
+    }
+
+    fn find_sensors(&mut self, model: &Model<N>) {
+        let sensor_section = match model.raw_section("sensor") {
+            Some(sensor_section) => sensor_section,
+            None => return,
+        };
+        for (index, sensor) in sensor_section.children.iter().enumerate() {
+            let name = sensor
+                .attribute("name")
+                .map_or_else(|| format!("{}{}", sensor.tag, index), str::to_string);
+            let frame_sensor = FrameQuantity::from_sensor_type(&sensor.tag).and_then(|quantity| {
+                let object = sensor.attribute("objname")?;
+                let (body, frame) = match sensor.attribute("objtype")? {
+                    // mujoco reads the inertial frame of a "body", and
+                    // the body frame of an "xbody"
+                    "body" if quantity != FrameQuantity::Orientation => {
+                        (self.handles.body(object)?, SensedFrame::CenterOfMass)
+                    }
+                    "xbody" => (
+                        self.handles.body(object)?,
+                        SensedFrame::Fixed(na::Isometry3::identity()),
+                    ),
+                    "site" => site_frame(model, object).and_then(|(body, offset)| {
+                        Some((self.handles.body(body)?, SensedFrame::Fixed(offset)))
+                    })?,
+                    _ => return None,
+                };
+                Some(FrameSensor {
+                    name: name.clone(),
+                    quantity,
+                    body,
+                    frame,
+                })
+            });
+            match frame_sensor {
+                Some(frame_sensor) => self.sensors.push(frame_sensor),
+                None => self
+                    .unsupported_sensors
+                    .push(format!("{} ({})", name, sensor.tag)),
+            }
+        }
+    }
+
+    pub fn world(&self) -> &World<N> {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World<N> {
+        &mut self.world
+    }
+
+    pub fn handles(&self) -> &ModelHandles {
+        &self.handles
+    }
+
+    pub fn timestep(&self) -> N {
+        self.world.timestep()
+    }
+
+    /// Simulated time since the start
+    pub fn time(&self) -> N {
+        self.timestep() * na::convert(self.step as f64)
+    }
+
+    /// Number of steps needed to simulate `duration` seconds
+    pub fn steps_for(&self, duration: N) -> usize {
+        let steps = to_f64((duration / self.timestep()).ceil());
+        if steps > 0.0 {
+            steps as usize
+        } else {
+            0
+        }
+    }
+
+    /// Names of the bodies recorded in each frame, sorted
+    pub fn body_names(&self) -> Vec<&str> {
+        self.bodies.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Names and number of values of the sensors recorded in each
+    /// frame
+    pub fn sensor_dimensions(&self) -> Vec<(&str, usize)> {
+        self.sensors
+            .iter()
+            .map(|sensor| (sensor.name.as_str(), sensor.quantity.dimension()))
+            .collect()
+    }
+
+    /// Sensors that are not read, with their types, e.g.
+    /// `"wrist_force (force)"`
+    pub fn unsupported_sensors(&self) -> &[String] {
+        &self.unsupported_sensors
+    }
+
+    /// Advance the world by one timestep
+    pub fn step(&mut self) {
+        self.world.step();
+        self.step += 1;
+    }
+
+    /// Step for `duration` seconds, passing the frame after every
+    /// step to `record`. Stops at the first error `record` returns.
+    pub fn run<E, F>(&mut self, duration: N, mut record: F) -> Result<(), E>
+    where
+        F: FnMut(&Frame<N>) -> Result<(), E>,
+    {
+        for _ in 0..self.steps_for(duration) {
+            self.step();
+            record(&self.frame())?;
+        }
+        Ok(())
+    }
+
+    /// State of the simulation after the last step
+    pub fn frame(&self) -> Frame<N> {
+        Frame {
+            step: self.step,
+            time: self.time(),
+            bodies: self
+                .bodies
+                .iter()
+                .filter_map(|(name, handle)| self.body_state(name, *handle))
+                .collect(),
+            contacts: self.contacts(),
+            sensors: self
+                .sensors
+                .iter()
+                .filter_map(|sensor| self.read_sensor(sensor))
+                .collect(),
+        }
+    }
+
+    fn body_state(&self, name: &str, handle: BodyPartHandle) -> Option<BodyState<N>> {
+        let part = self.world.body_part(handle)?;
+        let position = part.position();
+        let velocity = part.velocity();
+        Some(BodyState {
+            name: name.to_string(),
+            position: position.translation.vector,
+            orientation: position.rotation,
+            linear_velocity: velocity.linear,
+            angular_velocity: velocity.angular,
+        })
+    }
+
+    fn contacts(&self) -> Vec<ContactState<N>> {
+        let mut contacts = vec![];
+        for (collider1, collider2, _, manifold) in self.world.collider_world().contact_pairs(true) {
+            for tracked in manifold.contacts() {
+                let contact = &tracked.contact;
+                contacts.push(ContactState {
+                    collider1: collider1.name().to_string(),
+                    collider2: collider2.name().to_string(),
+                    point: contact.world1,
+                    normal: contact.normal.into_inner(),
+                    depth: contact.depth,
+                });
+            }
+        }
+        contacts
+    }
+
+    fn read_sensor(&self, sensor: &FrameSensor<N>) -> Option<SensorReading<N>> {
+        let part = self.world.body_part(sensor.body)?;
+        let (position, rotation) = match &sensor.frame {
+            SensedFrame::Fixed(offset) => {
+                let pose = part.position() * offset;
+                (pose.translation.vector, Some(pose.rotation))
+            }
+            SensedFrame::CenterOfMass => (part.center_of_mass().coords, None),
+        };
+        let velocity = part.velocity();
+        let values = match sensor.quantity {
+            FrameQuantity::Position => position.iter().cloned().collect(),
+            FrameQuantity::Orientation => {
+                // mujoco uses [w, x, y, z] but nalgebra uses [x, y, z, w]
+                let quat = rotation?.quaternion();
+                vec![quat.w, quat.i, quat.j, quat.k]
+            }
+            FrameQuantity::LinearVelocity => {
+                // velocity of the sensed point, not of the body frame
+                let lever = position - part.position().translation.vector;
+                (velocity.linear + velocity.angular.cross(&lever))
+                    .iter()
+                    .cloned()
+                    .collect()
+            }
+            FrameQuantity::AngularVelocity => velocity.angular.iter().cloned().collect(),
+        };
+        Some(SensorReading {
+            name: sensor.name.clone(),
+            values,
+        })
+    }
+}
+
+/// Name of the body a site is on, and the site's pose in it
+fn site_frame<'m, N: na::RealField>(
+    model: &'m Model<N>,
+    site_name: &str,
+) -> Option<(&'m str, na::Isometry3<N>)> {
+    let mut bodies = model.bodies();
+    bodies.push(&model.worldbody);
+    bodies.into_iter().find_map(|body| {
+        let site = body
+            .sites
+            .iter()
+            .find(|site| site.name.as_ref().map(String::as_str) == Some(site_name))?;
+        let body_name = if std::ptr::eq(body, &model.worldbody) {
+            WORLD_BODY_NAME
+        } else {
+            body.name.as_ref()?.as_str()
+        };
+        Some((
+            body_name,
+            na::Isometry3::from_parts(na::Translation3::from(site.pos), site.quat),
+        ))
+    })
+}
+
+/// Value of a real as an `f64`, for writing trajectories
+pub(crate) fn to_f64<N: na::RealField>(value: N) -> f64 {
+    <N as SupersetOf<f64>>::to_subset(&value).unwrap_or(std::f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DROP_XML: &str = "<mujoco>
+  <option timestep=\"0.01\"/>
+  <worldbody>
+    <geom name=\"floor\" type=\"plane\" size=\"5 5 0.1\"/>
+    <body name=\"ball\" pos=\"0 0 1\">
+      <freejoint/>
+      <geom name=\"ball\" size=\"0.1\"/>
+      <site name=\"top\" pos=\"0 0 0.1\"/>
+    </body>
+  </worldbody>
+  <sensor>
+    <framepos name=\"ball_pos\" objtype=\"xbody\" objname=\"ball\"/>
+    <framepos name=\"ball_com\" objtype=\"body\" objname=\"ball\"/>
+    <framequat name=\"ball_inertia\" objtype=\"body\" objname=\"ball\"/>
+    <framepos name=\"top_pos\" objtype=\"site\" objname=\"top\"/>
+    <touch name=\"touch\" site=\"top\"/>
+  </sensor>
+</mujoco>";
+
+    #[test]
+    fn falling_ball_lands_on_the_floor() {
+        let mut model_desc = MJCFModelDesc::<f32>::parse_xml_string(DROP_XML).unwrap();
+        let mut simulation = Simulation::new(&mut model_desc);
+        assert_eq!(simulation.body_names(), vec!["ball"]);
+        assert_eq!(
+            simulation.sensor_dimensions(),
+            vec![("ball_pos", 3), ("ball_com", 3), ("top_pos", 3)]
+        );
+        assert_eq!(
+            simulation.unsupported_sensors(),
+            &[
+                "ball_inertia (framequat)".to_string(),
+                "touch (touch)".to_string()
+            ]
+        );
+        assert_eq!(simulation.steps_for(2.0), 200);
+
+        let mut frames = vec![];
+        simulation
+            .run(2.0, |frame| -> Result<(), ()> {
+                frames.push(frame.clone());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(frames.len(), 200);
+
+        let first = &frames[0];
+        assert_eq!(first.step, 1);
+        assert!(first.bodies[0].position.z < 1.0);
+        assert!(first.bodies[0].linear_velocity.z < 0.0);
+        assert!(first.contacts.is_empty());
+
+        let last = frames.last().unwrap();
+        assert!((last.time - 2.0).abs() < 1.0e-4);
+        let ball = &last.bodies[0];
+        assert!((ball.position.z - 0.1).abs() < 0.05);
+        assert!(!last.contacts.is_empty());
+        // sensors read the body frame, the center of mass at its
+        // origin, and the site above it
+        assert_eq!(last.sensors[0].values[2], ball.position.z);
+        assert!((last.sensors[1].values[2] - ball.position.z).abs() < 1.0e-3);
+        assert!((last.sensors[2].values[2] - ball.position.z - 0.1).abs() < 1.0e-3);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::attributes;
+use crate::context::ParseContext;
+use crate::model::{Asset, Material, Mesh, Texture};
+use crate::report::WarningCode;
+use crate::schema;
+use crate::tags;
+use failure::Fail;
+use nalgebra as na;
+use roxmltree;
+#[allow(unused_imports)]
+use slog::{debug, error, info, trace, warn};
+use std::str::FromStr;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum AssetError {
+    #[fail(display = "{} asset needs a name or a file", 0)]
+    Unnamed(String),
+    #[fail(display = "Bad attribute values. {}", 0)]
+    BadRealAttribute(#[fail(cause)] attributes::ParseRealAttributeError),
+    #[fail(display = "Bad attribute value. {}", 0)]
+    BadScalarAttribute(#[fail(cause)] attributes::ParseScalarAttributeError),
+}
+
+impl From<attributes::ParseRealAttributeError> for AssetError {
+    fn from(error: attributes::ParseRealAttributeError) -> AssetError {
+        AssetError::BadRealAttribute(error)
+    }
+}
+
+impl From<attributes::ParseScalarAttributeError> for AssetError {
+    fn from(error: attributes::ParseScalarAttributeError) -> AssetError {
+        AssetError::BadScalarAttribute(error)
+    }
+}
+
+/// Parse the children of an `<asset>` section into the model's
+/// assets. Errors in children are recorded, and the children left
+/// out.
+pub fn parse_asset_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    asset_node: &roxmltree::Node,
+    asset: &mut Asset<N>,
+) where
+    N: FromStr,
+{
+    for child in asset_node.children() {
+        if !child.is_element() {
+            continue;
+        }
+        let result = match child.tag_name().name() {
+            "mesh" => parse_mesh_node(&child).map(|mesh| asset.meshes.push(mesh)),
+            "material" => {
+                parse_material_node(&child).map(|material| asset.materials.push(material))
+            }
+            "texture" => parse_texture_node(&child).map(|texture| asset.textures.push(texture)),
+            // tags missing from the schema were already reported
+            tag if schema::ASSET.child(tag).is_none() => Ok(()),
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} asset is not currently supported", tag),
+                );
+                asset.raw_assets.push(tags::raw::parse_raw_node(&child));
+                Ok(())
+            }
+        };
+        if let Err(error) = result {
+            ctx.error(&child, error);
+        }
+    }
+}
+
+fn parse_mesh_node<N: na::RealField>(mesh_node: &roxmltree::Node) -> Result<Mesh<N>, AssetError>
+where
+    N: FromStr,
+{
+    let mesh = Mesh {
+        name: mesh_node.attribute("name").map(str::to_string),
+        class: mesh_node.attribute("class").map(str::to_string),
+        file: mesh_node.attribute("file").map(str::to_string),
+        scale: match mesh_node.attribute("scale") {
+            Some(scale) => Some(attributes::parse_real_vector_attribute(scale)?),
+            None => None,
+        },
+        raw_attributes: tags::raw::parse_raw_attributes(
+            mesh_node,
+            &schema::MESH,
+            &["name", "class", "file", "scale"],
+        ),
+    };
+    if mesh.reference_name().is_none() {
+        return Err(AssetError::Unnamed(String::from("mesh")));
+    }
+    Ok(mesh)
+}
+
+fn parse_material_node(material_node: &roxmltree::Node) -> Result<Material, AssetError> {
+    let material = Material {
+        name: material_node.attribute("name").map(str::to_string),
+        class: material_node.attribute("class").map(str::to_string),
+        texture: material_node.attribute("texture").map(str::to_string),
+        rgba: match material_node.attribute("rgba") {
+            Some(rgba) => Some(attributes::parse_real_vector_attribute(rgba)?),
+            None => None,
+        },
+        raw_attributes: tags::raw::parse_raw_attributes(
+            material_node,
+            &schema::MATERIAL,
+            &["name", "class", "texture", "rgba"],
+        ),
+    };
+    if material.name.is_none() {
+        return Err(AssetError::Unnamed(String::from("material")));
+    }
+    Ok(material)
+}
+
+fn parse_texture_node(texture_node: &roxmltree::Node) -> Result<Texture, AssetError> {
+    let mut texture = Texture::default();
+    texture.name = texture_node.attribute("name").map(str::to_string);
+    texture.texture_type = texture_node.attribute("type").map(str::to_string);
+    texture.file = texture_node.attribute("file").map(str::to_string);
+    texture.builtin = texture_node.attribute("builtin").map(str::to_string);
+    if let Some(rgb1) = texture_node.attribute("rgb1") {
+        texture.rgb1 = Some(attributes::parse_real_vector_attribute(rgb1)?);
+    }
+    if let Some(rgb2) = texture_node.attribute("rgb2") {
+        texture.rgb2 = Some(attributes::parse_real_vector_attribute(rgb2)?);
+    }
+    if let Some(width) = texture_node.attribute("width") {
+        texture.width = Some(attributes::parse_int_attribute(width)?);
+    }
+    if let Some(height) = texture_node.attribute("height") {
+        texture.height = Some(attributes::parse_int_attribute(height)?);
+    }
+    texture.raw_attributes = tags::raw::parse_raw_attributes(
+        texture_node,
+        &schema::TEXTURE,
+        &[
+            "name", "type", "file", "builtin", "rgb1", "rgb2", "width", "height",
+        ],
+    );
+    if texture.name.is_none() && texture.file.is_none() {
+        return Err(AssetError::Unnamed(String::from("texture")));
+    }
+    Ok(texture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Source;
+    use crate::options::ParseOptions;
+
+    #[test]
+    fn parse_assets() {
+        let xml = "<asset>
+                     <mesh file=\"meshes/link.stl\" scale=\"0.001 0.001 0.001\"/>
+                     <texture name=\"grid\" type=\"2d\" builtin=\"checker\" width=\"512\" height=\"512\"/>
+                     <material name=\"floor\" texture=\"grid\" rgba=\"1 1 1 1\"/>
+                     <material rgba=\"1 1 1 1\"/>
+                     <hfield name=\"terrain\" file=\"terrain.png\"/>
+                   </asset>";
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let mut asset = Asset::<f32>::default();
+        parse_asset_node(&mut ctx, &doc.root_element(), &mut asset);
+
+        assert!(asset.mesh("link").is_some());
+        assert_eq!(asset.textures[0].width, Some(512));
+        assert_eq!(
+            asset
+                .material("floor")
+                .unwrap()
+                .texture
+                .as_ref()
+                .map(String::as_str),
+            Some("grid")
+        );
+        // unnamed material
+        assert_eq!(ctx.report.errors().len(), 1);
+        // hfield, which is kept as written
+        assert_eq!(ctx.report.warning_count(WarningCode::IgnoredTag), 1);
+        assert_eq!(asset.raw_assets[0].attribute("file"), Some("terrain.png"));
+    }
+}
@@ -0,0 +1,489 @@
+use crate::attributes;
+use crate::context::ParseContext;
+use crate::error::MJCFParseErrorKind;
+use crate::model::{Body, GeomType, Inertial, Joint, JointType, Site};
+use crate::report::WarningCode;
+use crate::schema;
+use crate::tags;
+use failure::Fail;
+use nalgebra as na;
+use roxmltree;
+#[allow(unused_imports)]
+use slog::{debug, error, info, trace, warn};
+use std::str::FromStr;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum BodyError {
+    #[fail(
+        display = "Required attribute \"{}\" missing on {} tag",
+        attribute, tag
+    )]
+    RequiredAttributeMissing { tag: String, attribute: String },
+    #[fail(display = "Bad attribute values. {}", 0)]
+    BadRealAttribute(#[fail(cause)] attributes::ParseRealAttributeError),
+    #[fail(display = "Bad attribute value. {}", 0)]
+    BadScalarAttribute(#[fail(cause)] attributes::ParseScalarAttributeError),
+    #[fail(display = "Failed to parse orientation. Reason {}", 0)]
+    BadOrientation(#[fail(cause)] attributes::ParseOrientationError),
+    #[fail(display = "Invalid joint type {}", 0)]
+    InvalidJointType(String),
+    #[fail(display = "Invalid site type {}", 0)]
+    InvalidSiteType(String),
+    #[fail(display = "inertial tag needs either a diaginertia or a fullinertia attribute")]
+    MissingInertia,
+    #[fail(display = "inertial tag has both diaginertia and fullinertia attributes")]
+    MultipleInertias,
+}
+
+impl From<attributes::ParseRealAttributeError> for BodyError {
+    fn from(error: attributes::ParseRealAttributeError) -> BodyError {
+        BodyError::BadRealAttribute(error)
+    }
+}
+
+impl From<attributes::ParseScalarAttributeError> for BodyError {
+    fn from(error: attributes::ParseScalarAttributeError) -> BodyError {
+        BodyError::BadScalarAttribute(error)
+    }
+}
+
+impl From<attributes::ParseOrientationError> for BodyError {
+    fn from(error: attributes::ParseOrientationError) -> BodyError {
+        BodyError::BadOrientation(error)
+    }
+}
+
+/// Attributes that are valid on a joint, but have no effect on the
+/// built multibody
+const UNSUPPORTED_JOINT_ATTRIBUTES: [&str; 13] = [
+    "class",
+    "damping",
+    "springdamper",
+    "solreflimit",
+    "solimplimit",
+    "solreffriction",
+    "solimpfriction",
+    "stiffness",
+    "margin",
+    "ref",
+    "springref",
+    "armature",
+    "frictionloss",
+];
+
+/// Attributes that are parsed into the fields of bodies, joints and
+/// sites. The rest are kept as they were written.
+const MODELED_BODY_ATTRIBUTES: [&str; 4] = ["name", "childclass", "pos", "mocap"];
+const MODELED_JOINT_ATTRIBUTES: [&str; 12] = [
+    "name",
+    "class",
+    "type",
+    "group",
+    "pos",
+    "axis",
+    "limited",
+    "range",
+    "damping",
+    "stiffness",
+    "armature",
+    "frictionloss",
+];
+const MODELED_SITE_ATTRIBUTES: [&str; 9] = [
+    "name", "class", "type", "size", "fromto", "pos", "rgba", "group", "material",
+];
+
+/// Parse the `<worldbody>` tag into the world body.
+///
+/// Errors in children are recorded, and the children left out.
+pub fn parse_worldbody_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    worldbody_node: &roxmltree::Node,
+) -> Body<N>
+where
+    N: FromStr,
+{
+    debug!(ctx.logger, "Parsing worldbody tag");
+    if !worldbody_node.attributes().is_empty() {
+        ctx.error(worldbody_node, MJCFParseErrorKind::WorldBodyHasAttributes);
+    }
+
+    let mut worldbody = Body::default();
+    parse_body_children(ctx, worldbody_node, &mut worldbody, true);
+    worldbody
+}
+
+/// Parse a `<body>` tag and everything nested in it.
+///
+/// Only errors in the body's own attributes are returned. Errors in
+/// children are recorded, and the children left out.
+pub fn parse_body_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    body_node: &roxmltree::Node,
+) -> Result<Body<N>, BodyError>
+where
+    N: FromStr,
+{
+    trace!(ctx.logger, "Parsing body tag");
+
+    let mut body = Body::default();
+    body.name = body_node.attribute("name").map(str::to_string);
+    body.childclass = body_node.attribute("childclass").map(str::to_string);
+    if let Some(pos) = body_node.attribute("pos") {
+        body.pos = attributes::parse_real_vector_attribute(pos)?;
+    }
+    body.quat = attributes::parse_orientation_attribute(&ctx.logger, body_node, false, ctx.angle)?;
+    if let Some(mocap) = body_node.attribute("mocap") {
+        body.mocap = Some(attributes::parse_bool_attribute(mocap)?);
+    }
+    body.raw_attributes =
+        tags::raw::parse_raw_attributes(body_node, &schema::BODY, &MODELED_BODY_ATTRIBUTES);
+    for attribute in ["childclass", "user"].iter() {
+        if body_node.has_attribute(*attribute) {
+            ctx.warn(
+                WarningCode::UnsupportedAttribute,
+                body_node,
+                format!("{} attribute is currently unsupported", attribute),
+            );
+        }
+    }
+
+    parse_body_children(ctx, body_node, &mut body, false);
+
+    if body.joints.len() > 1 {
+        ctx.warn(
+            WarningCode::IgnoredTag,
+            body_node,
+            format!(
+                "body has {} joints, but only one joint per body is currently supported. Only the first joint is built",
+                body.joints.len()
+            ),
+        );
+    }
+
+    Ok(body)
+}
+
+fn parse_body_children<N: na::RealField>(
+    ctx: &mut ParseContext,
+    body_node: &roxmltree::Node,
+    body: &mut Body<N>,
+    is_world: bool,
+) where
+    N: FromStr,
+{
+    let body_schema = if is_world {
+        &schema::WORLDBODY
+    } else {
+        &schema::BODY
+    };
+
+    for child in body_node.children() {
+        // skip non-element tags
+        if !child.is_element() {
+            continue;
+        }
+        match child.tag_name().name() {
+            tag if is_world && schema::WORLDBODY_INVALID_CHILDREN.contains(&tag) => {
+                ctx.error(&child, MJCFParseErrorKind::WorldBodyInvalidChildren);
+            }
+            "inertial" => match parse_inertial_node(ctx, &child) {
+                Ok(inertial) => body.inertial = Some(inertial),
+                Err(error) => ctx.error(&child, error),
+            },
+            "joint" | "freejoint" => match parse_joint_node(ctx, &child) {
+                Ok(joint) => body.joints.push(joint),
+                Err(error) => ctx.error(&child, error),
+            },
+            "geom" => match tags::geom::parse_geom_node(ctx, &child) {
+                Ok(geom) => body.geoms.push(geom),
+                Err(error) => ctx.error(&child, error),
+            },
+            "site" => match parse_site_node(ctx, &child) {
+                Ok(site) => body.sites.push(site),
+                Err(error) => ctx.error(&child, error),
+            },
+            "body" => match parse_body_node(ctx, &child) {
+                Ok(child_body) => body.bodies.push(child_body),
+                Err(error) => ctx.error(&child, error),
+            },
+            // tags missing from the schema were already reported
+            tag if body_schema.child(tag).is_none() => {}
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} tag is not currently supported", tag),
+                );
+                body.raw_children.push(tags::raw::parse_raw_node(&child));
+            }
+        };
+    }
+}
+
+pub fn parse_inertial_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    inertial_node: &roxmltree::Node,
+) -> Result<Inertial<N>, BodyError>
+where
+    N: FromStr,
+{
+    let mass = match inertial_node.attribute("mass") {
+        Some(mass) => attributes::parse_real_attribute(mass)?,
+        None => {
+            return Err(BodyError::RequiredAttributeMissing {
+                tag: String::from("inertial"),
+                attribute: String::from("mass"),
+            });
+        }
+    };
+    let pos = match inertial_node.attribute("pos") {
+        Some(pos) => attributes::parse_real_vector_attribute(pos)?,
+        None => {
+            return Err(BodyError::RequiredAttributeMissing {
+                tag: String::from("inertial"),
+                attribute: String::from("pos"),
+            });
+        }
+    };
+    let quat =
+        attributes::parse_orientation_attribute(&ctx.logger, inertial_node, false, ctx.angle)?;
+
+    let diaginertia = match inertial_node.attribute("diaginertia") {
+        Some(diaginertia) => Some(attributes::parse_real_vector_attribute(diaginertia)?),
+        None => None,
+    };
+    let fullinertia = match inertial_node.attribute("fullinertia") {
+        Some(fullinertia) => Some(attributes::parse_real_vector_attribute(fullinertia)?),
+        None => None,
+    };
+    match (&diaginertia, &fullinertia) {
+        (None, None) => return Err(BodyError::MissingInertia),
+        (Some(_), Some(_)) => return Err(BodyError::MultipleInertias),
+        _ => {}
+    }
+
+    Ok(Inertial {
+        mass,
+        pos,
+        quat,
+        diaginertia,
+        fullinertia,
+    })
+}
+
+pub fn parse_joint_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    joint_node: &roxmltree::Node,
+) -> Result<Joint<N>, BodyError>
+where
+    N: FromStr,
+{
+    let mut joint = Joint::default();
+    joint.name = joint_node.attribute("name").map(str::to_string);
+    if let Some(group) = joint_node.attribute("group") {
+        joint.group = Some(attributes::parse_int_attribute(group)?);
+    }
+
+    if joint_node.has_tag_name("freejoint") {
+        joint.joint_type = JointType::Free;
+        return Ok(joint);
+    }
+    joint.raw_attributes =
+        tags::raw::parse_raw_attributes(joint_node, &schema::JOINT, &MODELED_JOINT_ATTRIBUTES);
+
+    joint.class = joint_node.attribute("class").map(str::to_string);
+    if let Some(joint_type) = joint_node.attribute("type") {
+        joint.joint_type = JointType::from_str(joint_type).map_err(BodyError::InvalidJointType)?;
+    }
+    if let Some(pos) = joint_node.attribute("pos") {
+        joint.pos = attributes::parse_real_vector_attribute(pos)?;
+    }
+    if let Some(axis) = joint_node.attribute("axis") {
+        joint.axis = attributes::parse_real_vector_attribute(axis)?;
+    }
+    if let Some(limited) = joint_node.attribute("limited") {
+        joint.limited = Some(attributes::parse_bool_attribute(limited)?);
+    }
+    if let Some(range) = joint_node.attribute("range") {
+        joint.range = Some(attributes::parse_real_vector_attribute(range)?);
+    }
+    if let Some(damping) = joint_node.attribute("damping") {
+        joint.damping = Some(attributes::parse_real_attribute(damping)?);
+    }
+    if let Some(stiffness) = joint_node.attribute("stiffness") {
+        joint.stiffness = Some(attributes::parse_real_attribute(stiffness)?);
+    }
+    if let Some(armature) = joint_node.attribute("armature") {
+        joint.armature = Some(attributes::parse_real_attribute(armature)?);
+    }
+    if let Some(frictionloss) = joint_node.attribute("frictionloss") {
+        joint.frictionloss = Some(attributes::parse_real_attribute(frictionloss)?);
+    }
+
+    for attribute in UNSUPPORTED_JOINT_ATTRIBUTES.iter() {
+        if joint_node.has_attribute(*attribute) {
+            ctx.warn(
+                WarningCode::UnsupportedAttribute,
+                joint_node,
+                format!("{} attribute is currently unsupported", attribute),
+            );
+        }
+    }
+    if joint.joint_type == JointType::Ball && joint.is_limited() {
+        ctx.warn(
+            WarningCode::UnsupportedAttribute,
+            joint_node,
+            "range of ball joints is currently unsupported",
+        );
+    }
+
+    Ok(joint)
+}
+
+pub fn parse_site_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    site_node: &roxmltree::Node,
+) -> Result<Site<N>, BodyError>
+where
+    N: FromStr,
+{
+    let mut site = Site::default();
+    site.name = site_node.attribute("name").map(str::to_string);
+    site.class = site_node.attribute("class").map(str::to_string);
+    site.material = site_node.attribute("material").map(str::to_string);
+
+    if let Some(site_type) = site_node.attribute("type") {
+        site.site_type = match GeomType::from_str(site_type) {
+            Ok(GeomType::Plane) | Ok(GeomType::Hfield) | Ok(GeomType::Mesh) | Err(_) => {
+                return Err(BodyError::InvalidSiteType(site_type.to_string()));
+            }
+            Ok(site_type) => site_type,
+        };
+    }
+    if let Some(size) = site_node.attribute("size") {
+        site.size = attributes::parse_real_list_attribute(size)?;
+    }
+    if let Some(fromto) = site_node.attribute("fromto") {
+        site.fromto = Some(attributes::parse_real_vector_attribute(fromto)?);
+    }
+    if let Some(pos) = site_node.attribute("pos") {
+        site.pos = attributes::parse_real_vector_attribute(pos)?;
+    }
+    let quat = attributes::parse_orientation_attribute(
+        &ctx.logger,
+        site_node,
+        site.site_type.allows_fromto(),
+        ctx.angle,
+    )?;
+    if site.fromto.is_none() {
+        site.quat = quat;
+    }
+    if let Some(rgba) = site_node.attribute("rgba") {
+        site.rgba = Some(attributes::parse_real_vector_attribute(rgba)?);
+    }
+    if let Some(group) = site_node.attribute("group") {
+        site.group = Some(attributes::parse_int_attribute(group)?);
+    }
+    site.raw_attributes =
+        tags::raw::parse_raw_attributes(site_node, &schema::SITE, &MODELED_SITE_ATTRIBUTES);
+
+    Ok(site)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Source;
+    use crate::options::ParseOptions;
+    use crate::report::ParseReport;
+    use roxmltree;
+
+    fn parse_body(xml: &str) -> (Result<Body<f32>, BodyError>, ParseReport) {
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let body = parse_body_node(&mut ctx, &doc.root_element());
+        (body, ctx.report)
+    }
+
+    #[test]
+    fn parse_nested_bodies() {
+        let xml = "<body name=\"upper\" pos=\"0 0 1\">
+                     <joint name=\"shoulder\" type=\"hinge\" axis=\"0 1 0\" limited=\"true\" range=\"-90 90\"/>
+                     <geom type=\"capsule\" size=\"0.05\" fromto=\"0 0 0 0 0 -0.5\"/>
+                     <body name=\"lower\" pos=\"0 0 -0.5\">
+                       <freejoint/>
+                       <inertial pos=\"0 0 -0.25\" mass=\"1\" diaginertia=\"0.1 0.1 0.01\"/>
+                       <site name=\"tip\" pos=\"0 0 -0.5\"/>
+                     </body>
+                   </body>";
+
+        let (body, report) = parse_body(xml);
+        let body = body.unwrap();
+        assert!(report.is_clean(), "{}", report);
+
+        assert_eq!(body.name.as_ref().map(String::as_str), Some("upper"));
+        assert_eq!(body.pos, na::Vector3::new(0.0, 0.0, 1.0));
+        assert_eq!(body.joints.len(), 1);
+        assert_eq!(body.joints[0].joint_type, JointType::Hinge);
+        assert!(body.joints[0].is_limited());
+        assert_eq!(body.geoms.len(), 1);
+
+        let lower = &body.bodies[0];
+        assert_eq!(lower.joints[0].joint_type, JointType::Free);
+        assert_eq!(lower.inertial.as_ref().unwrap().mass, 1.0);
+        assert_eq!(
+            lower.sites[0].name.as_ref().map(String::as_str),
+            Some("tip")
+        );
+    }
+
+    #[test]
+    fn bad_child_is_left_out() {
+        let xml = "<body><geom type=\"box\"/><geom size=\"1\"/></body>";
+
+        let (body, report) = parse_body(xml);
+        assert_eq!(body.unwrap().geoms.len(), 1);
+        assert_eq!(report.errors().len(), 1);
+    }
+
+    #[test]
+    fn inertial_needs_inertia() {
+        let xml = "<body><inertial pos=\"0 0 0\" mass=\"1\"/></body>";
+
+        let (body, report) = parse_body(xml);
+        assert!(body.unwrap().inertial.is_none());
+        match report.errors()[0].kind() {
+            MJCFParseErrorKind::BodyError(BodyError::MissingInertia) => {}
+            kind => panic!("Got unexpected error type {}", kind),
+        }
+    }
+
+    #[test]
+    fn invalid_joint_type() {
+        let xml = "<body><joint type=\"hing\"/></body>";
+
+        let (body, report) = parse_body(xml);
+        assert!(body.unwrap().joints.is_empty());
+        assert_eq!(report.errors().len(), 1);
+    }
+
+    #[test]
+    fn joint_group_is_supported() {
+        let xml = "<body><joint type=\"hinge\" group=\"2\" damping=\"1\"/></body>";
+
+        let (body, report) = parse_body(xml);
+        assert_eq!(body.unwrap().joints[0].group, Some(2));
+        assert_eq!(report.warning_count(WarningCode::UnsupportedAttribute), 1);
+    }
+
+    #[test]
+    fn multiple_joints_are_reported() {
+        let xml = "<body><joint type=\"slide\" axis=\"1 0 0\"/><joint type=\"slide\" axis=\"0 1 0\"/></body>";
+
+        let (body, report) = parse_body(xml);
+        assert_eq!(body.unwrap().joints.len(), 2);
+        assert_eq!(report.warning_count(WarningCode::IgnoredTag), 1);
+    }
+}
@@ -0,0 +1,356 @@
+use crate::attributes;
+use crate::context::ParseContext;
+use crate::report::WarningCode;
+use failure::Fail;
+use nalgebra as na;
+use roxmltree;
+use serde::{Deserialize, Serialize};
+#[allow(unused_imports)]
+use slog::{debug, error, info, trace, warn};
+use std::str::FromStr;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum CustomError {
+    #[fail(
+        display = "Required attribute \"{}\" missing on {} tag",
+        attribute, tag
+    )]
+    RequiredAttributeMissing { tag: String, attribute: String },
+    #[fail(display = "Bad attribute values. {}", 0)]
+    BadRealAttribute(#[fail(cause)] attributes::ParseRealAttributeError),
+    #[fail(display = "Invalid numeric size \"{}\"", 0)]
+    InvalidSize(String),
+    #[fail(
+        display = "Numeric {} has size {}, but {} data values were given",
+        name, size, actual_len
+    )]
+    DataExceedsSize {
+        name: String,
+        size: usize,
+        actual_len: usize,
+    },
+}
+
+impl From<attributes::ParseRealAttributeError> for CustomError {
+    fn from(error: attributes::ParseRealAttributeError) -> CustomError {
+        CustomError::BadRealAttribute(error)
+    }
+}
+
+/// A `<numeric>` element. A named array of real values.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CustomNumeric<N: na::RealField> {
+    pub name: String,
+    pub data: Vec<N>,
+}
+
+/// A `<text>` element. A named string.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CustomText {
+    pub name: String,
+    pub data: String,
+}
+
+/// A single `<element>` of a `<tuple>`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CustomTupleElement<N: na::RealField> {
+    pub objtype: String,
+    pub objname: String,
+    pub prm: N,
+}
+
+/// A `<tuple>` element. A named list of references to other model
+/// objects, each with an associated real parameter.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CustomTuple<N: na::RealField> {
+    pub name: String,
+    pub elements: Vec<CustomTupleElement<N>>,
+}
+
+/// User data stored in the `<custom>` section of the model.
+///
+/// Entries are kept in document order. Lookups are by the MJCF name
+/// of the element.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Custom<N: na::RealField> {
+    pub numerics: Vec<CustomNumeric<N>>,
+    pub texts: Vec<CustomText>,
+    pub tuples: Vec<CustomTuple<N>>,
+}
+
+impl<N: na::RealField> Default for Custom<N> {
+    fn default() -> Self {
+        Custom {
+            numerics: vec![],
+            texts: vec![],
+            tuples: vec![],
+        }
+    }
+}
+
+impl<N: na::RealField> Custom<N> {
+    /// Data of the `<numeric>` element with the given name
+    pub fn numeric(&self, name: &str) -> Option<&[N]> {
+        self.numerics
+            .iter()
+            .find(|numeric| numeric.name == name)
+            .map(|numeric| numeric.data.as_slice())
+    }
+
+    /// Data of the `<text>` element with the given name
+    pub fn text(&self, name: &str) -> Option<&str> {
+        self.texts
+            .iter()
+            .find(|text| text.name == name)
+            .map(|text| text.data.as_str())
+    }
+
+    /// Elements of the `<tuple>` element with the given name
+    pub fn tuple(&self, name: &str) -> Option<&[CustomTupleElement<N>]> {
+        self.tuples
+            .iter()
+            .find(|tuple| tuple.name == name)
+            .map(|tuple| tuple.elements.as_slice())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.numerics.is_empty() && self.texts.is_empty() && self.tuples.is_empty()
+    }
+}
+
+fn required_attribute<'a>(
+    node: &'a roxmltree::Node,
+    attribute: &str,
+) -> Result<&'a str, CustomError> {
+    node.attribute(attribute)
+        .ok_or_else(|| CustomError::RequiredAttributeMissing {
+            tag: node.tag_name().name().to_string(),
+            attribute: attribute.to_string(),
+        })
+}
+
+fn parse_numeric_node<N: na::RealField>(
+    numeric_node: &roxmltree::Node,
+) -> Result<CustomNumeric<N>, CustomError>
+where
+    N: From<f32>,
+    N: FromStr,
+{
+    let name = required_attribute(numeric_node, "name")?;
+
+    let mut data: Vec<N> = match numeric_node.attribute("data") {
+        Some(data) => attributes::parse_real_list_attribute(data)?,
+        None => vec![],
+    };
+
+    // size defaults to -1, meaning the size is inferred from data
+    let size = match numeric_node.attribute("size") {
+        Some(size_text) => match size_text.trim().parse::<i64>() {
+            Ok(size) if size == -1 => None,
+            Ok(size) if size > 0 => Some(size as usize),
+            _ => return Err(CustomError::InvalidSize(size_text.to_string())),
+        },
+        None => None,
+    };
+
+    if let Some(size) = size {
+        if data.len() > size {
+            return Err(CustomError::DataExceedsSize {
+                name: name.to_string(),
+                size,
+                actual_len: data.len(),
+            });
+        }
+        // missing values are filled with zeros
+        data.resize(size, N::from(0.0));
+    }
+
+    Ok(CustomNumeric {
+        name: name.to_string(),
+        data,
+    })
+}
+
+fn parse_text_node(text_node: &roxmltree::Node) -> Result<CustomText, CustomError> {
+    Ok(CustomText {
+        name: required_attribute(text_node, "name")?.to_string(),
+        data: required_attribute(text_node, "data")?.to_string(),
+    })
+}
+
+fn parse_tuple_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    tuple_node: &roxmltree::Node,
+) -> Result<CustomTuple<N>, CustomError>
+where
+    N: From<f32>,
+    N: FromStr,
+{
+    let name = required_attribute(tuple_node, "name")?;
+
+    let mut elements: Vec<CustomTupleElement<N>> = vec![];
+    for child in tuple_node.children() {
+        if !child.is_element() {
+            continue;
+        }
+        match child.tag_name().name() {
+            "element" => {
+                let prm = match child.attribute("prm") {
+                    Some(prm) => *attributes::parse_real_vector_attribute::<N, na::U1>(prm)?
+                        .get(0)
+                        .unwrap(),
+                    None => N::from(0.0),
+                };
+                elements.push(CustomTupleElement {
+                    objtype: required_attribute(&child, "objtype")?.to_string(),
+                    objname: required_attribute(&child, "objname")?.to_string(),
+                    prm,
+                });
+            }
+            tag => ctx.warn(
+                WarningCode::IgnoredTag,
+                &child,
+                format!("Ignoring unsupported tuple child {}", tag),
+            ),
+        }
+    }
+
+    Ok(CustomTuple {
+        name: name.to_string(),
+        elements,
+    })
+}
+
+/// Parses a single child of a `<custom>` tag, adding it to `custom`.
+///
+/// A model may contain multiple `<custom>` sections, so parsed
+/// entries are accumulated instead of replacing the existing
+/// ones. Name uniqueness is checked by the model's
+/// `NameRegistry`.
+pub fn parse_custom_child<N: na::RealField>(
+    ctx: &mut ParseContext,
+    child_node: &roxmltree::Node,
+    custom: &mut Custom<N>,
+) -> Result<(), CustomError>
+where
+    N: From<f32>,
+    N: FromStr,
+{
+    match child_node.tag_name().name() {
+        "numeric" => {
+            let numeric = parse_numeric_node(child_node)?;
+            debug!(ctx.logger, "Parsed custom numeric"; "name" => &numeric.name);
+            custom.numerics.push(numeric);
+        }
+        "text" => {
+            let text = parse_text_node(child_node)?;
+            debug!(ctx.logger, "Parsed custom text"; "name" => &text.name);
+            custom.texts.push(text);
+        }
+        "tuple" => {
+            let tuple = parse_tuple_node(ctx, child_node)?;
+            debug!(ctx.logger, "Parsed custom tuple"; "name" => &tuple.name);
+            custom.tuples.push(tuple);
+        }
+        tag => ctx.warn(
+            WarningCode::IgnoredTag,
+            child_node,
+            format!("Ignoring unsupported custom child {}", tag),
+        ),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Source;
+    use crate::options::ParseOptions;
+    use proptest::prelude::*;
+    use roxmltree;
+
+    fn parse_custom(xml: &str) -> Result<Custom<f32>, CustomError> {
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let root = doc.root_element();
+
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let mut custom = Custom::default();
+        for child in root.children().filter(|node| node.is_element()) {
+            parse_custom_child(&mut ctx, &child, &mut custom)?;
+        }
+        Ok(custom)
+    }
+
+    #[test]
+    fn parse_text() {
+        let custom = parse_custom("<custom><text name=\"task\" data=\"reach\"/></custom>").unwrap();
+
+        assert_eq!(custom.text("task"), Some("reach"));
+        assert_eq!(custom.text("missing"), None);
+    }
+
+    #[test]
+    fn parse_text_missing_data() {
+        match parse_custom("<custom><text name=\"task\"/></custom>") {
+            Err(CustomError::RequiredAttributeMissing { tag, attribute }) => {
+                assert_eq!(tag, "text");
+                assert_eq!(attribute, "data");
+            }
+            result => panic!("Unexpected parse result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parse_numeric_padded_to_size() {
+        let custom =
+            parse_custom("<custom><numeric name=\"goal\" size=\"4\" data=\"1 2\"/></custom>")
+                .unwrap();
+
+        assert_eq!(custom.numeric("goal"), Some(&[1.0, 2.0, 0.0, 0.0][..]));
+    }
+
+    #[test]
+    fn parse_numeric_data_exceeds_size() {
+        match parse_custom("<custom><numeric name=\"goal\" size=\"1\" data=\"1 2\"/></custom>") {
+            Err(CustomError::DataExceedsSize {
+                size, actual_len, ..
+            }) => {
+                assert_eq!(size, 1);
+                assert_eq!(actual_len, 2);
+            }
+            result => panic!("Unexpected parse result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn parse_tuple() {
+        let xml = "<custom>
+                     <tuple name=\"targets\">
+                       <element objtype=\"body\" objname=\"arm\" prm=\"0.5\"/>
+                       <element objtype=\"geom\" objname=\"ball\"/>
+                     </tuple>
+                   </custom>";
+        let custom = parse_custom(xml).unwrap();
+
+        let elements = custom.tuple("targets").unwrap();
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].objtype, "body");
+        assert_eq!(elements[0].objname, "arm");
+        assert_eq!(elements[0].prm, 0.5);
+        assert_eq!(elements[1].prm, 0.0);
+    }
+
+    proptest! {
+        #[test]
+        fn parse_numeric_inferred_size(real_values in proptest::collection::vec(proptest::num::f32::NORMAL, 1..10)) {
+            let data = real_values.iter().map(f32::to_string).collect::<Vec<String>>().join(" ");
+            let xml = format!("<custom><numeric name=\"values\" data=\"{}\"/></custom>", data);
+
+            let custom = parse_custom(&xml).unwrap();
+            prop_assert_eq!(custom.numeric("values"), Some(real_values.as_slice()));
+        }
+    }
+}
@@ -1,12 +1,11 @@
 use crate::attributes;
+use crate::context::ParseContext;
+use crate::model::{Geom, GeomType};
+use crate::report::WarningCode;
+use crate::schema;
+use crate::tags;
 use failure::Fail;
 use nalgebra as na;
-use ncollide3d::shape;
-use ncollide3d::shape::ShapeHandle;
-use ncollide3d::transformation::ToTriMesh;
-use nphysics3d::material::{BasicMaterial, MaterialHandle};
-use nphysics3d::object::ColliderDesc;
-use nphysics_user_data::ColliderUserData;
 use roxmltree;
 #[allow(unused_imports)]
 use slog::{debug, error, info, trace, warn};
@@ -22,10 +21,23 @@ pub enum GeomError {
     RequiredAttributeMissing(String),
     #[fail(display = "Bad attribute values. {}", 0)]
     BadRealAttribute(#[fail(cause)] attributes::ParseRealAttributeError),
+    #[fail(display = "Bad attribute value. {}", 0)]
+    BadScalarAttribute(#[fail(cause)] attributes::ParseScalarAttributeError),
     #[fail(display = "Failed to parse orientation. Reason {}", 0)]
     BadOrientation(#[fail(cause)] attributes::ParseOrientationError),
     #[fail(display = "Multiple positions specified")]
     MultiplePositions,
+    #[fail(
+        display = "{} geom needs {} sizes, but has {}",
+        geom_type, expected, found
+    )]
+    MissingSizes {
+        geom_type: String,
+        expected: usize,
+        found: usize,
+    },
+    #[fail(display = "{} geom has no volume", geom_type)]
+    DegenerateShape { geom_type: String },
 }
 
 impl From<attributes::ParseRealAttributeError> for GeomError {
@@ -34,291 +46,275 @@ impl From<attributes::ParseRealAttributeError> for GeomError {
     }
 }
 
+impl From<attributes::ParseScalarAttributeError> for GeomError {
+    fn from(error: attributes::ParseScalarAttributeError) -> GeomError {
+        GeomError::BadScalarAttribute(error)
+    }
+}
+
 impl From<attributes::ParseOrientationError> for GeomError {
     fn from(error: attributes::ParseOrientationError) -> GeomError {
         GeomError::BadOrientation(error)
     }
 }
 
+/// Attributes that are valid on a geom, but have no effect on the
+/// built collider
+const UNSUPPORTED_ATTRIBUTES: [&str; 13] = [
+    "class",
+    "contype",
+    "conaffinity",
+    "condim",
+    "priority",
+    "material",
+    "mass",
+    "solmix",
+    "solref",
+    "solimp",
+    "gap",
+    "hfield",
+    "fitscale",
+];
+
+/// Attributes that are parsed into the geom's fields. The rest are
+/// kept as they were written.
+const MODELED_ATTRIBUTES: [&str; 18] = [
+    "name",
+    "class",
+    "type",
+    "size",
+    "fromto",
+    "pos",
+    "rgba",
+    "friction",
+    "density",
+    "mass",
+    "margin",
+    "group",
+    "contype",
+    "conaffinity",
+    "condim",
+    "material",
+    "mesh",
+    "hfield",
+];
+
+/// Parse the size attribute, checking it has the number of values
+/// the geom type needs
+fn parse_sizes<N: na::RealField, D: na::DimName>(
+    geom_node: &roxmltree::Node,
+) -> Result<Vec<N>, GeomError>
+where
+    na::DefaultAllocator: na::allocator::Allocator<N, D>,
+    N: FromStr,
+{
+    let size_attr = "size";
+    match geom_node.attribute(size_attr) {
+        Some(size_text) => {
+            let sizes = attributes::parse_real_vector_attribute::<N, D>(size_text)?;
+            Ok(sizes.iter().cloned().collect())
+        }
+        None => Err(GeomError::RequiredAttributeMissing(size_attr.to_string())),
+    }
+}
+
 #[allow(clippy::cyclomatic_complexity)]
 pub fn parse_geom_node<N: na::RealField>(
-    logger: &slog::Logger,
+    ctx: &mut ParseContext,
     geom_node: &roxmltree::Node,
-) -> Result<ColliderDesc<N>, GeomError>
+) -> Result<Geom<N>, GeomError>
 where
-    N: From<f32>,
     N: FromStr,
-    <N as FromStr>::Err: std::fmt::Display,
 {
-    trace!(logger, "Parsing geom tag");
+    trace!(ctx.logger, "Parsing geom tag");
 
-    let shape_handle: ShapeHandle<N> = match geom_node.attribute("type") {
-        Some("plane") => {
-            warn!(logger, "Size currently ignored"; "type" => "plane");
-            let normal = na::Unit::new_unchecked(na::Vector3::<N>::z());
-            ShapeHandle::new(shape::Plane::new(normal))
-        }
-        Some("hfield") => {
-            return Err(GeomError::UnsupportedType {
-                geom_type: String::from("hfield"),
-            });
-        }
-        Some("sphere") | None => {
-            let size_attr = "size";
-            let sizes = match geom_node.attribute(size_attr) {
-                Some(size_text) => attributes::parse_real_vector_attribute::<N, na::U1>(size_text)?,
-                None => return Err(GeomError::RequiredAttributeMissing(size_attr.to_string())),
-            };
-            let radius = *sizes.get(0).unwrap();
-            ShapeHandle::new(shape::Ball::new(radius))
-        }
-        Some(geom_type) if (geom_type == "capsule" || geom_type == "cylinder") => {
-            debug!(logger, "Parsing capsule/cylinder sizes");
-            let size_attr = "size";
-            let fromto_attr = "fromto";
-            let (half_length, radius) = match geom_node.attribute(size_attr) {
-                Some(size_text) => {
-                    if geom_node.has_attribute(fromto_attr) {
-                        let sizes: na::Vector1<N> =
-                            attributes::parse_real_vector_attribute(size_text)?;
-
-                        let radius = *sizes.get(0).unwrap();
-
-                        // parse half length from fromto
-                        let fromto: na::Vector6<N> = attributes::parse_real_vector_attribute(
-                            geom_node.attribute(fromto_attr).unwrap(),
-                        )?;
-                        let p0 = fromto.rows(0, 3);
-                        let p1 = fromto.rows(3, 3);
-                        let half_length = p0.metric_distance(&p1) / N::from(2.0);
-
-                        (half_length, radius)
-                    } else {
-                        let sizes: na::Vector2<N> =
-                            attributes::parse_real_vector_attribute(size_text)?;
-                        let radius = *sizes.get(0).unwrap();
-                        let half_length = *sizes.get(1).unwrap();
-
-                        (half_length, radius)
-                    }
-                }
-                None => return Err(GeomError::RequiredAttributeMissing(size_attr.to_string())),
-            };
+    let geom_type = match geom_node.attribute("type") {
+        Some(type_text) => GeomType::from_str(type_text)
+            .map_err(|geom_type| GeomError::InvalidType { geom_type })?,
+        None => GeomType::Sphere,
+    };
 
-            if geom_type == "capsule" {
-                debug!(logger, "Setting capsule shape");
-                ShapeHandle::new(shape::Capsule::new(half_length, radius))
-            } else {
-                debug!(logger, "Setting cylinder shape");
-                let cyl_trimesh = shape::Cylinder::new(half_length, radius).to_trimesh(32);
-                // ShapeHandle::new(shape::TriMesh::new(
-                //     cyl_trimesh.coords,
-                //     cyl_trimesh
-                //         .indices
-                //         .unwrap_unified()
-                //         .iter()
-                //         .map(na::convert_ref::<na::Point3<u32>, na::Point3<usize>>)
-                //         .collect(),
-                //     cyl_trimesh.uvs,
-                // ))
-                ShapeHandle::new(shape::ConvexHull::try_from_points(&cyl_trimesh.coords).unwrap())
+    let fromto: Option<na::Vector6<N>> = match geom_node.attribute("fromto") {
+        Some(fromto) if geom_type.allows_fromto() => {
+            if geom_node.has_attribute("pos") {
+                return Err(GeomError::MultiplePositions);
             }
+            Some(attributes::parse_real_vector_attribute(fromto)?)
         }
-        Some("ellipsoid") => {
-            return Err(GeomError::UnsupportedType {
-                geom_type: String::from("ellipsoid"),
-            });
-        }
-        Some("box") => {
-            let size_attr = "size";
-            let sizes: na::Vector3<N> = match geom_node.attribute(size_attr) {
-                Some(size_text) => attributes::parse_real_vector_attribute(size_text)?,
-                None => return Err(GeomError::RequiredAttributeMissing(size_attr.to_string())),
-            };
-            ShapeHandle::new(shape::Cuboid::new(sizes))
-        }
-        Some("mesh") => {
-            return Err(GeomError::UnsupportedType {
-                geom_type: String::from("mesh"),
-            });
-        }
-        Some(geom_type) => {
-            return Err(GeomError::InvalidType {
-                geom_type: geom_type.to_string(),
-            });
+        Some(_) => {
+            ctx.warn(
+                WarningCode::IgnoredAttribute,
+                geom_node,
+                format!("fromto attribute is ignored for {} geoms", geom_type),
+            );
+            None
         }
+        None => None,
     };
 
-    let mut collider_desc = ColliderDesc::new(shape_handle);
-    let mut user_data: ColliderUserData<N> = Default::default();
-
-    if let Some(name) = geom_node.attribute("name") {
-        collider_desc.set_name(name.to_owned());
-    }
-
-    let translation: na::Translation3<N> = match geom_node.attribute("type") {
-        Some("plane") | Some("sphere") | None => match geom_node.attribute("pos") {
-            Some(pos) => na::Translation3::from(attributes::parse_real_vector_attribute(pos)?),
-            None => na::Translation3::identity(),
-        },
-        Some("capsule") | Some("box") | Some("cylinder") => match geom_node.attribute("fromto") {
-            Some(fromto) => {
-                if geom_node.has_attribute("pos") {
-                    return Err(GeomError::MultiplePositions);
-                } else {
-                    // parse half length from fromto
-                    let fromto: na::Vector6<N> = attributes::parse_real_vector_attribute(fromto)?;
-                    let p0 = na::Point3::from(fromto.fixed_rows::<na::U3>(0).into_owned());
-                    let p1 = na::Point3::from(fromto.fixed_rows::<na::U3>(3).into_owned());
-                    let dir = p1 - p0;
-
-                    let center: na::Point3<N> = p0 + dir * N::from(0.5);
-                    na::Translation3::new(center.x, center.y, center.z)
-                }
+    let size: Vec<N> = match geom_type {
+        GeomType::Plane => {
+            if geom_node.has_attribute("size") {
+                ctx.warn(
+                    WarningCode::IgnoredAttribute,
+                    geom_node,
+                    "size attribute is ignored for planes, which are infinite",
+                );
+            }
+            match geom_node.attribute("size") {
+                Some(size_text) => attributes::parse_real_list_attribute(size_text)?,
+                None => vec![],
             }
-            None => match geom_node.attribute("pos") {
-                Some(pos) => na::Translation3::from(attributes::parse_real_vector_attribute(pos)?),
-                None => na::Translation3::identity(),
-            },
-        },
-        Some(geom_type) => {
-            return Err(GeomError::InvalidType {
-                geom_type: geom_type.to_string(),
-            });
         }
-    };
-
-    let orientation: na::UnitQuaternion<N> = match geom_node.attribute("type") {
-        Some("plane") => attributes::parse_orientation_attribute(logger, geom_node, false)?,
-        Some("sphere") | None => attributes::parse_orientation_attribute(logger, geom_node, false)?,
-        Some("capsule") | Some("cylinder") => {
-            let fix_principal_axis = na::UnitQuaternion::<N>::from_euler_angles(
-                N::from(0.0),
-                N::from(std::f32::consts::FRAC_PI_2),
-                N::from(0.0),
-            );
-            attributes::parse_orientation_attribute(logger, geom_node, true)? * fix_principal_axis
+        GeomType::Sphere => parse_sizes::<N, na::U1>(geom_node)?,
+        GeomType::Capsule | GeomType::Cylinder => {
+            debug!(ctx.logger, "Parsing capsule/cylinder sizes");
+            // with fromto, the half length comes from the end points
+            if fromto.is_some() {
+                parse_sizes::<N, na::U1>(geom_node)?
+            } else {
+                parse_sizes::<N, na::U2>(geom_node)?
+            }
+        }
+        GeomType::Box => {
+            if fromto.is_some() {
+                parse_sizes::<N, na::U2>(geom_node)?
+            } else {
+                parse_sizes::<N, na::U3>(geom_node)?
+            }
+        }
+        GeomType::Mesh => {
+            if !geom_node.has_attribute("mesh") {
+                return Err(GeomError::RequiredAttributeMissing(String::from("mesh")));
+            }
+            vec![]
         }
-        Some("box") => attributes::parse_orientation_attribute(logger, geom_node, true)?,
-        Some(geom_type) => {
-            return Err(GeomError::InvalidType {
+        GeomType::Hfield | GeomType::Ellipsoid => {
+            return Err(GeomError::UnsupportedType {
                 geom_type: geom_type.to_string(),
             });
         }
     };
-    collider_desc.set_position(na::Isometry3::from_parts(translation, orientation));
-
-    if let Some(density) = geom_node.attribute("density") {
-        let density: na::Vector1<N> = attributes::parse_real_vector_attribute(density)?;
-        collider_desc.set_density(*density.get(0).unwrap());
-    }
-
-    if let Some(margin) = geom_node.attribute("margin") {
-        let margin: na::Vector1<N> = attributes::parse_real_vector_attribute(margin)?;
-        collider_desc.set_margin(*margin.get(0).unwrap());
-    }
-
-    if let Some(friction) = geom_node.attribute("friction") {
-        let friction: na::Vector3<N> = attributes::parse_real_vector_attribute(friction)?;
-        warn!(logger, "Torsional and rolling friction not currently supported. Setting values in user data";
-              "torsional_friction" => %*friction.get(1).unwrap(),
-              "rolling_friction" => %*friction.get(2).unwrap());
-        user_data.torsional_friction = *friction.get(1).unwrap();
-        user_data.rolling_friction = *friction.get(2).unwrap();
-
-        collider_desc.set_material(MaterialHandle::new(BasicMaterial::new(
-            N::from(0.0),
-            *friction.get(0).unwrap(),
-        )));
-    } else {
-        // default value from mujoco xml reference
-        collider_desc.set_material(MaterialHandle::new(BasicMaterial::new(
-            N::from(0.0),
-            N::from(1.0),
-        )));
-    }
-
-    if let Some(rgba) = geom_node.attribute("rgba") {
-        let rgba: na::Vector4<f32> = attributes::parse_real_vector_attribute(rgba)?;
-        user_data.rgba = Some(na::Point4::from(rgba));
-    }
 
-    if geom_node.has_attribute("class") {
-        warn!(logger, "class attribute is currently unspported"; "node" => ?geom_node);
+    if geom_type == GeomType::Cylinder {
+        ctx.warn(
+            WarningCode::ApproximatedShape,
+            geom_node,
+            format!(
+                "cylinder is approximated by a convex hull with {} subdivisions",
+                ctx.options.get_tesselation()
+            ),
+        );
     }
-
-    if geom_node.has_attribute("contype") {
-        warn!(logger, "contype attribute is currently unsupported"; "node" => ?geom_node);
+    if geom_type == GeomType::Mesh {
+        ctx.warn(
+            WarningCode::ApproximatedShape,
+            geom_node,
+            "mesh is approximated by its convex hull",
+        );
     }
 
-    if geom_node.has_attribute("conaffinity") {
-        warn!(logger, "conaffinity attribute is currently unsupported"; "node" => ?geom_node);
-    }
+    let mut geom = Geom::new(geom_type, size);
+    geom.fromto = fromto;
+    geom.name = geom_node.attribute("name").map(str::to_string);
+    geom.class = geom_node.attribute("class").map(str::to_string);
+    geom.material = geom_node.attribute("material").map(str::to_string);
+    geom.mesh = geom_node.attribute("mesh").map(str::to_string);
+    geom.hfield = geom_node.attribute("hfield").map(str::to_string);
 
-    if geom_node.has_attribute("condim") {
-        warn!(logger, "condim attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(pos) = geom_node.attribute("pos") {
+        geom.pos = attributes::parse_real_vector_attribute(pos)?;
     }
 
-    if geom_node.has_attribute("group") {
-        warn!(logger, "group attribute is currently unsupported"; "node" => ?geom_node);
+    // fromto counts as an orientation, so this also checks that it
+    // is not combined with another one
+    let quat: na::UnitQuaternion<N> = attributes::parse_orientation_attribute(
+        &ctx.logger,
+        geom_node,
+        geom_type.allows_fromto(),
+        ctx.angle,
+    )?;
+    if geom.fromto.is_none() {
+        geom.quat = quat;
     }
 
-    if geom_node.has_attribute("priority") {
-        warn!(logger, "priority attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(density) = geom_node.attribute("density") {
+        geom.density = Some(attributes::parse_real_attribute(density)?);
     }
 
-    if geom_node.has_attribute("material") {
-        warn!(logger, "material attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(mass) = geom_node.attribute("mass") {
+        geom.mass = Some(attributes::parse_real_attribute(mass)?);
     }
 
-    if geom_node.has_attribute("mass") {
-        warn!(logger, "mass attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(margin) = geom_node.attribute("margin") {
+        geom.margin = Some(attributes::parse_real_attribute(margin)?);
     }
 
-    if geom_node.has_attribute("solmix") {
-        warn!(logger, "solmix attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(friction) = geom_node.attribute("friction") {
+        let friction: na::Vector3<N> = attributes::parse_real_vector_attribute(friction)?;
+        ctx.warn(
+            WarningCode::UnsupportedAttribute,
+            geom_node,
+            format!(
+                "torsional ({}) and rolling ({}) friction are not currently supported. Setting values in user data",
+                friction.get(1).unwrap(),
+                friction.get(2).unwrap()
+            ),
+        );
+        geom.friction = Some(friction);
     }
 
-    if geom_node.has_attribute("solref") {
-        warn!(logger, "solref attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(rgba) = geom_node.attribute("rgba") {
+        geom.rgba = Some(attributes::parse_real_vector_attribute(rgba)?);
     }
 
-    if geom_node.has_attribute("solimpl") {
-        warn!(logger, "solimpl attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(group) = geom_node.attribute("group") {
+        geom.group = Some(attributes::parse_int_attribute(group)?);
     }
-
-    if geom_node.has_attribute("gap") {
-        warn!(logger, "gap attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(contype) = geom_node.attribute("contype") {
+        geom.contype = Some(attributes::parse_int_attribute(contype)?);
     }
-
-    if geom_node.has_attribute("hfield") {
-        warn!(logger, "hfield attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(conaffinity) = geom_node.attribute("conaffinity") {
+        geom.conaffinity = Some(attributes::parse_int_attribute(conaffinity)?);
     }
-
-    if geom_node.has_attribute("mesh") {
-        warn!(logger, "mesh attribute is currently unsupported"; "node" => ?geom_node);
+    if let Some(condim) = geom_node.attribute("condim") {
+        geom.condim = Some(attributes::parse_int_attribute(condim)?);
     }
 
-    if geom_node.has_attribute("fitscale") {
-        warn!(logger, "fitscale attribute is currently unsupported"; "node" => ?geom_node);
+    // geoms that collide with nothing are built as sensors, which is
+    // the one use of contype and conaffinity that is supported
+    let visual_only = geom.contype == Some(0) && geom.conaffinity == Some(0);
+    for attribute in UNSUPPORTED_ATTRIBUTES.iter() {
+        let collision_filter = *attribute == "contype" || *attribute == "conaffinity";
+        if geom_node.has_attribute(*attribute) && !(visual_only && collision_filter) {
+            ctx.warn(
+                WarningCode::UnsupportedAttribute,
+                geom_node,
+                format!("{} attribute is currently unsupported", attribute),
+            );
+        }
     }
+    geom.raw_attributes =
+        tags::raw::parse_raw_attributes(geom_node, &schema::GEOM, &MODELED_ATTRIBUTES);
 
-    collider_desc.set_user_data(Some(user_data));
-    Ok(collider_desc)
+    Ok(geom)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::log;
+    use crate::compile;
+    use crate::location::Source;
+    use crate::mesh::Meshes;
+    use crate::options::ParseOptions;
     use nalgebra as na;
+    use ncollide3d::shape;
     use proptest::prelude::*;
     use roxmltree;
-    use slog::o;
 
     proptest! {
         #[test]
-        fn parse_default_geom_type(ball_radius in proptest::num::f32::NORMAL) {
+        fn parse_default_geom_type(ball_radius in proptest::num::f32::POSITIVE | proptest::num::f32::NORMAL) {
             prop_assume!(ball_radius != 0.0);
 
             let xml = format!("<geom size=\"{}\"></geom>", ball_radius);
@@ -326,9 +322,11 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml).unwrap();
             let root = doc.root_element();
 
-            let logger = log::LOG.read().unwrap().new(o!());
+            let options = ParseOptions::default();
+            let mut ctx = ParseContext::new(Source::new(None, &xml), &options);
 
-            let collider_desc = parse_geom_node::<f32>(&logger, &root).unwrap();
+            let geom = parse_geom_node::<f32>(&mut ctx, &root).unwrap();
+            let collider_desc = compile::geom_collider_desc(&geom, &Meshes::new(), options.get_tesselation()).unwrap().unwrap();
 
             // default is not moved
             prop_assert_eq!(*collider_desc.get_translation(), na::Vector3::zeros());
@@ -348,9 +346,10 @@ mod tests {
             let doc = roxmltree::Document::parse(&xml).unwrap();
             let root = doc.root_element();
 
-            let logger = log::LOG.read().unwrap().new(o!());
+            let options = ParseOptions::default();
+            let mut ctx = ParseContext::new(Source::new(None, &xml), &options);
 
-            if let Err(error) = parse_geom_node::<f32>(&logger, &root) {
+            if let Err(error) = parse_geom_node::<f32>(&mut ctx, &root) {
                 match error {
                     GeomError::BadRealAttribute(_) => {},
                     _ => {
@@ -364,16 +363,18 @@ mod tests {
         }
 
         #[test]
-        fn parse_sphere_geom(ball_radius in proptest::num::f32::NORMAL) {
+        fn parse_sphere_geom(ball_radius in proptest::num::f32::POSITIVE | proptest::num::f32::NORMAL) {
             prop_assume!(ball_radius != 0.0);
             let xml = format!("<geom type=\"sphere\" size=\"{}\"></geom>", ball_radius);
 
             let doc = roxmltree::Document::parse(&xml).unwrap();
             let root = doc.root_element();
 
-            let logger = log::LOG.read().unwrap().new(o!());
+            let options = ParseOptions::default();
+            let mut ctx = ParseContext::new(Source::new(None, &xml), &options);
 
-            let collider_desc = parse_geom_node::<f32>(&logger, &root).unwrap();
+            let geom = parse_geom_node::<f32>(&mut ctx, &root).unwrap();
+            let collider_desc = compile::geom_collider_desc(&geom, &Meshes::new(), options.get_tesselation()).unwrap().unwrap();
 
             // default is not moved
             prop_assert_eq!(*collider_desc.get_translation(), na::Vector3::zeros());
@@ -386,4 +387,105 @@ mod tests {
         }
 
     }
+
+    #[test]
+    fn unsupported_attributes_are_reported() {
+        let xml = "<geom size=\"1\" contype=\"1\" conaffinity=\"1\" solimp=\"0.9 0.95 0.001\" group=\"3\"/>";
+
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let root = doc.root_element();
+
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        parse_geom_node::<f32>(&mut ctx, &root).unwrap();
+
+        assert_eq!(
+            ctx.report.warning_count(WarningCode::UnsupportedAttribute),
+            3
+        );
+        assert_eq!(ctx.report.warnings().len(), 3);
+    }
+
+    #[test]
+    fn cylinder_is_reported_as_approximated() {
+        let xml = "<geom type=\"cylinder\" size=\"1 2\"/>";
+
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let root = doc.root_element();
+
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        parse_geom_node::<f32>(&mut ctx, &root).unwrap();
+
+        assert_eq!(ctx.report.warning_count(WarningCode::ApproximatedShape), 1);
+    }
+
+    #[test]
+    fn parse_keeps_written_values() {
+        let xml = "<geom name=\"arm\" class=\"links\" type=\"capsule\" size=\"0.1\" fromto=\"0 0 0 0 0 1\" group=\"2\" rgba=\"1 0 0 1\"/>";
+
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let root = doc.root_element();
+
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let geom = parse_geom_node::<f32>(&mut ctx, &root).unwrap();
+        assert_eq!(geom.name.as_ref().map(String::as_str), Some("arm"));
+        assert_eq!(geom.class.as_ref().map(String::as_str), Some("links"));
+        assert_eq!(geom.geom_type, GeomType::Capsule);
+        assert_eq!(geom.size, vec![0.1]);
+        assert_eq!(
+            geom.fromto,
+            Some(na::Vector6::new(0.0, 0.0, 0.0, 0.0, 0.0, 1.0))
+        );
+        assert_eq!(geom.group, Some(2));
+        assert_eq!(geom.rgba, Some(na::Vector4::new(1.0, 0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn fromto_and_pos_are_exclusive() {
+        let xml = "<geom type=\"capsule\" size=\"0.1\" fromto=\"0 0 0 0 0 1\" pos=\"1 0 0\"/>";
+
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let root = doc.root_element();
+
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        assert_eq!(
+            parse_geom_node::<f32>(&mut ctx, &root).unwrap_err(),
+            GeomError::MultiplePositions
+        );
+    }
+
+    #[test]
+    fn mesh_geom_needs_a_mesh() {
+        let xml = "<geom type=\"mesh\" mesh=\"link\" contype=\"0\" conaffinity=\"0\"/>";
+
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let root = doc.root_element();
+
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let geom = parse_geom_node::<f32>(&mut ctx, &root).unwrap();
+        assert_eq!(geom.mesh.as_ref().map(String::as_str), Some("link"));
+        // visual only geoms are supported
+        assert_eq!(
+            ctx.report.warning_count(WarningCode::UnsupportedAttribute),
+            0
+        );
+        assert_eq!(ctx.report.warning_count(WarningCode::ApproximatedShape), 1);
+
+        let xml = "<geom type=\"mesh\"/>";
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+        assert_eq!(
+            parse_geom_node::<f32>(&mut ctx, &doc.root_element()).unwrap_err(),
+            GeomError::RequiredAttributeMissing(String::from("mesh"))
+        );
+    }
 }
@@ -1 +1,6 @@
+pub mod asset;
+pub mod body;
+pub mod custom;
 pub mod geom;
+pub mod option;
+pub mod raw;
@@ -0,0 +1,164 @@
+use crate::attributes;
+use crate::context::ParseContext;
+use crate::model::{Angle, Compiler, SimOption};
+use crate::report::WarningCode;
+use crate::schema;
+use crate::tags;
+use failure::Fail;
+use nalgebra as na;
+use roxmltree;
+#[allow(unused_imports)]
+use slog::{debug, error, info, trace, warn};
+use std::str::FromStr;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum OptionError {
+    #[fail(display = "Bad attribute values. {}", 0)]
+    BadRealAttribute(#[fail(cause)] attributes::ParseRealAttributeError),
+    #[fail(display = "Invalid angle unit {}", 0)]
+    InvalidAngle(String),
+}
+
+impl From<attributes::ParseRealAttributeError> for OptionError {
+    fn from(error: attributes::ParseRealAttributeError) -> OptionError {
+        OptionError::BadRealAttribute(error)
+    }
+}
+
+/// Parse the `<compiler>` tag
+pub fn parse_compiler_node(
+    ctx: &mut ParseContext,
+    compiler_node: &roxmltree::Node,
+) -> Result<Compiler, OptionError> {
+    let mut compiler = Compiler::default();
+    for attribute in compiler_node.attributes() {
+        match attribute.name() {
+            "angle" => {
+                compiler.angle =
+                    Angle::from_str(attribute.value()).map_err(OptionError::InvalidAngle)?
+            }
+            "meshdir" => compiler.meshdir = Some(attribute.value().to_string()),
+            "texturedir" => compiler.texturedir = Some(attribute.value().to_string()),
+            name => ctx.warn(
+                WarningCode::UnsupportedAttribute,
+                compiler_node,
+                format!("compiler {} attribute is currently unsupported", name),
+            ),
+        }
+    }
+    compiler.raw_attributes = tags::raw::parse_raw_attributes(
+        compiler_node,
+        &schema::COMPILER,
+        &["angle", "meshdir", "texturedir"],
+    );
+    Ok(compiler)
+}
+
+/// Parse the `<option>` tag
+pub fn parse_option_node<N: na::RealField>(
+    ctx: &mut ParseContext,
+    option_node: &roxmltree::Node,
+) -> Result<SimOption<N>, OptionError>
+where
+    N: FromStr,
+{
+    let mut option = SimOption::default();
+    for attribute in option_node.attributes() {
+        match attribute.name() {
+            "timestep" => {
+                option.timestep = Some(attributes::parse_real_attribute(attribute.value())?)
+            }
+            "gravity" => {
+                option.gravity = Some(attributes::parse_real_vector_attribute(attribute.value())?)
+            }
+            "density" => {
+                option.density = Some(attributes::parse_real_attribute(attribute.value())?)
+            }
+            "viscosity" => {
+                option.viscosity = Some(attributes::parse_real_attribute(attribute.value())?)
+            }
+            name => ctx.warn(
+                WarningCode::UnsupportedAttribute,
+                option_node,
+                format!("option {} attribute is currently unsupported", name),
+            ),
+        }
+    }
+
+    for attribute in ["density", "viscosity"].iter() {
+        if option_node.has_attribute(*attribute) {
+            ctx.warn(
+                WarningCode::UnsupportedAttribute,
+                option_node,
+                format!(
+                    "option {} attribute has no effect on the built world",
+                    attribute
+                ),
+            );
+        }
+    }
+    option.raw_attributes = tags::raw::parse_raw_attributes(
+        option_node,
+        &schema::OPTION,
+        &["timestep", "gravity", "density", "viscosity"],
+    );
+    for child in option_node.children().filter(|child| child.is_element()) {
+        let tag = child.tag_name().name();
+        ctx.warn(
+            WarningCode::IgnoredTag,
+            &child,
+            format!("{} tag is not currently supported", tag),
+        );
+        if schema::OPTION.child(tag).is_some() {
+            option.raw_children.push(tags::raw::parse_raw_node(&child));
+        }
+    }
+
+    Ok(option)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::location::Source;
+    use crate::options::ParseOptions;
+
+    #[test]
+    fn parse_option() {
+        let xml = "<option timestep=\"0.01\" gravity=\"0 0 -1\" integrator=\"RK4\"><flag contact=\"disable\"/></option>";
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let option = parse_option_node::<f32>(&mut ctx, &doc.root_element()).unwrap();
+        assert_eq!(option.timestep, Some(0.01));
+        assert_eq!(option.gravity, Some(na::Vector3::new(0.0, 0.0, -1.0)));
+        assert_eq!(
+            ctx.report.warning_count(WarningCode::UnsupportedAttribute),
+            1
+        );
+        assert_eq!(ctx.report.warning_count(WarningCode::IgnoredTag), 1);
+        // kept so they are written back
+        assert_eq!(
+            option.raw_attributes,
+            vec![(String::from("integrator"), String::from("RK4"))]
+        );
+        assert_eq!(option.raw_children[0].attribute("contact"), Some("disable"));
+    }
+
+    #[test]
+    fn parse_compiler() {
+        let xml = "<compiler angle=\"radian\" meshdir=\"meshes\"/>";
+        let doc = roxmltree::Document::parse(xml).unwrap();
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(Source::new(None, xml), &options);
+
+        let compiler = parse_compiler_node(&mut ctx, &doc.root_element()).unwrap();
+        assert_eq!(compiler.angle, Angle::Radian);
+        assert_eq!(
+            compiler.meshdir.as_ref().map(String::as_str),
+            Some("meshes")
+        );
+        assert!(ctx.report.is_clean());
+    }
+}
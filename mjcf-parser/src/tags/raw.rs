@@ -0,0 +1,82 @@
+use crate::model::RawElement;
+use crate::schema::ElementSchema;
+use roxmltree;
+
+/// Attributes that set an orientation, all of which are parsed into
+/// a quaternion
+pub const ORIENTATION_ATTRIBUTES: [&str; 5] = ["quat", "axisangle", "xyaxes", "zaxis", "euler"];
+
+/// Copy an element and its descendants as they were written
+pub fn parse_raw_node(node: &roxmltree::Node) -> RawElement {
+    RawElement {
+        tag: node.tag_name().name().to_string(),
+        attributes: node
+            .attributes()
+            .iter()
+            .map(|attribute| (attribute.name().to_string(), attribute.value().to_string()))
+            .collect(),
+        children: node
+            .children()
+            .filter(|child| child.is_element())
+            .map(|child| parse_raw_node(&child))
+            .collect(),
+    }
+}
+
+/// Copy the attributes of an element that the model has no field
+/// for, so they are written back when the model is saved.
+///
+/// `modeled` lists the attributes that are parsed into fields.
+/// Attributes that are not valid according to the schema were already
+/// reported, and are left out.
+pub fn parse_raw_attributes(
+    node: &roxmltree::Node,
+    schema: &ElementSchema,
+    modeled: &[&str],
+) -> Vec<(String, String)> {
+    node.attributes()
+        .iter()
+        .filter(|attribute| {
+            !modeled.contains(&attribute.name())
+                && !ORIENTATION_ATTRIBUTES.contains(&attribute.name())
+        })
+        .filter(|attribute| {
+            schema
+                .attribute(attribute.name())
+                .map_or(false, |attribute_schema| {
+                    attribute_schema.value.accepts(attribute.value())
+                })
+        })
+        .map(|attribute| (attribute.name().to_string(), attribute.value().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema;
+
+    #[test]
+    fn copies_nested_elements() {
+        let xml =
+            "<actuator><motor name=\"m\" joint=\"j\" gear=\"10\"/><!-- comment --></actuator>";
+        let doc = roxmltree::Document::parse(xml).unwrap();
+
+        let raw = parse_raw_node(&doc.root_element());
+        assert_eq!(raw.tag, "actuator");
+        assert_eq!(raw.children.len(), 1);
+        assert_eq!(raw.children[0].attribute("gear"), Some("10"));
+    }
+
+    #[test]
+    fn copies_unmodeled_valid_attributes() {
+        let xml = "<geom name=\"g\" euler=\"0 0 1\" solref=\"0.02 1\" priority=\"x\" typo=\"1\"/>";
+        let doc = roxmltree::Document::parse(xml).unwrap();
+
+        let attributes = parse_raw_attributes(&doc.root_element(), &schema::GEOM, &["name"]);
+        assert_eq!(
+            attributes,
+            vec![(String::from("solref"), String::from("0.02 1"))]
+        );
+    }
+}
@@ -0,0 +1,554 @@
+//! Writers for the frames of a `Simulation`.
+//!
+//! - CSV has one row per step, with the position, orientation and
+//!   velocities of each body, each sensor value, the number of
+//!   contacts and the contacts themselves. Columns are named e.g.
+//!   `arm.x`, `arm.qw`, `arm.vx`, `arm.wx` and `tip_pos[0]`. See
+//!   `TrajectoryWriter::write_csv_frame` for the contact encoding.
+//! - JSON Lines has one object per step, including every contact.
+//! - The binary format starts with a header naming the bodies,
+//!   sensors and colliders, followed by one record per step. See
+//!   `TrajectoryWriter::write_binary_frame`.
+//!
+//! Orientations are quaternions in MuJoCo's `[w, x, y, z]` order.
+//! In CSV and binary frames, bodies and sensors that could not be
+//! read, e.g. because their body was removed from the world, are NaN,
+//! so every frame matches the header.
+
+use crate::simulation::{to_f64, Frame, Simulation};
+use nalgebra as na;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Identifies binary trajectory files
+pub const BINARY_MAGIC: &[u8; 4] = b"MJTR";
+
+/// Version of the binary format
+pub const BINARY_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TrajectoryFormat {
+    Csv,
+    JsonLines,
+    Binary,
+}
+
+impl TrajectoryFormat {
+    /// Format of a trajectory file, from its extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<TrajectoryFormat> {
+        let extension = path.as_ref().extension()?.to_string_lossy().to_lowercase();
+        extension.parse().ok()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrajectoryFormat::Csv => "csv",
+            TrajectoryFormat::JsonLines => "jsonl",
+            TrajectoryFormat::Binary => "bin",
+        }
+    }
+}
+
+impl FromStr for TrajectoryFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text {
+            "csv" => Ok(TrajectoryFormat::Csv),
+            "jsonl" | "ndjson" => Ok(TrajectoryFormat::JsonLines),
+            "bin" => Ok(TrajectoryFormat::Binary),
+            _ => Err(text.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TrajectoryFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Writes the frames of one simulation in a trajectory format
+pub struct TrajectoryWriter<W: Write> {
+    output: W,
+    format: TrajectoryFormat,
+    /// Bodies in the header, in order
+    body_names: Vec<String>,
+    /// Sensors in the header, in order, with their number of values
+    sensor_dimensions: Vec<(String, usize)>,
+    /// Index of each collider name in the binary header
+    collider_indices: HashMap<String, u32>,
+}
+
+impl<W: Write> TrajectoryWriter<W> {
+    /// Create a writer for the frames of `simulation`, writing the
+    /// header of the format right away
+    pub fn new<N>(
+        output: W,
+        format: TrajectoryFormat,
+        simulation: &Simulation<N>,
+    ) -> io::Result<Self>
+    where
+        N: na::RealField + From<f32> + FromStr,
+        <N as FromStr>::Err: fmt::Display,
+    {
+        let mut collider_names: Vec<String> = simulation
+            .world()
+            .colliders()
+            .map(|collider| collider.name().to_string())
+            .collect();
+        collider_names.sort();
+        collider_names.dedup();
+
+        let mut writer = TrajectoryWriter {
+            output,
+            format,
+            body_names: simulation
+                .body_names()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            sensor_dimensions: simulation
+                .sensor_dimensions()
+                .into_iter()
+                .map(|(name, dimension)| (name.to_string(), dimension))
+                .collect(),
+            collider_indices: collider_names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (name.clone(), index as u32))
+                .collect(),
+        };
+        match format {
+            TrajectoryFormat::Csv => writer.write_csv_header(simulation)?,
+            TrajectoryFormat::JsonLines => {}
+            TrajectoryFormat::Binary => writer.write_binary_header(simulation, &collider_names)?,
+        }
+        Ok(writer)
+    }
+
+    pub fn write_frame<N: na::RealField>(&mut self, frame: &Frame<N>) -> io::Result<()> {
+        match self.format {
+            TrajectoryFormat::Csv => self.write_csv_frame(frame),
+            TrajectoryFormat::JsonLines => self.write_json_frame(frame),
+            TrajectoryFormat::Binary => self.write_binary_frame(frame),
+        }
+    }
+
+    /// Flush the output and return it
+    pub fn finish(mut self) -> io::Result<W> {
+        self.output.flush()?;
+        Ok(self.output)
+    }
+
+    fn write_csv_header<N>(&mut self, simulation: &Simulation<N>) -> io::Result<()>
+    where
+        N: na::RealField + From<f32> + FromStr,
+        <N as FromStr>::Err: fmt::Display,
+    {
+        let mut columns = vec![String::from("step"), String::from("time")];
+        for body in simulation.body_names() {
+            for value in &[
+                "x", "y", "z", "qw", "qx", "qy", "qz", "vx", "vy", "vz", "wx", "wy", "wz",
+            ] {
+                columns.push(format!("{}.{}", body, value));
+            }
+        }
+        for (sensor, dimension) in simulation.sensor_dimensions() {
+            for index in 0..dimension {
+                columns.push(format!("{}[{}]", sensor, index));
+            }
+        }
+        columns.push(String::from("contacts"));
+        columns.push(String::from("contact_list"));
+        writeln!(self.output, "{}", columns.join(","))
+    }
+
+    /// Write a frame as a CSV row.
+    ///
+    /// The `contact_list` column holds the contacts separated by `;`.
+    /// Each contact is its two collider names, point, normal and
+    /// depth separated by `:`, e.g. `floor:ball:0:0:0:0:0:1:0.001`.
+    /// The column is quoted when a collider name needs it.
+    pub fn write_csv_frame<N: na::RealField>(&mut self, frame: &Frame<N>) -> io::Result<()> {
+        let mut values = vec![frame.step.to_string(), to_f64(frame.time).to_string()];
+        values.extend(self.aligned_values(frame).iter().map(ToString::to_string));
+        values.push(frame.contacts.len().to_string());
+        let contacts: Vec<String> = frame
+            .contacts
+            .iter()
+            .map(|contact| {
+                let mut fields = vec![contact.collider1.clone(), contact.collider2.clone()];
+                let numbers = contact
+                    .point
+                    .coords
+                    .iter()
+                    .chain(contact.normal.iter())
+                    .chain(std::iter::once(&contact.depth));
+                fields.extend(numbers.map(|value| to_f64(*value).to_string()));
+                fields.join(":")
+            })
+            .collect();
+        values.push(csv_field(&contacts.join(";")));
+        writeln!(self.output, "{}", values.join(","))
+    }
+
+    /// Values of the bodies and sensors of the header, in order, with
+    /// NaN for those missing from the frame
+    fn aligned_values<N: na::RealField>(&self, frame: &Frame<N>) -> Vec<f64> {
+        // the frame's bodies and sensors are in the header's order,
+        // some may only be left out
+        let mut values = vec![];
+        let mut bodies = frame.bodies.iter().peekable();
+        for name in &self.body_names {
+            match bodies.peek() {
+                Some(body) if body.name == *name => {
+                    values.extend(body_values(body).iter());
+                    bodies.next();
+                }
+                _ => values.extend([std::f64::NAN; 13].iter()),
+            }
+        }
+        let mut sensors = frame.sensors.iter().peekable();
+        for (name, dimension) in &self.sensor_dimensions {
+            match sensors.peek() {
+                Some(sensor) if sensor.name == *name && sensor.values.len() == *dimension => {
+                    values.extend(sensor.values.iter().map(|value| to_f64(*value)));
+                    sensors.next();
+                }
+                _ => values.extend(std::iter::repeat(std::f64::NAN).take(*dimension)),
+            }
+        }
+        values
+    }
+
+    fn write_json_frame<N: na::RealField>(&mut self, frame: &Frame<N>) -> io::Result<()> {
+        let bodies: Vec<_> = frame
+            .bodies
+            .iter()
+            .map(|body| {
+                let values = body_values(body);
+                json!({
+                    "name": body.name,
+                    "position": &values[0..3],
+                    "orientation": &values[3..7],
+                    "linear_velocity": &values[7..10],
+                    "angular_velocity": &values[10..13],
+                })
+            })
+            .collect();
+        let contacts: Vec<_> = frame
+            .contacts
+            .iter()
+            .map(|contact| {
+                json!({
+                    "collider1": contact.collider1,
+                    "collider2": contact.collider2,
+                    "point": vector_values(contact.point.coords.iter()),
+                    "normal": vector_values(contact.normal.iter()),
+                    "depth": to_f64(contact.depth),
+                })
+            })
+            .collect();
+        let sensors: serde_json::Map<String, serde_json::Value> = frame
+            .sensors
+            .iter()
+            .map(|sensor| {
+                (
+                    sensor.name.clone(),
+                    json!(vector_values(sensor.values.iter())),
+                )
+            })
+            .collect();
+
+        let line = json!({
+            "step": frame.step,
+            "time": to_f64(frame.time),
+            "bodies": bodies,
+            "contacts": contacts,
+            "sensors": sensors,
+        });
+        serde_json::to_writer(&mut self.output, &line)?;
+        writeln!(self.output)
+    }
+
+    fn write_binary_header<N>(
+        &mut self,
+        simulation: &Simulation<N>,
+        collider_names: &[String],
+    ) -> io::Result<()>
+    where
+        N: na::RealField + From<f32> + FromStr,
+        <N as FromStr>::Err: fmt::Display,
+    {
+        self.output.write_all(BINARY_MAGIC)?;
+        self.write_u32(BINARY_VERSION)?;
+
+        let body_names = simulation.body_names();
+        self.write_u32(body_names.len() as u32)?;
+        for name in body_names {
+            self.write_string(name)?;
+        }
+        let sensors = simulation.sensor_dimensions();
+        self.write_u32(sensors.len() as u32)?;
+        for (name, dimension) in sensors {
+            self.write_string(name)?;
+            self.write_u32(dimension as u32)?;
+        }
+        self.write_u32(collider_names.len() as u32)?;
+        for name in collider_names {
+            self.write_string(name)?;
+        }
+        Ok(())
+    }
+
+    /// Write a frame as:
+    ///
+    /// - the step, as a `u64`, and the time, as an `f64`
+    /// - for each body in the header, 13 `f32`s: position,
+    ///   orientation, linear and angular velocity, NaN when the body
+    ///   is missing from the frame
+    /// - for each sensor in the header, its values as `f32`s, NaN when
+    ///   the sensor could not be read
+    /// - the number of contacts, as a `u32`, then for each contact the
+    ///   header indices of its colliders, as `u32`s, and 7 `f32`s:
+    ///   point, normal and depth
+    ///
+    /// Like the header, every number is little endian. Strings in the
+    /// header are a `u32` length followed by UTF-8 bytes.
+    pub fn write_binary_frame<N: na::RealField>(&mut self, frame: &Frame<N>) -> io::Result<()> {
+        self.output.write_all(&(frame.step as u64).to_le_bytes())?;
+        self.output
+            .write_all(&to_f64(frame.time).to_bits().to_le_bytes())?;
+        for value in self.aligned_values(frame) {
+            self.write_f32(value)?;
+        }
+
+        self.write_u32(frame.contacts.len() as u32)?;
+        for contact in &frame.contacts {
+            for collider in &[&contact.collider1, &contact.collider2] {
+                let index = self
+                    .collider_indices
+                    .get(collider.as_str())
+                    .cloned()
+                    .unwrap_or(std::u32::MAX);
+                self.write_u32(index)?;
+            }
+            let values = contact
+                .point
+                .coords
+                .iter()
+                .chain(contact.normal.iter())
+                .chain(std::iter::once(&contact.depth));
+            for value in values {
+                self.write_f32(to_f64(*value))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_u32(&mut self, value: u32) -> io::Result<()> {
+        self.output.write_all(&value.to_le_bytes())
+    }
+
+    fn write_f32(&mut self, value: f64) -> io::Result<()> {
+        self.output
+            .write_all(&(value as f32).to_bits().to_le_bytes())
+    }
+
+    fn write_string(&mut self, text: &str) -> io::Result<()> {
+        self.write_u32(text.len() as u32)?;
+        self.output.write_all(text.as_bytes())
+    }
+}
+
+/// A CSV field, quoted when it contains a separator or a quote
+fn csv_field(text: &str) -> String {
+    if text.contains(|c| c == ',' || c == '"' || c == '\n') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn vector_values<'v, N: na::RealField, I: Iterator<Item = &'v N>>(values: I) -> Vec<f64> {
+    values.map(|value| to_f64(*value)).collect()
+}
+
+/// Position, orientation, linear and angular velocity of a body
+fn body_values<N: na::RealField>(body: &crate::simulation::BodyState<N>) -> [f64; 13] {
+    let quat = body.orientation.quaternion();
+    let mut values = [0.0; 13];
+    let all = body
+        .position
+        .iter()
+        .chain(&[quat.w, quat.i, quat.j, quat.k])
+        .chain(body.linear_velocity.iter())
+        .chain(body.angular_velocity.iter());
+    for (slot, value) in values.iter_mut().zip(all) {
+        *slot = to_f64(*value);
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ContactState;
+    use crate::MJCFModelDesc;
+
+    const BALL_XML: &str = "<mujoco>
+  <worldbody>
+    <geom name=\"floor\" type=\"plane\" size=\"5 5 0.1\"/>
+    <body name=\"ball\" pos=\"0 0 1\">
+      <freejoint/>
+      <geom name=\"ball\" size=\"0.1\"/>
+    </body>
+  </worldbody>
+  <sensor><framepos name=\"ball_pos\" objtype=\"body\" objname=\"ball\"/></sensor>
+</mujoco>";
+
+    fn record(format: TrajectoryFormat, steps: usize) -> Vec<u8> {
+        let mut model_desc = MJCFModelDesc::<f32>::parse_xml_string(BALL_XML).unwrap();
+        let mut simulation = Simulation::new(&mut model_desc);
+        let mut writer = TrajectoryWriter::new(vec![], format, &simulation).unwrap();
+        for _ in 0..steps {
+            simulation.step();
+            writer.write_frame(&simulation.frame()).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn format_from_extension() {
+        assert_eq!(
+            TrajectoryFormat::from_path("run/poses.JSONL"),
+            Some(TrajectoryFormat::JsonLines)
+        );
+        assert_eq!(
+            TrajectoryFormat::from_path("poses.bin"),
+            Some(TrajectoryFormat::Binary)
+        );
+        assert_eq!(TrajectoryFormat::from_path("poses.txt"), None);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_step() {
+        let csv = String::from_utf8(record(TrajectoryFormat::Csv, 3)).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        let columns: Vec<&str> = lines[0].split(',').collect();
+        assert_eq!(&columns[..4], &["step", "time", "ball.x", "ball.y"]);
+        assert_eq!(
+            &columns[columns.len() - 5..],
+            &[
+                "ball_pos[0]",
+                "ball_pos[1]",
+                "ball_pos[2]",
+                "contacts",
+                "contact_list"
+            ]
+        );
+        for line in &lines[1..] {
+            assert_eq!(line.split(',').count(), columns.len());
+        }
+        assert!(lines[3].starts_with("3,"));
+    }
+
+    #[test]
+    fn json_lines_are_objects() {
+        let jsonl = String::from_utf8(record(TrajectoryFormat::JsonLines, 2)).unwrap();
+        let frames: Vec<serde_json::Value> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1]["step"], 2);
+        assert_eq!(frames[1]["bodies"][0]["name"], "ball");
+        assert_eq!(
+            frames[1]["bodies"][0]["orientation"]
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+        assert_eq!(
+            frames[1]["sensors"]["ball_pos"].as_array().unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn binary_frames_have_a_fixed_size_without_contacts() {
+        let bytes = record(TrajectoryFormat::Binary, 2);
+        assert_eq!(&bytes[0..4], BINARY_MAGIC);
+
+        // magic, version, 1 body, 1 sensor with 3 values, and the
+        // "ball" and "floor" colliders
+        let header = 4 + 4 + (4 + 4 + 4) + (4 + 4 + 8 + 4) + (4 + 4 + 4 + 4 + 5);
+        // step, time, body values, sensor values, contact count
+        let frame = 8 + 8 + 13 * 4 + 3 * 4 + 4;
+        assert_eq!(bytes.len(), header + 2 * frame);
+    }
+
+    #[test]
+    fn csv_rows_keep_the_header_width() {
+        let mut model_desc = MJCFModelDesc::<f32>::parse_xml_string(BALL_XML).unwrap();
+        let simulation = Simulation::new(&mut model_desc);
+        let mut writer = TrajectoryWriter::new(vec![], TrajectoryFormat::Csv, &simulation).unwrap();
+
+        // the ball's body and the sensor on it are missing
+        let mut frame = simulation.frame();
+        frame.bodies.clear();
+        frame.sensors.clear();
+        frame.contacts = vec![ContactState {
+            collider1: String::from("floor"),
+            collider2: String::from("ball, left"),
+            point: na::Point3::new(0.0, 0.0, 0.0),
+            normal: na::Vector3::z(),
+            depth: 0.5,
+        }];
+        writer.write_frame(&frame).unwrap();
+
+        let csv = String::from_utf8(writer.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        let columns = lines[0].split(',').count();
+        let row = lines[1];
+        assert!(row.ends_with(",1,\"floor:ball, left:0:0:0:0:0:1:0.5\""));
+        // without the quoted contact list, the row has one value per
+        // column but the last
+        let values: Vec<&str> = row[..row.rfind(",\"").unwrap()].split(',').collect();
+        assert_eq!(values.len(), columns - 1);
+        assert!(values[2..columns - 2].iter().all(|value| *value == "NaN"));
+    }
+
+    #[test]
+    fn binary_frames_keep_the_header_size() {
+        let mut model_desc = MJCFModelDesc::<f32>::parse_xml_string(BALL_XML).unwrap();
+        let simulation = Simulation::new(&mut model_desc);
+        let header = TrajectoryWriter::new(vec![], TrajectoryFormat::Binary, &simulation)
+            .unwrap()
+            .finish()
+            .unwrap()
+            .len();
+        let mut writer =
+            TrajectoryWriter::new(vec![], TrajectoryFormat::Binary, &simulation).unwrap();
+
+        let mut frame = simulation.frame();
+        frame.bodies.clear();
+        writer.write_frame(&frame).unwrap();
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes.len(), header + 8 + 8 + 13 * 4 + 3 * 4 + 4);
+        let x = f32::from_bits(u32::from_le_bytes([
+            bytes[header + 16],
+            bytes[header + 17],
+            bytes[header + 18],
+            bytes[header + 19],
+        ]));
+        assert!(x.is_nan());
+    }
+}
@@ -0,0 +1,841 @@
+//! Import of URDF robot descriptions into the typed model.
+//!
+//! Links become bodies and joints place each child link in its
+//! parent, so a URDF robot compiles and builds exactly like an MJCF
+//! model:
+//!
+//! - root links are welded to the world, as MuJoCo does. A root link
+//!   named `world` is the world body itself, so a `floating` joint
+//!   from it frees the robot.
+//! - `revolute` and `continuous` joints become hinges, `prismatic`
+//!   joints slides and `floating` joints free joints. `fixed` joints
+//!   weld their child, and so do `planar` joints, which have no
+//!   equivalent.
+//! - `collision` elements become geoms, and `visual` elements geoms
+//!   that collide with nothing, in group 1.
+//!
+//! Mesh filenames are kept as written, except for `file://` and
+//! `package://` URIs. The directories of ROS packages are set with
+//! `ParseOptions::package_path`.
+
+use crate::attributes;
+use crate::context::ParseContext;
+use crate::error::{MJCFParseError, MJCFParseErrorKind, MJCFParseResult};
+use crate::location::Source;
+use crate::model::{Angle, Body, Geom, GeomType, Inertial, Joint, JointType, Material, Model};
+use crate::options::ParseOptions;
+use crate::parse::{first_error, read_model_file};
+use crate::report::{ParseReport, WarningCode};
+use failure::Fail;
+use na::RealField;
+use nalgebra as na;
+use roxmltree;
+use slog::debug;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Clone, PartialEq, Debug, Fail)]
+pub enum UrdfError {
+    #[fail(
+        display = "Required attribute \"{}\" missing on {} tag",
+        attribute, tag
+    )]
+    RequiredAttributeMissing { tag: String, attribute: String },
+    #[fail(display = "Bad attribute values. {}", 0)]
+    BadRealAttribute(#[fail(cause)] attributes::ParseRealAttributeError),
+    #[fail(display = "Invalid joint type {}", 0)]
+    InvalidJointType(String),
+    #[fail(display = "{} tag needs a geometry", 0)]
+    MissingGeometry(String),
+    #[fail(display = "Invalid geometry {}", 0)]
+    InvalidGeometry(String),
+    #[fail(display = "Duplicate link name \"{}\"", 0)]
+    DuplicateLink(String),
+    #[fail(display = "Joint {} refers to unknown link \"{}\"", joint, link)]
+    UnknownLink { joint: String, link: String },
+    #[fail(display = "Link \"{}\" is the child of more than one joint", 0)]
+    MultipleParents(String),
+    #[fail(display = "Link \"{}\" is part of a kinematic loop", 0)]
+    KinematicLoop(String),
+    #[fail(
+        display = "Mesh \"{}\" is in package \"{}\", which has no path in the parse options",
+        filename, package
+    )]
+    UnknownPackage { filename: String, package: String },
+}
+
+impl From<attributes::ParseRealAttributeError> for UrdfError {
+    fn from(error: attributes::ParseRealAttributeError) -> UrdfError {
+        UrdfError::BadRealAttribute(error)
+    }
+}
+
+/// Name of the link that stands for the world
+const WORLD_LINK_NAME: &str = "world";
+
+impl<N: RealField> Model<N>
+where
+    N: From<f32>,
+    N: FromStr,
+    <N as FromStr>::Err: std::fmt::Display,
+{
+    /// Parse a URDF XML string with the default options, failing on
+    /// the first error.
+    pub fn parse_urdf_string(text: &str) -> MJCFParseResult<Model<N>> {
+        first_error(Self::parse_urdf_string_with_report(
+            text,
+            &ParseOptions::default(),
+        )?)
+    }
+
+    /// Parse a URDF XML string, collecting every warning and
+    /// recoverable error.
+    pub fn parse_urdf_string_with_report(
+        text: &str,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(Model<N>, ParseReport)> {
+        parse_urdf_source(Source::new(None, text), options)
+    }
+
+    /// Read and parse a URDF file, collecting every warning and
+    /// recoverable error.
+    ///
+    /// The file is read with the options' file resolver.
+    pub fn parse_urdf_file_with_report<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+    ) -> MJCFParseResult<(Model<N>, ParseReport)> {
+        let path = path.as_ref();
+        let text = read_model_file(path, options)?;
+        parse_urdf_source(Source::new(Some(path), &text), options)
+    }
+}
+
+/// A joint, placing its child link in its parent's frame
+struct UrdfJoint<N: RealField> {
+    name: String,
+    parent: String,
+    child: String,
+    origin: na::Isometry3<N>,
+    /// `None` for joints that weld the child to its parent
+    joint: Option<Joint<N>>,
+}
+
+fn parse_urdf_source<N: RealField>(
+    source: Source,
+    options: &ParseOptions,
+) -> MJCFParseResult<(Model<N>, ParseReport)>
+where
+    N: FromStr,
+{
+    let mut ctx = ParseContext::new(source, options);
+    let mut model = Model::default();
+
+    debug!(ctx.logger, "Parsing URDF string");
+    let doc = match roxmltree::Document::parse(source.text) {
+        Ok(doc) => doc,
+        Err(error) => {
+            return Err(
+                MJCFParseError::from(MJCFParseErrorKind::BadXML(format!("{}", error)))
+                    .with_location(source.location_at(error.pos())),
+            );
+        }
+    };
+
+    let root = doc.root_element();
+    if !root.has_tag_name("robot") {
+        return Err(
+            MJCFParseError::from(MJCFParseErrorKind::MissingRequiredTag {
+                tag_name: String::from("robot"),
+            })
+            .with_location(source.location(&root)),
+        );
+    }
+
+    model.name = root.attribute("name").map(str::to_string);
+    // URDF angles are always in radians
+    model.compiler.angle = Angle::Radian;
+    ctx.angle = Angle::Radian;
+
+    // materials may be used before they are defined
+    for material_node in root.children().filter(|node| node.has_tag_name("material")) {
+        match parse_material_node(&material_node) {
+            Ok(material) => model.asset.materials.push(material),
+            Err(error) => ctx.error(&material_node, error),
+        }
+    }
+
+    let mut links: HashMap<String, Body<N>> = HashMap::new();
+    let mut link_order = vec![];
+    let mut joints = vec![];
+    for child in root.children().filter(|node| node.is_element()) {
+        match child.tag_name().name() {
+            "material" => {}
+            "link" => match parse_link_node(&mut ctx, &child, &mut model) {
+                Ok(link) => {
+                    let name = link.name.clone().unwrap_or_default();
+                    if links.contains_key(&name) {
+                        ctx.error(&child, UrdfError::DuplicateLink(name));
+                    } else {
+                        link_order.push(name.clone());
+                        links.insert(name, link);
+                    }
+                }
+                Err(error) => ctx.error(&child, error),
+            },
+            "joint" => match parse_joint_node(&mut ctx, &child) {
+                Ok(joint) => joints.push((joint, child)),
+                Err(error) => ctx.error(&child, error),
+            },
+            tag => ctx.warn(
+                WarningCode::IgnoredTag,
+                &child,
+                format!("{} tag is not currently supported", tag),
+            ),
+        }
+    }
+
+    // each link is placed by the joint it is the child of
+    let mut parent_joints: HashMap<String, usize> = HashMap::new();
+    let mut child_joints: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, (joint, joint_node)) in joints.iter().enumerate() {
+        let unknown_link = [&joint.parent, &joint.child]
+            .iter()
+            .find(|link| !links.contains_key(link.as_str()))
+            .map(|link| link.to_string());
+        if let Some(link) = unknown_link {
+            let name = joint.name.clone();
+            ctx.error(joint_node, UrdfError::UnknownLink { joint: name, link });
+            continue;
+        }
+        if parent_joints.contains_key(&joint.child) {
+            ctx.error(joint_node, UrdfError::MultipleParents(joint.child.clone()));
+            continue;
+        }
+        parent_joints.insert(joint.child.clone(), index);
+        child_joints
+            .entry(joint.parent.clone())
+            .or_default()
+            .push(index);
+    }
+
+    let joints: Vec<UrdfJoint<N>> = joints.into_iter().map(|(joint, _)| joint).collect();
+    for name in &link_order {
+        if parent_joints.contains_key(name) {
+            continue;
+        }
+        let link = attach_children(name, &mut links, &joints, &child_joints);
+        if name == WORLD_LINK_NAME {
+            model.worldbody.geoms.extend(link.geoms);
+            model.worldbody.bodies.extend(link.bodies);
+        } else {
+            model.worldbody.bodies.push(link);
+        }
+    }
+
+    // links every root misses are only reachable through a loop
+    if let Some(name) = link_order.iter().find(|name| links.contains_key(*name)) {
+        ctx.error(&root, UrdfError::KinematicLoop(name.clone()));
+    }
+
+    Ok((model, ctx.report))
+}
+
+/// Take a link out of `links`, and attach its child links to it
+fn attach_children<N: RealField>(
+    name: &str,
+    links: &mut HashMap<String, Body<N>>,
+    joints: &[UrdfJoint<N>],
+    child_joints: &HashMap<String, Vec<usize>>,
+) -> Body<N> {
+    let mut link = links.remove(name).unwrap_or_default();
+    for index in child_joints.get(name).into_iter().flatten() {
+        let joint = &joints[*index];
+        let mut child = attach_children(&joint.child, links, joints, child_joints);
+        child.pos = joint.origin.translation.vector;
+        child.quat = joint.origin.rotation;
+        child.joints.extend(joint.joint.clone());
+        link.bodies.push(child);
+    }
+    link
+}
+
+fn required_attribute<'a>(
+    node: &'a roxmltree::Node,
+    attribute: &str,
+) -> Result<&'a str, UrdfError> {
+    node.attribute(attribute)
+        .ok_or_else(|| UrdfError::RequiredAttributeMissing {
+            tag: node.tag_name().name().to_string(),
+            attribute: attribute.to_string(),
+        })
+}
+
+fn child_node<'a, 'input>(
+    node: &roxmltree::Node<'a, 'input>,
+    tag: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(tag))
+}
+
+/// Pose given by an element's `<origin>` child
+fn parse_origin<N: RealField>(node: &roxmltree::Node) -> Result<na::Isometry3<N>, UrdfError>
+where
+    N: FromStr,
+{
+    let origin_node = match child_node(node, "origin") {
+        Some(origin_node) => origin_node,
+        None => return Ok(na::Isometry3::identity()),
+    };
+    let xyz: na::Vector3<N> = match origin_node.attribute("xyz") {
+        Some(xyz) => attributes::parse_real_vector_attribute(xyz)?,
+        None => na::Vector3::zeros(),
+    };
+    let rpy: na::Vector3<N> = match origin_node.attribute("rpy") {
+        Some(rpy) => attributes::parse_real_vector_attribute(rpy)?,
+        None => na::Vector3::zeros(),
+    };
+    // roll, pitch and yaw are about the fixed x, y and z axes
+    Ok(na::Isometry3::from_parts(
+        na::Translation3::from(xyz),
+        na::UnitQuaternion::from_euler_angles(rpy[0], rpy[1], rpy[2]),
+    ))
+}
+
+fn parse_material_node(material_node: &roxmltree::Node) -> Result<Material, UrdfError> {
+    let name = required_attribute(material_node, "name")?;
+    let rgba = match child_node(material_node, "color") {
+        Some(color_node) => Some(attributes::parse_real_vector_attribute(
+            required_attribute(&color_node, "rgba")?,
+        )?),
+        None => None,
+    };
+    Ok(Material {
+        name: Some(name.to_string()),
+        rgba,
+        ..Default::default()
+    })
+}
+
+/// Parse a `<link>` into a body without children. Errors in its
+/// visual and collision elements are recorded, and the elements left
+/// out.
+fn parse_link_node<N: RealField>(
+    ctx: &mut ParseContext,
+    link_node: &roxmltree::Node,
+    model: &mut Model<N>,
+) -> Result<Body<N>, UrdfError>
+where
+    N: FromStr,
+{
+    let mut link = Body::new(required_attribute(link_node, "name")?);
+
+    for child in link_node.children().filter(|node| node.is_element()) {
+        let parsed = match child.tag_name().name() {
+            "inertial" => {
+                parse_inertial_node(&child).map(|inertial| link.inertial = Some(inertial))
+            }
+            "collision" => parse_geom_node(ctx, &child, model).map(|geom| link.geoms.push(geom)),
+            "visual" => parse_geom_node(ctx, &child, model).map(|mut geom| {
+                geom.contype = Some(0);
+                geom.conaffinity = Some(0);
+                geom.group = Some(1);
+                link.geoms.push(geom)
+            }),
+            tag => {
+                ctx.warn(
+                    WarningCode::IgnoredTag,
+                    &child,
+                    format!("{} tag is not currently supported", tag),
+                );
+                Ok(())
+            }
+        };
+        if let Err(error) = parsed {
+            ctx.error(&child, error);
+        }
+    }
+    Ok(link)
+}
+
+fn parse_inertial_node<N: RealField>(
+    inertial_node: &roxmltree::Node,
+) -> Result<Inertial<N>, UrdfError>
+where
+    N: FromStr,
+{
+    let origin = parse_origin(inertial_node)?;
+    let mass_node =
+        child_node(inertial_node, "mass").ok_or_else(|| UrdfError::RequiredAttributeMissing {
+            tag: String::from("mass"),
+            attribute: String::from("value"),
+        })?;
+    let mass = attributes::parse_real_attribute(required_attribute(&mass_node, "value")?)?;
+
+    let mut fullinertia = na::Vector6::zeros();
+    if let Some(inertia_node) = child_node(inertial_node, "inertia") {
+        for (index, attribute) in ["ixx", "iyy", "izz", "ixy", "ixz", "iyz"]
+            .iter()
+            .enumerate()
+        {
+            if let Some(value) = inertia_node.attribute(*attribute) {
+                fullinertia[index] = attributes::parse_real_attribute(value)?;
+            }
+        }
+    }
+
+    Ok(Inertial {
+        mass,
+        pos: origin.translation.vector,
+        quat: origin.rotation,
+        diaginertia: None,
+        fullinertia: Some(fullinertia),
+    })
+}
+
+/// Path of a mesh file, from a plain path or a `file://` or
+/// `package://` URI
+fn mesh_file(ctx: &ParseContext, filename: &str) -> Result<String, UrdfError> {
+    if filename.starts_with("package://") {
+        let uri_path = filename.trim_start_matches("package://");
+        let (package, file) = match uri_path.find('/') {
+            Some(index) => (&uri_path[..index], &uri_path[index + 1..]),
+            None => (uri_path, ""),
+        };
+        return match ctx.options.get_package_path(package) {
+            Some(directory) => Ok(directory.join(file).to_string_lossy().into_owned()),
+            None => Err(UrdfError::UnknownPackage {
+                filename: filename.to_string(),
+                package: package.to_string(),
+            }),
+        };
+    }
+    Ok(filename.trim_start_matches("file://").to_string())
+}
+
+/// Parse a `<collision>` or `<visual>` element, adding the meshes it
+/// refers to to the model's assets
+fn parse_geom_node<N: RealField>(
+    ctx: &mut ParseContext,
+    node: &roxmltree::Node,
+    model: &mut Model<N>,
+) -> Result<Geom<N>, UrdfError>
+where
+    N: FromStr,
+{
+    let origin = parse_origin(node)?;
+    let shape_node = child_node(node, "geometry")
+        .and_then(|geometry_node| geometry_node.children().find(|child| child.is_element()))
+        .ok_or_else(|| UrdfError::MissingGeometry(node.tag_name().name().to_string()))?;
+
+    let real = |attribute: &str| -> Result<N, UrdfError> {
+        Ok(attributes::parse_real_attribute(required_attribute(
+            &shape_node,
+            attribute,
+        )?)?)
+    };
+    let mut geom = match shape_node.tag_name().name() {
+        "box" => {
+            let size: na::Vector3<N> =
+                attributes::parse_real_vector_attribute(required_attribute(&shape_node, "size")?)?;
+            let half_extents = size / na::convert::<f64, N>(2.0);
+            Geom::new(GeomType::Box, half_extents.iter().cloned().collect())
+        }
+        "cylinder" => {
+            ctx.warn(
+                WarningCode::ApproximatedShape,
+                &shape_node,
+                format!(
+                    "cylinder is approximated by a convex hull with {} subdivisions",
+                    ctx.options.get_tesselation()
+                ),
+            );
+            let half_length = real("length")? / na::convert::<f64, N>(2.0);
+            Geom::new(GeomType::Cylinder, vec![real("radius")?, half_length])
+        }
+        "sphere" => Geom::new(GeomType::Sphere, vec![real("radius")?]),
+        "mesh" => {
+            ctx.warn(
+                WarningCode::ApproximatedShape,
+                &shape_node,
+                "mesh is approximated by its convex hull",
+            );
+            let file = mesh_file(ctx, required_attribute(&shape_node, "filename")?)?;
+            let scale = match shape_node.attribute("scale") {
+                Some(scale) => Some(attributes::parse_real_vector_attribute(scale)?),
+                None => None,
+            };
+            let mut geom = Geom::new(GeomType::Mesh, vec![]);
+            geom.mesh = Some(model.asset.add_mesh_file(&file, scale));
+            geom
+        }
+        shape => return Err(UrdfError::InvalidGeometry(shape.to_string())),
+    };
+    geom.pos = origin.translation.vector;
+    geom.quat = origin.rotation;
+
+    if let Some(material_node) = child_node(node, "material") {
+        geom.rgba = match child_node(&material_node, "color") {
+            Some(color_node) => Some(attributes::parse_real_vector_attribute(
+                required_attribute(&color_node, "rgba")?,
+            )?),
+            None => material_node.attribute("name").and_then(|name| {
+                model
+                    .asset
+                    .materials
+                    .iter()
+                    .find(|material| material.name.as_ref().map(String::as_str) == Some(name))
+                    .and_then(|material| material.rgba)
+            }),
+        };
+    }
+    Ok(geom)
+}
+
+fn parse_joint_node<N: RealField>(
+    ctx: &mut ParseContext,
+    joint_node: &roxmltree::Node,
+) -> Result<UrdfJoint<N>, UrdfError>
+where
+    N: FromStr,
+{
+    let name = required_attribute(joint_node, "name")?.to_string();
+    let link = |tag: &str| -> Result<String, UrdfError> {
+        let link_node =
+            child_node(joint_node, tag).ok_or_else(|| UrdfError::RequiredAttributeMissing {
+                tag: tag.to_string(),
+                attribute: String::from("link"),
+            })?;
+        Ok(required_attribute(&link_node, "link")?.to_string())
+    };
+    let parent = link("parent")?;
+    let child = link("child")?;
+    let origin = parse_origin(joint_node)?;
+
+    let joint_type = match required_attribute(joint_node, "type")? {
+        "revolute" | "continuous" => Some(JointType::Hinge),
+        "prismatic" => Some(JointType::Slide),
+        "floating" => Some(JointType::Free),
+        "fixed" => None,
+        "planar" => {
+            ctx.warn(
+                WarningCode::IgnoredTag,
+                joint_node,
+                format!(
+                    "planar joints are not supported, link {} is welded to its parent",
+                    child
+                ),
+            );
+            None
+        }
+        joint_type => return Err(UrdfError::InvalidJointType(joint_type.to_string())),
+    };
+
+    let joint = match joint_type {
+        Some(joint_type) => {
+            let mut joint = Joint::new(joint_type);
+            joint.name = Some(name.clone());
+            joint.axis = match child_node(joint_node, "axis").and_then(|axis| axis.attribute("xyz"))
+            {
+                Some(axis) => attributes::parse_real_vector_attribute(axis)?,
+                None => na::Vector3::x(),
+            };
+
+            // only revolute and prismatic joints have a range
+            let bounded = match joint_node.attribute("type") {
+                Some("revolute") | Some("prismatic") => true,
+                _ => false,
+            };
+            if let Some(limit_node) = child_node(joint_node, "limit").filter(|_| bounded) {
+                let bound = |attribute: &str| -> Result<N, UrdfError> {
+                    match limit_node.attribute(attribute) {
+                        Some(value) => Ok(attributes::parse_real_attribute(value)?),
+                        None => Ok(N::zero()),
+                    }
+                };
+                joint.limited = Some(true);
+                joint.range = Some(na::Vector2::new(bound("lower")?, bound("upper")?));
+            }
+
+            if let Some(dynamics_node) = child_node(joint_node, "dynamics") {
+                if let Some(damping) = dynamics_node.attribute("damping") {
+                    joint.damping = Some(attributes::parse_real_attribute(damping)?);
+                }
+                if let Some(friction) = dynamics_node.attribute("friction") {
+                    joint.frictionloss = Some(attributes::parse_real_attribute(friction)?);
+                }
+                ctx.warn(
+                    WarningCode::UnsupportedAttribute,
+                    &dynamics_node,
+                    "joint damping and friction are currently unsupported",
+                );
+            }
+            Some(joint)
+        }
+        None => None,
+    };
+
+    for tag in &["mimic", "calibration", "safety_controller"] {
+        if let Some(node) = child_node(joint_node, tag) {
+            ctx.warn(
+                WarningCode::IgnoredTag,
+                &node,
+                format!("{} tag is not currently supported", tag),
+            );
+        }
+    }
+
+    Ok(UrdfJoint {
+        name,
+        parent,
+        child,
+        origin,
+        joint,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::FileResolver;
+    use crate::MJCFModelDesc;
+    use nphysics3d::world::World;
+    use std::io;
+
+    const ARM_URDF: &str = "<robot name=\"arm\">
+  <material name=\"blue\"><color rgba=\"0 0 1 1\"/></material>
+  <link name=\"base\">
+    <inertial>
+      <mass value=\"2\"/>
+      <inertia ixx=\"0.1\" iyy=\"0.2\" izz=\"0.3\" ixy=\"0\" ixz=\"0\" iyz=\"0\"/>
+    </inertial>
+    <collision><geometry><box size=\"0.2 0.4 0.1\"/></geometry></collision>
+    <visual>
+      <geometry><mesh filename=\"meshes/base.obj\" scale=\"0.5 0.5 0.5\"/></geometry>
+      <material name=\"blue\"/>
+    </visual>
+  </link>
+  <link name=\"upper\">
+    <collision>
+      <origin xyz=\"0 0 0.25\"/>
+      <geometry><cylinder radius=\"0.05\" length=\"0.5\"/></geometry>
+    </collision>
+  </link>
+  <link name=\"lower\">
+    <collision><geometry><sphere radius=\"0.1\"/></geometry></collision>
+  </link>
+  <link name=\"tool\"/>
+  <joint name=\"shoulder\" type=\"revolute\">
+    <parent link=\"base\"/>
+    <child link=\"upper\"/>
+    <origin xyz=\"0 0 0.05\" rpy=\"0 0 1.5707963\"/>
+    <axis xyz=\"0 1 0\"/>
+    <limit lower=\"-1\" upper=\"1\" effort=\"10\" velocity=\"1\"/>
+  </joint>
+  <joint name=\"elbow\" type=\"continuous\">
+    <parent link=\"upper\"/>
+    <child link=\"lower\"/>
+    <origin xyz=\"0 0 0.5\"/>
+    <limit effort=\"10\" velocity=\"1\"/>
+  </joint>
+  <joint name=\"mount\" type=\"fixed\">
+    <parent link=\"lower\"/>
+    <child link=\"tool\"/>
+  </joint>
+  <transmission name=\"shoulder_transmission\"/>
+</robot>";
+
+    #[test]
+    fn parse_urdf_link_tree() {
+        let (model, report) =
+            Model::<f32>::parse_urdf_string_with_report(ARM_URDF, &ParseOptions::default())
+                .unwrap();
+        assert!(!report.has_errors());
+        assert_eq!(report.warning_count(WarningCode::IgnoredTag), 1);
+        assert_eq!(model.name.as_ref().map(String::as_str), Some("arm"));
+        assert_eq!(model.compiler.angle, Angle::Radian);
+
+        // the root link is welded to the world
+        let base = &model.worldbody.bodies[0];
+        assert!(base.joints.is_empty());
+        assert_eq!(base.inertial.as_ref().unwrap().mass, 2.0);
+        assert_eq!(base.geoms[0].size, vec![0.1, 0.2, 0.05]);
+        let visual = &base.geoms[1];
+        assert_eq!(visual.geom_type, GeomType::Mesh);
+        assert_eq!((visual.contype, visual.conaffinity), (Some(0), Some(0)));
+        assert_eq!(visual.rgba, Some(na::Vector4::new(0.0, 0.0, 1.0, 1.0)));
+        assert_eq!(
+            model.asset.mesh("base").unwrap().scale,
+            Some(na::Vector3::repeat(0.5))
+        );
+
+        let upper = &base.bodies[0];
+        assert_eq!(upper.name.as_ref().map(String::as_str), Some("upper"));
+        assert_eq!(upper.pos, na::Vector3::new(0.0, 0.0, 0.05));
+        let x = upper.quat * na::Vector3::x();
+        assert!((x - na::Vector3::y()).norm() < 1.0e-6);
+        let shoulder = &upper.joints[0];
+        assert_eq!(shoulder.joint_type, JointType::Hinge);
+        assert!(shoulder.is_limited());
+        assert_eq!(shoulder.range, Some(na::Vector2::new(-1.0, 1.0)));
+        assert_eq!(upper.geoms[0].size, vec![0.05, 0.25]);
+
+        let lower = &upper.bodies[0];
+        assert!(!lower.joints[0].is_limited());
+        assert_eq!(lower.joints[0].axis, na::Vector3::x());
+        let tool = &lower.bodies[0];
+        assert!(tool.joints.is_empty());
+    }
+
+    #[test]
+    fn floating_joint_from_world_frees_the_robot() {
+        let urdf = "<robot name=\"ball\">
+                      <link name=\"world\">
+                        <collision><geometry><box size=\"10 10 0.1\"/></geometry></collision>
+                      </link>
+                      <link name=\"ball\">
+                        <collision><geometry><sphere radius=\"0.1\"/></geometry></collision>
+                      </link>
+                      <joint name=\"float\" type=\"floating\">
+                        <parent link=\"world\"/>
+                        <child link=\"ball\"/>
+                        <origin xyz=\"0 0 1\"/>
+                      </joint>
+                    </robot>";
+
+        let model = Model::<f32>::parse_urdf_string(urdf).unwrap();
+        assert_eq!(model.worldbody.geoms.len(), 1);
+        assert_eq!(model.worldbody.bodies.len(), 1);
+        let ball = &model.worldbody.bodies[0];
+        assert_eq!(ball.joints[0].joint_type, JointType::Free);
+        assert_eq!(ball.pos, na::Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn invalid_link_trees_are_reported() {
+        let urdf = "<robot name=\"loop\">
+                      <link name=\"a\"/>
+                      <link name=\"a\"/>
+                      <link name=\"b\"/>
+                      <link name=\"c\"/>
+                      <joint name=\"ab\" type=\"fixed\"><parent link=\"a\"/><child link=\"b\"/></joint>
+                      <joint name=\"cb\" type=\"fixed\"><parent link=\"c\"/><child link=\"b\"/></joint>
+                      <joint name=\"ad\" type=\"fixed\"><parent link=\"a\"/><child link=\"d\"/></joint>
+                      <joint name=\"bad\" type=\"screw\"><parent link=\"a\"/><child link=\"c\"/></joint>
+                    </robot>";
+
+        let (_, report) =
+            Model::<f32>::parse_urdf_string_with_report(urdf, &ParseOptions::default()).unwrap();
+        let errors: Vec<MJCFParseErrorKind> =
+            report.errors().iter().map(MJCFParseError::kind).collect();
+        assert_eq!(
+            errors,
+            vec![
+                UrdfError::DuplicateLink(String::from("a")),
+                UrdfError::InvalidJointType(String::from("screw")),
+                UrdfError::MultipleParents(String::from("b")),
+                UrdfError::UnknownLink {
+                    joint: String::from("ad"),
+                    link: String::from("d")
+                },
+            ]
+            .into_iter()
+            .map(MJCFParseErrorKind::UrdfError)
+            .collect::<Vec<_>>()
+        );
+        for error in report.errors() {
+            assert_eq!(error.kind().code(), "invalid-urdf");
+            assert!(error.location().is_some());
+        }
+    }
+
+    #[test]
+    fn kinematic_loop_is_reported() {
+        let urdf = "<robot name=\"loop\">
+                      <link name=\"a\"/>
+                      <link name=\"b\"/>
+                      <joint name=\"ab\" type=\"fixed\"><parent link=\"a\"/><child link=\"b\"/></joint>
+                      <joint name=\"ba\" type=\"fixed\"><parent link=\"b\"/><child link=\"a\"/></joint>
+                    </robot>";
+
+        match Model::<f32>::parse_urdf_string(urdf) {
+            Err(error) => assert_eq!(
+                error.kind(),
+                MJCFParseErrorKind::UrdfError(UrdfError::KinematicLoop(String::from("a")))
+            ),
+            Ok(_) => panic!("Parsed a URDF with a kinematic loop"),
+        }
+    }
+
+    /// Serves the arm and its mesh from memory
+    struct ArmResolver;
+
+    impl FileResolver for ArmResolver {
+        fn read_to_string(&self, path: &Path) -> io::Result<String> {
+            match path.to_str() {
+                Some("robots/arm.urdf") => Ok(ARM_URDF.to_string()),
+                Some("robots/meshes/base.obj") => Ok(String::from(
+                    "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nf 1 2 3 4\n",
+                )),
+                _ => Err(io::Error::new(io::ErrorKind::NotFound, "not found")),
+            }
+        }
+    }
+
+    #[test]
+    fn urdf_builds_like_mjcf() {
+        let options = ParseOptions::new().file_resolver(ArmResolver);
+        let (mut model_desc, report) =
+            MJCFModelDesc::<f32>::load_file_with_report("robots/arm.urdf", &options).unwrap();
+        assert!(!report.has_errors());
+
+        let mut world = World::new();
+        let handles = model_desc.build(&mut world);
+        for link in &["base", "upper", "lower", "tool"] {
+            assert!(handles.body(link).is_some());
+        }
+    }
+
+    #[test]
+    fn package_meshes_are_resolved() {
+        let urdf = ARM_URDF.replace(
+            "meshes/base.obj",
+            "package://arm_description/meshes/base.obj",
+        );
+        let options = ParseOptions::new().package_path("arm_description", "/opt/arm_description");
+        let (model, report) = Model::<f32>::parse_urdf_string_with_report(&urdf, &options).unwrap();
+        assert!(!report.has_errors());
+        let file = Path::new("/opt/arm_description").join("meshes/base.obj");
+        assert_eq!(
+            model.asset.mesh("base").unwrap().file,
+            Some(file.to_string_lossy().into_owned())
+        );
+
+        // without the package's path, the visual is left out
+        let (model, report) =
+            Model::<f32>::parse_urdf_string_with_report(&urdf, &ParseOptions::default()).unwrap();
+        assert_eq!(
+            report.errors()[0].kind(),
+            MJCFParseErrorKind::UrdfError(UrdfError::UnknownPackage {
+                filename: String::from("package://arm_description/meshes/base.obj"),
+                package: String::from("arm_description"),
+            })
+        );
+        assert!(report.errors()[0].location().is_some());
+        assert_eq!(model.worldbody.bodies[0].geoms.len(), 1);
+        assert!(model.asset.meshes.is_empty());
+    }
+
+    #[test]
+    fn missing_mesh_is_reported() {
+        let options = ParseOptions::new().file_resolver(ArmResolver);
+        let urdf = ARM_URDF.replace("meshes/base.obj", "meshes/missing.obj");
+        let (model, _) = Model::<f32>::parse_urdf_string_with_report(&urdf, &options).unwrap();
+
+        match MJCFModelDesc::from_model(model, &options) {
+            Err(error) => assert_eq!(error.kind().code(), "mesh-load-failed"),
+            Ok(_) => panic!("Compiled a model with a missing mesh"),
+        }
+    }
+}
@@ -0,0 +1,774 @@
+//! Conversion of a `Model` to a URDF robot description.
+//!
+//! URDF describes a single tree of links, each placed by the one joint
+//! it is the child of, so:
+//!
+//! - worldbody geoms go on a `world` root link. A model with a single
+//!   free body and no world geoms has that body as its root instead.
+//! - bodies with several joints, and ball joints, are exported as a
+//!   chain of joints through massless links. Ball joints become three
+//!   continuous joints.
+//! - capsules are exported as a cylinder and two spheres. Ellipsoids
+//!   and planes, which URDF has no shapes for, are approximated by a
+//!   sphere and a thin box.
+//!
+//! Everything else URDF can not express, e.g. tendons, actuators,
+//! equality constraints and friction, is left out. Each is reported
+//! as a `LossyConversion` warning.
+
+use crate::model::{Body, Geom, GeomType, Inertial, Joint, JointType, Model, RawElement};
+use crate::report::{ParseReport, ParseWarning, WarningCode};
+use crate::writer::{element, list, set, set_optional, write_element, PRELUDE_SECTIONS};
+use nalgebra as na;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Name of the root link holding the worldbody's geoms
+const WORLD_LINK_NAME: &str = "world";
+
+/// Effort and velocity limits of exported joints. URDF requires them
+/// on limited joints, but MJCF joints have none.
+const UNLIMITED_EFFORT: f64 = 1.0e6;
+
+/// Range of unlimited slide joints. URDF requires a range on
+/// prismatic joints.
+const UNLIMITED_SLIDE_RANGE: f64 = 1.0e6;
+
+/// Thickness of the boxes planes are approximated by
+const PLANE_THICKNESS: f64 = 0.01;
+
+/// Half size of the boxes planes that MuJoCo renders infinitely large
+/// are approximated by
+const INFINITE_PLANE_HALF_SIZE: f64 = 100.0;
+
+impl<N: na::RealField + Display> Model<N> {
+    /// Convert the model to a URDF robot description, along with a
+    /// warning for every feature that could not be converted exactly.
+    pub fn to_urdf_string(&self) -> (String, ParseReport) {
+        let mut exporter = UrdfExporter::new(self);
+        let robot = exporter.export();
+        let mut text = String::from("<?xml version=\"1.0\"?>\n");
+        write_element(&mut text, &robot, 0);
+        (text, exporter.report)
+    }
+
+    /// Write the model to a URDF file, returning the conversion's
+    /// warnings
+    pub fn write_urdf_file<P: AsRef<Path>>(&self, path: P) -> io::Result<ParseReport> {
+        let (text, report) = self.to_urdf_string();
+        fs::write(path, text)?;
+        Ok(report)
+    }
+}
+
+/// A URDF joint, and the MJCF joint it was exported from
+struct JointStep<'m, N: na::RealField> {
+    name: String,
+    joint_type: &'static str,
+    axis: na::Vector3<N>,
+    /// Position of the joint in the body's frame
+    anchor: na::Vector3<N>,
+    joint: Option<&'m Joint<N>>,
+}
+
+struct UrdfExporter<'m, N: na::RealField> {
+    model: &'m Model<N>,
+    robot: RawElement,
+    link_names: HashSet<String>,
+    joint_names: HashSet<String>,
+    colors: usize,
+    /// Number of dropped elements of each kind, reported once all
+    /// bodies are exported
+    dropped: BTreeMap<String, usize>,
+    report: ParseReport,
+}
+
+impl<'m, N: na::RealField + Display> UrdfExporter<'m, N> {
+    fn new(model: &'m Model<N>) -> Self {
+        UrdfExporter {
+            model,
+            robot: element("robot"),
+            link_names: HashSet::new(),
+            joint_names: HashSet::new(),
+            colors: 0,
+            dropped: BTreeMap::new(),
+            report: ParseReport::default(),
+        }
+    }
+
+    fn warn<S: Into<String>>(&mut self, code: WarningCode, message: S) {
+        self.report.push_warning(ParseWarning {
+            code,
+            message: message.into(),
+            location: None,
+        });
+    }
+
+    fn drop_element(&mut self, kind: &str) {
+        *self.dropped.entry(kind.to_string()).or_insert(0) += 1;
+    }
+
+    fn export(&mut self) -> RawElement {
+        let model = self.model;
+        set(
+            &mut self.robot,
+            "name",
+            model.name.as_ref().map_or("robot", String::as_str),
+        );
+
+        let worldbody = &model.worldbody;
+        let floating_base = worldbody.geoms.is_empty()
+            && worldbody.bodies.len() == 1
+            && worldbody.bodies[0].joints.len() == 1
+            && worldbody.bodies[0].joints[0].joint_type == JointType::Free;
+        if floating_base {
+            self.add_body(&worldbody.bodies[0], None, &na::Isometry3::identity());
+        } else {
+            let world = unique_name(&mut self.link_names, WORLD_LINK_NAME);
+            let world_link = self.link_element(&world, worldbody, &na::Isometry3::identity());
+            self.robot.children.push(world_link);
+            for body in &worldbody.bodies {
+                self.add_body(body, Some(&world), &na::Isometry3::identity());
+            }
+        }
+        self.count_dropped_children(worldbody);
+
+        if model.option != Default::default() {
+            self.warn(
+                WarningCode::LossyConversion,
+                "simulation options, e.g. gravity and timestep, are not exported",
+            );
+        }
+        for section in &model.raw_sections {
+            if !PRELUDE_SECTIONS.contains(&section.tag.as_str()) {
+                self.warn(
+                    WarningCode::LossyConversion,
+                    format!("<{}> section is not exported", section.tag),
+                );
+            }
+        }
+        if !model.custom.is_empty() {
+            self.warn(
+                WarningCode::LossyConversion,
+                "<custom> data is not exported",
+            );
+        }
+        let dropped: Vec<(String, usize)> = self
+            .dropped
+            .iter()
+            .map(|(kind, count)| (kind.clone(), *count))
+            .collect();
+        for (kind, count) in dropped {
+            self.warn(
+                WarningCode::LossyConversion,
+                format!("{} are not exported ({} in the model)", kind, count),
+            );
+        }
+
+        std::mem::replace(&mut self.robot, element("robot"))
+    }
+
+    /// Add the links and joints of a body and its descendants. The
+    /// body is attached to the `parent` link, whose frame is at
+    /// `parent_offset` in the link's frame.
+    fn add_body(
+        &mut self,
+        body: &'m Body<N>,
+        parent: Option<&str>,
+        parent_offset: &na::Isometry3<N>,
+    ) {
+        let name = unique_name(
+            &mut self.link_names,
+            body.name.as_ref().map_or("body", String::as_str),
+        );
+        if body.mocap == Some(true) {
+            self.warn(
+                WarningCode::LossyConversion,
+                format!("mocap body {} is exported as a regular link", name),
+            );
+        }
+
+        let mut steps = self.joint_steps(body, &name, parent.is_none());
+        if steps.is_empty() && parent.is_some() {
+            steps.push(JointStep {
+                name: format!("{}_fixed", name),
+                joint_type: "fixed",
+                axis: na::Vector3::z(),
+                anchor: na::Vector3::zeros(),
+                joint: None,
+            });
+        }
+
+        // pose of the body frame in the frame of the link the next
+        // joint is attached to
+        let mut offset = match parent {
+            Some(_) => parent_offset * body.pose(),
+            None => na::Isometry3::identity(),
+        };
+        let mut link = parent.map(str::to_string);
+        let step_count = steps.len();
+        for (index, step) in steps.into_iter().enumerate() {
+            // the joint frame, and the frame of its child link, is at
+            // the anchor, oriented like the body
+            let anchor = na::Translation3::from(step.anchor);
+            let child = if index + 1 == step_count {
+                name.clone()
+            } else {
+                let child = unique_name(&mut self.link_names, &format!("{}_{}", name, step.name));
+                let mut child_link = element("link");
+                set(&mut child_link, "name", &child);
+                self.robot.children.push(child_link);
+                child
+            };
+            let joint =
+                self.joint_element(&step, link.as_ref().unwrap(), &child, &(offset * anchor));
+            self.robot.children.push(joint);
+            offset = na::Isometry3::from_parts(anchor.inverse(), na::UnitQuaternion::identity());
+            link = Some(child);
+        }
+
+        let link = self.link_element(&name, body, &offset);
+        self.robot.children.push(link);
+        self.count_dropped_children(body);
+        for child in &body.bodies {
+            self.add_body(child, Some(&name), &offset);
+        }
+    }
+
+    /// URDF joints a body's joints are exported as
+    fn joint_steps(
+        &mut self,
+        body: &'m Body<N>,
+        name: &str,
+        is_root: bool,
+    ) -> Vec<JointStep<'m, N>> {
+        let mut steps = vec![];
+        for (index, joint) in body.joints.iter().enumerate() {
+            let joint_name = joint
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{}_joint{}", name, index));
+            if joint.stiffness.is_some() || joint.armature.is_some() {
+                self.warn(
+                    WarningCode::LossyConversion,
+                    format!(
+                        "stiffness and armature of joint {} are not exported",
+                        joint_name
+                    ),
+                );
+            }
+            let step = |joint_type: &'static str, name: String, axis: na::Vector3<N>| JointStep {
+                name,
+                joint_type,
+                axis,
+                anchor: joint.pos,
+                joint: Some(joint),
+            };
+            match joint.joint_type {
+                JointType::Hinge if joint.is_limited() => {
+                    steps.push(step("revolute", joint_name, joint.axis))
+                }
+                JointType::Hinge => steps.push(step("continuous", joint_name, joint.axis)),
+                JointType::Slide => steps.push(step("prismatic", joint_name, joint.axis)),
+                JointType::Ball => {
+                    self.warn(
+                        WarningCode::LossyConversion,
+                        format!("ball joint {} is exported as three hinges", joint_name),
+                    );
+                    for (suffix, axis) in &[
+                        ("x", na::Vector3::x()),
+                        ("y", na::Vector3::y()),
+                        ("z", na::Vector3::z()),
+                    ] {
+                        steps.push(step(
+                            "continuous",
+                            format!("{}_{}", joint_name, suffix),
+                            *axis,
+                        ));
+                    }
+                }
+                // the root of a URDF is free by convention
+                JointType::Free if is_root => {}
+                JointType::Free => {
+                    let mut free = step("floating", joint_name, na::Vector3::z());
+                    free.anchor = na::Vector3::zeros();
+                    steps.push(free);
+                }
+            }
+        }
+        steps
+    }
+
+    fn joint_element(
+        &mut self,
+        step: &JointStep<N>,
+        parent: &str,
+        child: &str,
+        origin: &na::Isometry3<N>,
+    ) -> RawElement {
+        let mut joint_element = element("joint");
+        set(
+            &mut joint_element,
+            "name",
+            unique_name(&mut self.joint_names, &step.name),
+        );
+        set(&mut joint_element, "type", step.joint_type);
+        joint_element.children.extend(origin_element(origin));
+        let mut parent_element = element("parent");
+        set(&mut parent_element, "link", parent);
+        let mut child_element = element("child");
+        set(&mut child_element, "link", child);
+        joint_element.children.push(parent_element);
+        joint_element.children.push(child_element);
+
+        let joint = match step.joint {
+            Some(joint) => joint,
+            None => return joint_element,
+        };
+        if step.joint_type != "floating" {
+            let mut axis_element = element("axis");
+            set(&mut axis_element, "xyz", list(step.axis.iter()));
+            joint_element.children.push(axis_element);
+        }
+
+        let range = match (step.joint_type, joint.range) {
+            ("revolute", Some(range)) => {
+                Some(range.map(|bound| self.model.compiler.angle.to_radians(bound)))
+            }
+            ("prismatic", Some(range)) if joint.is_limited() => Some(range),
+            ("prismatic", _) => {
+                let bound: N = na::convert(UNLIMITED_SLIDE_RANGE);
+                Some(na::Vector2::new(-bound, bound))
+            }
+            _ => None,
+        };
+        if let Some(range) = range {
+            let mut limit_element = element("limit");
+            set(&mut limit_element, "lower", range[0]);
+            set(&mut limit_element, "upper", range[1]);
+            set(&mut limit_element, "effort", UNLIMITED_EFFORT);
+            set(&mut limit_element, "velocity", UNLIMITED_EFFORT);
+            joint_element.children.push(limit_element);
+        }
+
+        if joint.damping.is_some() || joint.frictionloss.is_some() {
+            let mut dynamics_element = element("dynamics");
+            set_optional(&mut dynamics_element, "damping", joint.damping);
+            set_optional(&mut dynamics_element, "friction", joint.frictionloss);
+            joint_element.children.push(dynamics_element);
+        }
+        joint_element
+    }
+
+    /// Link with a body's inertial and geoms, for a body whose frame
+    /// is at `offset` in the link's frame
+    fn link_element(
+        &mut self,
+        name: &str,
+        body: &Body<N>,
+        offset: &na::Isometry3<N>,
+    ) -> RawElement {
+        let mut link_element = element("link");
+        set(&mut link_element, "name", name);
+        match &body.inertial {
+            Some(inertial) => link_element
+                .children
+                .push(inertial_element(inertial, offset)),
+            None if !body.geoms.is_empty() && !std::ptr::eq(body, &self.model.worldbody) => self
+                .warn(
+                    WarningCode::LossyConversion,
+                    format!(
+                    "mass and inertia of {}, which MuJoCo infers from its geoms, are not exported",
+                    name
+                ),
+                ),
+            None => {}
+        }
+        for geom in &body.geoms {
+            link_element
+                .children
+                .extend(self.geom_elements(geom, offset));
+        }
+        link_element
+    }
+
+    /// Visual, and collision, elements of a geom
+    fn geom_elements(&mut self, geom: &Geom<N>, offset: &na::Isometry3<N>) -> Vec<RawElement> {
+        let size = |index: usize| geom.size.get(index).cloned().unwrap_or_else(N::zero);
+        let two: N = na::convert(2.0);
+        let (pose, half_length) = match geom.fromto {
+            Some(fromto) => {
+                let p0 = na::Point3::from(fromto.fixed_rows::<na::U3>(0).into_owned());
+                let p1 = na::Point3::from(fromto.fixed_rows::<na::U3>(3).into_owned());
+                let axis = p1 - p0;
+                let rotation = na::UnitQuaternion::rotation_between(&na::Vector3::z(), &axis)
+                    .unwrap_or_else(|| {
+                        na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), N::pi())
+                    });
+                (
+                    na::Isometry3::from_parts(na::center(&p0, &p1).coords.into(), rotation),
+                    axis.norm() / two,
+                )
+            }
+            None => {
+                let half_length = match geom.geom_type {
+                    GeomType::Box => size(2),
+                    _ => size(1),
+                };
+                (
+                    na::Isometry3::from_parts(geom.pos.into(), geom.quat),
+                    half_length,
+                )
+            }
+        };
+        let name = geom
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("{} geom", geom.geom_type));
+
+        let along_axis = |z: N| na::Isometry3::translation(N::zero(), N::zero(), z);
+        let shapes: Vec<(RawElement, na::Isometry3<N>)> = match geom.geom_type {
+            GeomType::Sphere => vec![(sphere_element(size(0)), na::Isometry3::identity())],
+            GeomType::Box => {
+                let half_extents = na::Vector3::new(size(0), size(1), half_length);
+                vec![(
+                    box_element(&(half_extents * two)),
+                    na::Isometry3::identity(),
+                )]
+            }
+            GeomType::Cylinder => vec![(
+                cylinder_element(size(0), half_length * two),
+                na::Isometry3::identity(),
+            )],
+            GeomType::Capsule => vec![
+                (
+                    cylinder_element(size(0), half_length * two),
+                    na::Isometry3::identity(),
+                ),
+                (sphere_element(size(0)), along_axis(half_length)),
+                (sphere_element(size(0)), along_axis(-half_length)),
+            ],
+            GeomType::Ellipsoid => {
+                let radius = (0..3)
+                    .map(&size)
+                    .fold(N::zero(), |max, radius| max.max(radius));
+                self.warn(
+                    WarningCode::ApproximatedShape,
+                    format!("ellipsoid {} is approximated by its bounding sphere", name),
+                );
+                vec![(sphere_element(radius), na::Isometry3::identity())]
+            }
+            GeomType::Plane => {
+                self.warn(
+                    WarningCode::ApproximatedShape,
+                    format!("plane {} is approximated by a thin box", name),
+                );
+                let thickness: N = na::convert(PLANE_THICKNESS);
+                let half_size = |index: usize| {
+                    if size(index) > N::zero() {
+                        size(index)
+                    } else {
+                        na::convert(INFINITE_PLANE_HALF_SIZE)
+                    }
+                };
+                let extents = na::Vector3::new(half_size(0) * two, half_size(1) * two, thickness);
+                vec![(box_element(&extents), along_axis(-thickness / two))]
+            }
+            GeomType::Mesh => match self.mesh_element(geom) {
+                Some(mesh_element) => vec![(mesh_element, na::Isometry3::identity())],
+                None => {
+                    self.warn(
+                        WarningCode::LossyConversion,
+                        format!("mesh geom {} has no mesh file, and is not exported", name),
+                    );
+                    vec![]
+                }
+            },
+            GeomType::Hfield => {
+                self.drop_element("height field geoms");
+                vec![]
+            }
+        };
+        if geom.friction.is_some() {
+            self.drop_element("geom friction coefficients");
+        }
+
+        let rgba = geom.rgba.or_else(|| {
+            geom.material
+                .as_ref()
+                .and_then(|material| self.model.asset.material(material))
+                .and_then(|material| material.rgba)
+        });
+        let collides = !(geom.contype == Some(0) && geom.conaffinity == Some(0));
+        let mut elements = vec![];
+        for (shape, shape_pose) in shapes {
+            let origin = offset * pose * shape_pose;
+            let mut geometry_element = element("geometry");
+            geometry_element.children.push(shape);
+
+            let mut visual_element = element("visual");
+            visual_element.children.extend(origin_element(&origin));
+            visual_element.children.push(geometry_element.clone());
+            if let Some(rgba) = rgba {
+                self.colors += 1;
+                let mut material_element = element("material");
+                set(
+                    &mut material_element,
+                    "name",
+                    format!("color{}", self.colors),
+                );
+                let mut color_element = element("color");
+                set(&mut color_element, "rgba", list(rgba.iter()));
+                material_element.children.push(color_element);
+                visual_element.children.push(material_element);
+            }
+            elements.push(visual_element);
+
+            if collides {
+                let mut collision_element = element("collision");
+                collision_element.children.extend(origin_element(&origin));
+                collision_element.children.push(geometry_element);
+                elements.push(collision_element);
+            }
+        }
+        elements
+    }
+
+    fn mesh_element(&self, geom: &Geom<N>) -> Option<RawElement> {
+        let mesh = self.model.asset.mesh(geom.mesh.as_ref()?)?;
+        let file = mesh.file.as_ref()?;
+        let file = match &self.model.compiler.meshdir {
+            Some(meshdir) => Path::new(meshdir).join(file).to_string_lossy().into_owned(),
+            None => file.clone(),
+        };
+        let mut mesh_element = element("mesh");
+        set(&mut mesh_element, "filename", file);
+        if let Some(scale) = &mesh.scale {
+            set(&mut mesh_element, "scale", list(scale.iter()));
+        }
+        Some(mesh_element)
+    }
+
+    /// Record the sites, cameras, lights and other elements of a body,
+    /// which URDF has no equivalent of
+    fn count_dropped_children(&mut self, body: &Body<N>) {
+        for _ in &body.sites {
+            self.drop_element("sites");
+        }
+        for raw_child in &body.raw_children {
+            self.drop_element(&format!("<{}> elements", raw_child.tag));
+        }
+    }
+}
+
+/// `name`, or `name` with a numbered suffix when it is already taken
+fn unique_name(names: &mut HashSet<String>, name: &str) -> String {
+    let mut unique = name.to_string();
+    let mut suffix = 1;
+    while names.contains(&unique) {
+        suffix += 1;
+        unique = format!("{}_{}", name, suffix);
+    }
+    names.insert(unique.clone());
+    unique
+}
+
+/// `<origin>` of a pose, left out for the identity
+fn origin_element<N: na::RealField + Display>(pose: &na::Isometry3<N>) -> Option<RawElement> {
+    if *pose == na::Isometry3::identity() {
+        return None;
+    }
+    let mut origin_element = element("origin");
+    set(
+        &mut origin_element,
+        "xyz",
+        list(pose.translation.vector.iter()),
+    );
+    let (roll, pitch, yaw) = pose.rotation.euler_angles();
+    set(&mut origin_element, "rpy", list(&[roll, pitch, yaw]));
+    Some(origin_element)
+}
+
+fn inertial_element<N: na::RealField + Display>(
+    inertial: &Inertial<N>,
+    offset: &na::Isometry3<N>,
+) -> RawElement {
+    let mut inertial_element = element("inertial");
+    let pose = offset * na::Isometry3::from_parts(inertial.pos.into(), inertial.quat);
+    inertial_element.children.extend(origin_element(&pose));
+
+    let mut mass_element = element("mass");
+    set(&mut mass_element, "value", inertial.mass);
+    inertial_element.children.push(mass_element);
+
+    // ixx, iyy, izz, ixy, ixz, iyz, as in MJCF's fullinertia
+    let inertia = match (&inertial.fullinertia, &inertial.diaginertia) {
+        (Some(fullinertia), _) => *fullinertia,
+        (None, Some(diaginertia)) => na::Vector6::new(
+            diaginertia[0],
+            diaginertia[1],
+            diaginertia[2],
+            N::zero(),
+            N::zero(),
+            N::zero(),
+        ),
+        (None, None) => na::Vector6::zeros(),
+    };
+    let mut inertia_element = element("inertia");
+    for (name, value) in ["ixx", "iyy", "izz", "ixy", "ixz", "iyz"]
+        .iter()
+        .zip(inertia.iter())
+    {
+        set(&mut inertia_element, name, value);
+    }
+    inertial_element.children.push(inertia_element);
+    inertial_element
+}
+
+fn sphere_element<N: na::RealField + Display>(radius: N) -> RawElement {
+    let mut sphere_element = element("sphere");
+    set(&mut sphere_element, "radius", radius);
+    sphere_element
+}
+
+fn box_element<N: na::RealField + Display>(size: &na::Vector3<N>) -> RawElement {
+    let mut box_element = element("box");
+    set(&mut box_element, "size", list(size.iter()));
+    box_element
+}
+
+fn cylinder_element<N: na::RealField + Display>(radius: N, length: N) -> RawElement {
+    let mut cylinder_element = element("cylinder");
+    set(&mut cylinder_element, "radius", radius);
+    set(&mut cylinder_element, "length", length);
+    cylinder_element
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const ARM_XML: &str = "<mujoco model=\"arm\">
+  <option timestep=\"0.005\"/>
+  <worldbody>
+    <geom name=\"floor\" type=\"plane\" size=\"1 1 0.1\"/>
+    <body name=\"upper\" pos=\"0 0 1\">
+      <inertial pos=\"0 0 -0.25\" mass=\"1\" diaginertia=\"0.1 0.1 0.01\"/>
+      <joint name=\"shoulder\" axis=\"0 1 0\" limited=\"true\" range=\"-90 90\"/>
+      <geom type=\"capsule\" size=\"0.05\" fromto=\"0 0 0 0 0 -0.5\" rgba=\"1 0 0 1\"/>
+      <site name=\"tip\" pos=\"0 0 -0.5\"/>
+      <body name=\"lower\" pos=\"0 0 -0.5\">
+        <joint name=\"wrist\" type=\"ball\" pos=\"0 0 0.1\"/>
+        <joint name=\"extend\" type=\"slide\"/>
+      </body>
+    </body>
+  </worldbody>
+  <actuator><motor joint=\"shoulder\" gear=\"10\"/></actuator>
+</mujoco>";
+
+    #[test]
+    fn export_arm_to_urdf() {
+        let mut model = Model::<f32>::parse_xml_string(ARM_XML).unwrap();
+        // the parser does not support ellipsoids, but models can be
+        // built with them
+        let mut ellipsoid = Geom::new(GeomType::Ellipsoid, vec![0.1, 0.2, 0.1]);
+        ellipsoid.contype = Some(0);
+        ellipsoid.conaffinity = Some(0);
+        model.worldbody.bodies[0].bodies[0].geoms.push(ellipsoid);
+        let (urdf, report) = model.to_urdf_string();
+
+        // the plane and the ellipsoid
+        assert_eq!(report.warning_count(WarningCode::ApproximatedShape), 2);
+        let messages: Vec<&str> = report
+            .warnings()
+            .iter()
+            .filter(|warning| warning.code == WarningCode::LossyConversion)
+            .map(|warning| warning.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                "ball joint wrist is exported as three hinges",
+                "mass and inertia of lower, which MuJoCo infers from its geoms, are not exported",
+                "simulation options, e.g. gravity and timestep, are not exported",
+                "<actuator> section is not exported",
+                "sites are not exported (1 in the model)",
+            ]
+        );
+
+        let exported = Model::<f32>::parse_urdf_string(&urdf)
+            .unwrap_or_else(|error| panic!("Failed to parse exported URDF: {}\n{}", error, urdf));
+        assert_eq!(exported.name.as_ref().map(String::as_str), Some("arm"));
+        assert_eq!(exported.worldbody.geoms.len(), 2);
+
+        let upper = &exported.worldbody.bodies[0];
+        assert_eq!(upper.pos, na::Vector3::new(0.0, 0.0, 1.0));
+        let shoulder = &upper.joints[0];
+        assert_eq!(shoulder.axis, na::Vector3::y());
+        let range = shoulder.range.unwrap();
+        assert!((range[1] - std::f32::consts::FRAC_PI_2).abs() < 1.0e-6);
+        // the capsule is a cylinder and two spheres, each with a
+        // visual and a collision element
+        assert_eq!(upper.geoms.len(), 6);
+        assert_eq!(upper.inertial.as_ref().unwrap().mass, 1.0);
+
+        // the ball joint's three hinges and the slide are chained
+        // through massless links, placed at the ball joint's anchor
+        let mut link = &upper.bodies[0];
+        assert_eq!(link.pos, na::Vector3::new(0.0, 0.0, -0.4));
+        let mut joint_names = vec![];
+        loop {
+            joint_names.push(link.joints[0].name.clone().unwrap());
+            match link.bodies.first() {
+                Some(child) => link = child,
+                None => break,
+            }
+        }
+        assert_eq!(joint_names, vec!["wrist_x", "wrist_y", "wrist_z", "extend"]);
+        assert_eq!(link.name.as_ref().map(String::as_str), Some("lower"));
+        assert_eq!(link.pos, na::Vector3::new(0.0, 0.0, -0.1));
+        // visual only
+        assert_eq!(link.geoms.len(), 1);
+    }
+
+    #[test]
+    fn single_free_body_is_the_root() {
+        let xml = "<mujoco>
+                     <worldbody>
+                       <body name=\"ball\" pos=\"0 0 1\">
+                         <freejoint/>
+                         <geom size=\"0.1\" mass=\"1\"/>
+                       </body>
+                     </worldbody>
+                   </mujoco>";
+        let model = Model::<f32>::parse_xml_string(xml).unwrap();
+        let (urdf, _) = model.to_urdf_string();
+
+        let exported = Model::<f32>::parse_urdf_string(&urdf).unwrap();
+        let ball = &exported.worldbody.bodies[0];
+        assert_eq!(ball.name.as_ref().map(String::as_str), Some("ball"));
+        assert!(ball.bodies.is_empty());
+    }
+
+    #[test]
+    fn example_models_export() {
+        let models_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/models");
+        for entry in fs::read_dir(models_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "xml")
+            {
+                continue;
+            }
+            let model = Model::<f32>::parse_file(&path).unwrap();
+            let (urdf, _) = model.to_urdf_string();
+            let exported = Model::<f32>::parse_urdf_string(&urdf);
+            assert!(exported.is_ok(), "{}\n{}", path.display(), urdf);
+        }
+    }
+}
@@ -0,0 +1,582 @@
+//! Serialization of a `Model` back to MJCF XML.
+//!
+//! The output is canonical rather than a copy of the source
+//! document. Attributes are written in a fixed order, values equal to
+//! MuJoCo's defaults are left out, orientations are written as
+//! quaternions and comments are dropped. Parsing the output yields an
+//! equal `Model`.
+//!
+//! Attributes and elements the model has no typed representation
+//! for, e.g. geom `solref` or `<hfield>` assets, are written back as
+//! they were parsed.
+
+use crate::model::{
+    Asset, Body, Compiler, Geom, GeomType, Inertial, Joint, JointType, Model, RawElement,
+    SimOption, Site,
+};
+use crate::tags::custom::Custom;
+use nalgebra as na;
+use std::fmt::{Display, Write};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Top level sections written before the asset and worldbody
+/// sections, when kept as raw elements
+pub(crate) const PRELUDE_SECTIONS: [&str; 4] = ["size", "visual", "statistic", "default"];
+
+impl<N: na::RealField + Display> Model<N> {
+    /// Write the model as an MJCF XML document
+    pub fn to_xml_string(&self) -> String {
+        let mut text = String::new();
+        write_element(&mut text, &self.to_element(), 0);
+        text
+    }
+
+    /// Write the model to an MJCF XML file
+    pub fn write_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_xml_string())
+    }
+
+    fn to_element(&self) -> RawElement {
+        let mut mujoco = element("mujoco");
+        set_optional(&mut mujoco, "model", self.name.as_ref());
+
+        if self.compiler != Compiler::default() {
+            mujoco.children.push(compiler_element(&self.compiler));
+        }
+        if self.option != SimOption::default() {
+            mujoco.children.push(option_element(&self.option));
+        }
+        let (prelude, sections): (Vec<&RawElement>, Vec<&RawElement>) = self
+            .raw_sections
+            .iter()
+            .partition(|section| PRELUDE_SECTIONS.contains(&section.tag.as_str()));
+        mujoco.children.extend(prelude.into_iter().cloned());
+        if !self.asset.is_empty() {
+            mujoco.children.push(asset_element(&self.asset));
+        }
+
+        let mut worldbody = element("worldbody");
+        body_children(&mut worldbody, &self.worldbody);
+        mujoco.children.push(worldbody);
+
+        mujoco.children.extend(sections.into_iter().cloned());
+        if !self.custom.is_empty() {
+            mujoco.children.push(custom_element(&self.custom));
+        }
+        mujoco
+    }
+}
+
+pub(crate) fn element(tag: &str) -> RawElement {
+    RawElement {
+        tag: tag.to_string(),
+        attributes: vec![],
+        children: vec![],
+    }
+}
+
+pub(crate) fn set<V: Display>(element: &mut RawElement, name: &str, value: V) {
+    element
+        .attributes
+        .push((name.to_string(), value.to_string()));
+}
+
+pub(crate) fn set_optional<V: Display>(element: &mut RawElement, name: &str, value: Option<V>) {
+    if let Some(value) = value {
+        set(element, name, value);
+    }
+}
+
+/// Add attributes kept as they were written
+fn set_raw(element: &mut RawElement, attributes: &[(String, String)]) {
+    element.attributes.extend(attributes.iter().cloned());
+}
+
+/// Space separated list of values, as used by vector attributes
+pub(crate) fn list<'v, V: Display + 'v, I: IntoIterator<Item = &'v V>>(values: I) -> String {
+    values
+        .into_iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn set_pose<N: na::RealField + Display>(
+    element: &mut RawElement,
+    pos: &na::Vector3<N>,
+    quat: &na::UnitQuaternion<N>,
+) {
+    if *pos != na::Vector3::zeros() {
+        set(element, "pos", list(pos.iter()));
+    }
+    if *quat != na::UnitQuaternion::identity() {
+        // mujoco uses [w, x, y, z] but nalgebra uses [x, y, z, w]
+        let quat = quat.quaternion();
+        set(element, "quat", list(&[quat.w, quat.i, quat.j, quat.k]));
+    }
+}
+
+fn compiler_element(compiler: &Compiler) -> RawElement {
+    let mut compiler_element = element("compiler");
+    set(&mut compiler_element, "angle", compiler.angle);
+    set_optional(&mut compiler_element, "meshdir", compiler.meshdir.as_ref());
+    set_optional(
+        &mut compiler_element,
+        "texturedir",
+        compiler.texturedir.as_ref(),
+    );
+    set_raw(&mut compiler_element, &compiler.raw_attributes);
+    compiler_element
+}
+
+fn option_element<N: na::RealField + Display>(option: &SimOption<N>) -> RawElement {
+    let mut option_element = element("option");
+    set_optional(&mut option_element, "timestep", option.timestep);
+    if let Some(gravity) = &option.gravity {
+        set(&mut option_element, "gravity", list(gravity.iter()));
+    }
+    set_optional(&mut option_element, "density", option.density);
+    set_optional(&mut option_element, "viscosity", option.viscosity);
+    set_raw(&mut option_element, &option.raw_attributes);
+    option_element
+        .children
+        .extend(option.raw_children.iter().cloned());
+    option_element
+}
+
+fn asset_element<N: na::RealField + Display>(asset: &Asset<N>) -> RawElement {
+    let mut asset_element = element("asset");
+    for texture in &asset.textures {
+        let mut texture_element = element("texture");
+        set_optional(&mut texture_element, "name", texture.name.as_ref());
+        set_optional(&mut texture_element, "type", texture.texture_type.as_ref());
+        set_optional(&mut texture_element, "file", texture.file.as_ref());
+        set_optional(&mut texture_element, "builtin", texture.builtin.as_ref());
+        if let Some(rgb1) = &texture.rgb1 {
+            set(&mut texture_element, "rgb1", list(rgb1.iter()));
+        }
+        if let Some(rgb2) = &texture.rgb2 {
+            set(&mut texture_element, "rgb2", list(rgb2.iter()));
+        }
+        set_optional(&mut texture_element, "width", texture.width);
+        set_optional(&mut texture_element, "height", texture.height);
+        set_raw(&mut texture_element, &texture.raw_attributes);
+        asset_element.children.push(texture_element);
+    }
+    for material in &asset.materials {
+        let mut material_element = element("material");
+        set_optional(&mut material_element, "name", material.name.as_ref());
+        set_optional(&mut material_element, "class", material.class.as_ref());
+        set_optional(&mut material_element, "texture", material.texture.as_ref());
+        if let Some(rgba) = &material.rgba {
+            set(&mut material_element, "rgba", list(rgba.iter()));
+        }
+        set_raw(&mut material_element, &material.raw_attributes);
+        asset_element.children.push(material_element);
+    }
+    for mesh in &asset.meshes {
+        let mut mesh_element = element("mesh");
+        set_optional(&mut mesh_element, "name", mesh.name.as_ref());
+        set_optional(&mut mesh_element, "class", mesh.class.as_ref());
+        set_optional(&mut mesh_element, "file", mesh.file.as_ref());
+        if let Some(scale) = &mesh.scale {
+            set(&mut mesh_element, "scale", list(scale.iter()));
+        }
+        set_raw(&mut mesh_element, &mesh.raw_attributes);
+        asset_element.children.push(mesh_element);
+    }
+    asset_element
+        .children
+        .extend(asset.raw_assets.iter().cloned());
+    asset_element
+}
+
+/// Add the contents of a body, or the worldbody, to its element
+fn body_children<N: na::RealField + Display>(body_element: &mut RawElement, body: &Body<N>) {
+    if let Some(inertial) = &body.inertial {
+        body_element.children.push(inertial_element(inertial));
+    }
+    for joint in &body.joints {
+        body_element.children.push(joint_element(joint));
+    }
+    for geom in &body.geoms {
+        body_element.children.push(geom_element(geom));
+    }
+    for site in &body.sites {
+        body_element.children.push(site_element(site));
+    }
+    for child in &body.bodies {
+        body_element.children.push(body_element_of(child));
+    }
+    body_element
+        .children
+        .extend(body.raw_children.iter().cloned());
+}
+
+fn body_element_of<N: na::RealField + Display>(body: &Body<N>) -> RawElement {
+    let mut body_element = element("body");
+    set_optional(&mut body_element, "name", body.name.as_ref());
+    set_optional(&mut body_element, "childclass", body.childclass.as_ref());
+    set_pose(&mut body_element, &body.pos, &body.quat);
+    set_optional(&mut body_element, "mocap", body.mocap);
+    set_raw(&mut body_element, &body.raw_attributes);
+    body_children(&mut body_element, body);
+    body_element
+}
+
+fn inertial_element<N: na::RealField + Display>(inertial: &Inertial<N>) -> RawElement {
+    let mut inertial_element = element("inertial");
+    // pos is required on inertial tags
+    set(&mut inertial_element, "pos", list(inertial.pos.iter()));
+    set_pose(&mut inertial_element, &na::Vector3::zeros(), &inertial.quat);
+    set(&mut inertial_element, "mass", inertial.mass);
+    if let Some(diaginertia) = &inertial.diaginertia {
+        set(
+            &mut inertial_element,
+            "diaginertia",
+            list(diaginertia.iter()),
+        );
+    }
+    if let Some(fullinertia) = &inertial.fullinertia {
+        set(
+            &mut inertial_element,
+            "fullinertia",
+            list(fullinertia.iter()),
+        );
+    }
+    inertial_element
+}
+
+fn joint_element<N: na::RealField + Display>(joint: &Joint<N>) -> RawElement {
+    let mut joint_element = element("joint");
+    set_optional(&mut joint_element, "name", joint.name.as_ref());
+    set_optional(&mut joint_element, "class", joint.class.as_ref());
+    if joint.joint_type != JointType::default() {
+        set(&mut joint_element, "type", joint.joint_type);
+    }
+    if joint.pos != na::Vector3::zeros() {
+        set(&mut joint_element, "pos", list(joint.pos.iter()));
+    }
+    if joint.axis != na::Vector3::z() {
+        set(&mut joint_element, "axis", list(joint.axis.iter()));
+    }
+    set_optional(&mut joint_element, "limited", joint.limited);
+    if let Some(range) = &joint.range {
+        set(&mut joint_element, "range", list(range.iter()));
+    }
+    set_optional(&mut joint_element, "damping", joint.damping);
+    set_optional(&mut joint_element, "stiffness", joint.stiffness);
+    set_optional(&mut joint_element, "armature", joint.armature);
+    set_optional(&mut joint_element, "frictionloss", joint.frictionloss);
+    set_optional(&mut joint_element, "group", joint.group);
+    set_raw(&mut joint_element, &joint.raw_attributes);
+    joint_element
+}
+
+fn geom_element<N: na::RealField + Display>(geom: &Geom<N>) -> RawElement {
+    let mut geom_element = element("geom");
+    set_optional(&mut geom_element, "name", geom.name.as_ref());
+    set_optional(&mut geom_element, "class", geom.class.as_ref());
+    if geom.geom_type != GeomType::default() {
+        set(&mut geom_element, "type", geom.geom_type);
+    }
+    if !geom.size.is_empty() {
+        set(&mut geom_element, "size", list(&geom.size));
+    }
+    match &geom.fromto {
+        Some(fromto) => set(&mut geom_element, "fromto", list(fromto.iter())),
+        None => set_pose(&mut geom_element, &geom.pos, &geom.quat),
+    }
+    if let Some(rgba) = &geom.rgba {
+        set(&mut geom_element, "rgba", list(rgba.iter()));
+    }
+    if let Some(friction) = &geom.friction {
+        set(&mut geom_element, "friction", list(friction.iter()));
+    }
+    set_optional(&mut geom_element, "density", geom.density);
+    set_optional(&mut geom_element, "mass", geom.mass);
+    set_optional(&mut geom_element, "margin", geom.margin);
+    set_optional(&mut geom_element, "group", geom.group);
+    set_optional(&mut geom_element, "contype", geom.contype);
+    set_optional(&mut geom_element, "conaffinity", geom.conaffinity);
+    set_optional(&mut geom_element, "condim", geom.condim);
+    set_optional(&mut geom_element, "material", geom.material.as_ref());
+    set_optional(&mut geom_element, "mesh", geom.mesh.as_ref());
+    set_optional(&mut geom_element, "hfield", geom.hfield.as_ref());
+    set_raw(&mut geom_element, &geom.raw_attributes);
+    geom_element
+}
+
+fn site_element<N: na::RealField + Display>(site: &Site<N>) -> RawElement {
+    let mut site_element = element("site");
+    set_optional(&mut site_element, "name", site.name.as_ref());
+    set_optional(&mut site_element, "class", site.class.as_ref());
+    if site.site_type != GeomType::default() {
+        set(&mut site_element, "type", site.site_type);
+    }
+    if !site.size.is_empty() {
+        set(&mut site_element, "size", list(&site.size));
+    }
+    match &site.fromto {
+        Some(fromto) => set(&mut site_element, "fromto", list(fromto.iter())),
+        None => set_pose(&mut site_element, &site.pos, &site.quat),
+    }
+    if let Some(rgba) = &site.rgba {
+        set(&mut site_element, "rgba", list(rgba.iter()));
+    }
+    set_optional(&mut site_element, "group", site.group);
+    set_optional(&mut site_element, "material", site.material.as_ref());
+    set_raw(&mut site_element, &site.raw_attributes);
+    site_element
+}
+
+fn custom_element<N: na::RealField + Display>(custom: &Custom<N>) -> RawElement {
+    let mut custom_element = element("custom");
+    for numeric in &custom.numerics {
+        let mut numeric_element = element("numeric");
+        set(&mut numeric_element, "name", &numeric.name);
+        if !numeric.data.is_empty() {
+            set(&mut numeric_element, "data", list(&numeric.data));
+        }
+        custom_element.children.push(numeric_element);
+    }
+    for text in &custom.texts {
+        let mut text_element = element("text");
+        set(&mut text_element, "name", &text.name);
+        set(&mut text_element, "data", &text.data);
+        custom_element.children.push(text_element);
+    }
+    for tuple in &custom.tuples {
+        let mut tuple_element = element("tuple");
+        set(&mut tuple_element, "name", &tuple.name);
+        for tuple_entry in &tuple.elements {
+            let mut entry_element = element("element");
+            set(&mut entry_element, "objtype", &tuple_entry.objtype);
+            set(&mut entry_element, "objname", &tuple_entry.objname);
+            set(&mut entry_element, "prm", tuple_entry.prm);
+            tuple_element.children.push(entry_element);
+        }
+        custom_element.children.push(tuple_element);
+    }
+    custom_element
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub(crate) fn write_element(text: &mut String, element: &RawElement, depth: usize) {
+    let indent = "  ".repeat(depth);
+    write!(text, "{}<{}", indent, element.tag).unwrap();
+    for (name, value) in &element.attributes {
+        write!(text, " {}=\"{}\"", name, escape(value)).unwrap();
+    }
+    if element.children.is_empty() {
+        text.push_str("/>\n");
+    } else {
+        text.push_str(">\n");
+        for child in &element.children {
+            write_element(text, child, depth + 1);
+        }
+        writeln!(text, "{}</{}>", indent, element.tag).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+    use std::fs;
+
+    fn round_trip(model: &Model<f32>) -> Model<f32> {
+        let xml = model.to_xml_string();
+        Model::parse_xml_string(&xml)
+            .unwrap_or_else(|error| panic!("Failed to parse written model: {}\n{}", error, xml))
+    }
+
+    #[test]
+    fn example_models_round_trip() {
+        let models_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/models");
+        let mut count = 0;
+        for entry in fs::read_dir(models_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "xml")
+            {
+                continue;
+            }
+            let model = Model::<f32>::parse_file(&path)
+                .unwrap_or_else(|error| panic!("Failed to parse {}: {}", path.display(), error));
+            assert_eq!(round_trip(&model), model, "{}", path.display());
+            count += 1;
+        }
+        assert!(count > 0);
+    }
+
+    /// Attributes the writer leaves out when they hold MuJoCo's
+    /// default, e.g. a zero `pos`
+    const DEFAULTED_ATTRIBUTES: [&str; 3] = ["pos", "axis", "type"];
+
+    /// Whether two attribute values are the same, allowing numbers to
+    /// be formatted differently
+    fn same_value(source: &str, written: &str) -> bool {
+        let numbers = |text: &str| -> Option<Vec<f64>> {
+            text.split_whitespace()
+                .map(|value| value.parse().ok())
+                .collect()
+        };
+        match (numbers(source), numbers(written)) {
+            (Some(source), Some(written)) => {
+                source.len() == written.len()
+                    && source.iter().zip(&written).all(|(source, written)| {
+                        (source - written).abs() <= 1.0e-6 * source.abs().max(1.0)
+                    })
+            }
+            _ => source.trim() == written.trim(),
+        }
+    }
+
+    /// Check that every attribute of an element and its descendants
+    /// is written with the same value. Children are matched by tag,
+    /// in document order.
+    fn assert_attributes_written(source: &roxmltree::Node, written: &roxmltree::Node, file: &Path) {
+        let tag = source.tag_name().name();
+        for attribute in source.attributes() {
+            let name = attribute.name();
+            match written.attribute(name) {
+                Some(value) => assert!(
+                    same_value(attribute.value(), value),
+                    "{}: {} {}=\"{}\" was written as \"{}\"",
+                    file.display(),
+                    tag,
+                    name,
+                    attribute.value(),
+                    value
+                ),
+                None => assert!(
+                    crate::tags::raw::ORIENTATION_ATTRIBUTES.contains(&name)
+                        || DEFAULTED_ATTRIBUTES.contains(&name),
+                    "{}: {} {} attribute was not written",
+                    file.display(),
+                    tag,
+                    name
+                ),
+            }
+        }
+
+        let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+        for child in source.children().filter(|child| child.is_element()) {
+            let child_tag = child.tag_name().name();
+            let index = tag_counts.entry(child_tag).or_insert(0);
+            let written_child = written
+                .children()
+                .filter(|written_child| {
+                    written_child.is_element() && written_child.tag_name().name() == child_tag
+                })
+                .nth(*index);
+            *index += 1;
+            match written_child {
+                Some(written_child) => assert_attributes_written(&child, &written_child, file),
+                None => panic!(
+                    "{}: {} child {} of {} was not written",
+                    file.display(),
+                    child_tag,
+                    index,
+                    tag
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn example_models_keep_their_attributes() {
+        let models_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/models");
+        for entry in fs::read_dir(models_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "xml")
+            {
+                continue;
+            }
+            let source_text = fs::read_to_string(&path).unwrap();
+            let written_text = Model::<f32>::parse_xml_string(&source_text)
+                .unwrap()
+                .to_xml_string();
+            let source = roxmltree::Document::parse(&source_text).unwrap();
+            let written = roxmltree::Document::parse(&written_text).unwrap();
+            assert_attributes_written(&source.root_element(), &written.root_element(), &path);
+        }
+    }
+
+    #[test]
+    fn names_classes_and_references_round_trip() {
+        let xml = "<mujoco model=\"arm &amp; hand\">
+                     <compiler angle=\"radian\" meshdir=\"meshes\"/>
+                     <option timestep=\"0.005\" gravity=\"0 0 -1\"/>
+                     <default><geom rgba=\"1 0 0 1\"/></default>
+                     <asset>
+                       <texture name=\"grid\" type=\"2d\" builtin=\"checker\" width=\"64\" height=\"64\"/>
+                       <material name=\"floor\" texture=\"grid\"/>
+                     </asset>
+                     <worldbody>
+                       <geom name=\"floor\" type=\"plane\" size=\"1 1 1\" material=\"floor\"/>
+                       <light pos=\"0 0 3\"/>
+                       <body name=\"upper\" pos=\"0 0 1\">
+                         <inertial pos=\"0 0 -0.25\" mass=\"1\" diaginertia=\"0.1 0.1 0.01\"/>
+                         <joint name=\"shoulder\" type=\"slide\" axis=\"1 0 0\" limited=\"true\" range=\"-1 1\"/>
+                         <geom class=\"links\" type=\"capsule\" size=\"0.05\" fromto=\"0 0 0 0 0 -0.5\" group=\"1\"/>
+                         <site name=\"tip\" pos=\"0 0 -0.5\"/>
+                       </body>
+                     </worldbody>
+                     <actuator><motor joint=\"shoulder\" gear=\"10\"/></actuator>
+                     <custom>
+                       <numeric name=\"goal\" data=\"1 2 3\"/>
+                       <text name=\"task\" data=\"&lt;reach&gt;\"/>
+                       <tuple name=\"targets\"><element objtype=\"site\" objname=\"tip\" prm=\"0.5\"/></tuple>
+                     </custom>
+                   </mujoco>";
+
+        let model = Model::<f32>::parse_xml_string(xml).unwrap();
+        assert_eq!(round_trip(&model), model);
+    }
+
+    proptest! {
+        #[test]
+        fn generated_geoms_round_trip(
+            geoms in proptest::collection::vec(
+                (
+                    proptest::num::f32::NORMAL,
+                    proptest::collection::vec(proptest::num::f32::NORMAL, 3),
+                    proptest::option::of(proptest::collection::vec(0.0f32..1.0, 4)),
+                ),
+                0..10,
+            )
+        ) {
+            let mut model = Model::<f32>::default();
+            for (index, (radius, pos, rgba)) in geoms.into_iter().enumerate() {
+                let mut geom = Geom::new(GeomType::Sphere, vec![radius]);
+                geom.name = Some(format!("geom{}", index));
+                geom.pos = na::Vector3::from_column_slice(&pos);
+                geom.rgba = rgba.map(|rgba| na::Vector4::from_column_slice(&rgba));
+                model.worldbody.geoms.push(geom);
+            }
+
+            prop_assert_eq!(round_trip(&model), model);
+        }
+    }
+}
@@ -3,9 +3,10 @@ use amethyst::{
     input::InputBundle,
     prelude::*,
     renderer::{
-        ColorMask, DepthMode, DisplayConfig, DrawFlat, DrawSkybox, Pipeline, PosNormTex,
-        RenderBundle, Stage, ALPHA,
+        ColorMask, DebugLines, DepthMode, DisplayConfig, DrawDebugLines, DrawFlat, DrawSkybox,
+        Pipeline, PosColorNorm, PosNormTex, RenderBundle, Stage, ALPHA,
     },
+    ui::{DrawUi, UiBundle},
     utils::application_root_dir,
 };
 use model_explorer::state::LoadModelState;
@@ -16,12 +17,17 @@ use structopt::StructOpt;
 #[derive(Debug, StructOpt)]
 #[structopt(
     name = "Model Explorer",
-    about = "Simulate and interact with Nphysics model parsed from MJCF XML file"
+    about = "Simulate and interact with Nphysics model parsed from an MJCF XML, URDF or SDFormat file"
 )]
-/// Specify an MJCF XML file, load the model, and simulate it
+/// Specify an MJCF XML, URDF or SDFormat file, load the model, and simulate it
 struct Args {
     #[structopt(parse(from_os_str))]
     model_file: PathBuf,
+
+    /// Start with the simulation paused. Press space to resume, or
+    /// period to take a single step.
+    #[structopt(long = "paused")]
+    paused: bool,
 }
 
 fn main() -> amethyst::Result<()> {
@@ -45,18 +51,49 @@ fn main() -> amethyst::Result<()> {
                     ColorMask::all(),
                     ALPHA,
                     Some(DepthMode::LessEqualWrite),
-                )),
+                ))
+                .with_pass(DrawDebugLines::<PosColorNorm>::new())
+                .with_pass(DrawUi::new()),
         );
 
     let game_data = GameDataBuilder::default()
         .with_bundle(RenderBundle::new(pipe, Some(config)))?
-        .with_bundle(TransformBundle::new())?
-        .with_bundle(InputBundle::<(), ()>::new())?
         .with(PhysicsSystem::<f32>::default(), "physics_system", &[])
+        .with_bundle(TransformBundle::new().with_dep(&["physics_system"]))?
+        .with_bundle(InputBundle::<(), ()>::new())?
+        .with_bundle(UiBundle::<(), ()>::new())?
         .with(MouseDrag::default(), "left_button_drag", &[])
-        .with(system::FPSCamera {}, "fps_camera", &["left_button_drag"]);
+        .with(
+            system::PerturbationSystem,
+            "perturbation",
+            &["left_button_drag", "physics_system"],
+        )
+        .with(
+            system::FPSCamera {},
+            "fps_camera",
+            &["left_button_drag", "perturbation"],
+        )
+        .with(
+            system::ArcBallCameraSystem::default(),
+            "arc_ball_camera",
+            &["left_button_drag", "perturbation"],
+        )
+        .with(
+            system::PickingSystem::default(),
+            "picking",
+            &["left_button_drag", "physics_system"],
+        )
+        .with(system::InspectorSystem, "inspector", &["picking"])
+        .with(system::LoadErrorSystem, "load_error", &[])
+        .with(system::DebugDrawSystem, "debug_draw", &["physics_system"])
+        .with(system::GroupVisibilitySystem, "group_visibility", &[]);
 
-    let mut game = Application::new("./", LoadModelState::new(args.model_file), game_data)?;
+    let mut game = Application::build(
+        "./",
+        LoadModelState::new(args.model_file).start_paused(args.paused),
+    )?
+    .with_resource(DebugLines::new())
+    .build(game_data)?;
 
     game.run();
 
@@ -0,0 +1,68 @@
+use amethyst::{
+    core::{nalgebra as na, Transform},
+    ecs::prelude::{Component, HashMapStorage},
+};
+
+/// Closest the camera gets to its target
+pub const MIN_DISTANCE: f32 = 0.1;
+
+/// Orbits the camera around a target point.
+///
+/// The camera sits `distance` away from `target`, rotated `yaw`
+/// radians around the y axis and raised `elevation` radians above the
+/// xz plane, looking at the target.
+#[derive(Clone)]
+pub struct ArcBallCamera {
+    pub target: na::Point3<f32>,
+    pub distance: f32,
+    pub yaw: f32,
+    pub elevation: f32,
+}
+
+impl ArcBallCamera {
+    /// Orbit the point the camera of `transform` looks at, as far away
+    /// as the camera is from the origin
+    pub fn from_transform(transform: &Transform) -> Self {
+        let position = na::Point3::from(*transform.translation());
+        let distance = position.coords.norm().max(MIN_DISTANCE);
+        let backward = transform.rotation() * na::Vector3::z();
+
+        Self {
+            target: position - backward * distance,
+            distance,
+            yaw: backward.x.atan2(backward.z),
+            elevation: backward.y.max(-1.0).min(1.0).asin(),
+        }
+    }
+
+    pub fn rotation(&self) -> na::UnitQuaternion<f32> {
+        na::UnitQuaternion::from_axis_angle(&na::Vector3::y_axis(), self.yaw)
+            * na::UnitQuaternion::from_axis_angle(&na::Vector3::x_axis(), -self.elevation)
+    }
+
+    pub fn position(&self) -> na::Point3<f32> {
+        self.target + self.rotation() * na::Vector3::z() * self.distance
+    }
+
+    /// Move the camera to its place on the orbit, facing the target
+    pub fn update_transform(&self, transform: &mut Transform) {
+        let position = self.position();
+        transform.set_xyz(position.x, position.y, position.z);
+        *transform.rotation_mut() = self.rotation();
+    }
+}
+
+impl Default for ArcBallCamera {
+    fn default() -> Self {
+        Self {
+            target: na::Point3::origin(),
+            distance: 50.0,
+            yaw: 0.0,
+            elevation: 0.0,
+        }
+    }
+}
+
+impl Component for ArcBallCamera {
+    type Storage = HashMapStorage<Self>;
+}
@@ -4,6 +4,8 @@ use nphysics3d::object::ColliderHandle;
 /// Component that stores the physics collider handle of the entity
 pub struct Collider {
     pub id: ColliderHandle,
+    /// Visibility group of the collider's geom
+    pub group: i32,
 }
 
 impl Component for Collider {
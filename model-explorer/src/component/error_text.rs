@@ -0,0 +1,9 @@
+use amethyst::ecs::prelude::{Component, NullStorage};
+
+/// Marks the UI text that shows why the model failed to load
+#[derive(Default)]
+pub struct ErrorText;
+
+impl Component for ErrorText {
+    type Storage = NullStorage<Self>;
+}
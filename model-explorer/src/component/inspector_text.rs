@@ -0,0 +1,9 @@
+use amethyst::ecs::prelude::{Component, NullStorage};
+
+/// Marks the UI text that shows the state of the selected collider
+#[derive(Default)]
+pub struct InspectorText;
+
+impl Component for InspectorText {
+    type Storage = NullStorage<Self>;
+}
@@ -1,5 +1,11 @@
+pub mod arc_ball_camera;
 mod collider;
+mod error_text;
 mod fps_camera;
+mod inspector_text;
 
+pub use arc_ball_camera::ArcBallCamera;
 pub use collider::Collider;
+pub use error_text::ErrorText;
 pub use fps_camera::FPSCamera;
+pub use inspector_text::InspectorText;
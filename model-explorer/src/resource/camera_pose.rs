@@ -0,0 +1,11 @@
+use crate::component::ArcBallCamera;
+use amethyst::core::Transform;
+
+/// Pose of the camera when the model was cleared, so the view does
+/// not jump back to the default when the model is reloaded
+#[derive(Default)]
+pub struct CameraPose {
+    pub transform: Option<Transform>,
+    /// The orbit, when the orbit camera was active
+    pub arc_ball: Option<ArcBallCamera>,
+}
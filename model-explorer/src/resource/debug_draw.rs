@@ -0,0 +1,14 @@
+/// Which debug layers are drawn on top of the model
+#[derive(Default)]
+pub struct DebugDraw {
+    /// Contact points and normals
+    pub contacts: bool,
+    /// Bounding boxes of the colliders
+    pub aabbs: bool,
+    /// Joint anchors and axes, of the joints in shown groups
+    pub joints: bool,
+    /// Centers of mass and equivalent inertia boxes
+    pub inertia: bool,
+    /// Coordinate frames of the world and of each body
+    pub frames: bool,
+}
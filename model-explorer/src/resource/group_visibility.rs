@@ -0,0 +1,32 @@
+/// Number of geom groups that can be hidden, like in MuJoCo's viewer
+pub const GROUP_COUNT: usize = 6;
+
+/// Which geom and joint groups are shown. Geoms and joints in groups
+/// outside of `0..GROUP_COUNT` are always shown.
+pub struct GroupVisibility {
+    visible: [bool; GROUP_COUNT],
+}
+
+impl GroupVisibility {
+    pub fn is_visible(&self, group: i32) -> bool {
+        if group < 0 || group as usize >= GROUP_COUNT {
+            return true;
+        }
+        self.visible[group as usize]
+    }
+
+    /// Show a hidden group, or hide a shown one
+    pub fn toggle(&mut self, group: usize) {
+        if let Some(visible) = self.visible.get_mut(group) {
+            *visible = !*visible;
+        }
+    }
+}
+
+impl Default for GroupVisibility {
+    fn default() -> Self {
+        Self {
+            visible: [true; GROUP_COUNT],
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use mjcf_parser::model::{Body, Model};
+use nalgebra as na;
+use std::collections::HashMap;
+
+/// Visibility group of the joint of each multibody link, keyed by the
+/// link's name, so joint overlays can be hidden with their group
+#[derive(Default)]
+pub struct JointGroups {
+    groups: HashMap<String, i32>,
+}
+
+impl JointGroups {
+    pub fn from_model<N: na::RealField>(model: &Model<N>) -> Self {
+        let mut joint_groups = Self::default();
+        for body in &model.worldbody.bodies {
+            joint_groups.add_body(body);
+        }
+        joint_groups
+    }
+
+    /// Group of the joint of a link, 0 unless the model sets one
+    pub fn group(&self, link_name: &str) -> i32 {
+        self.groups.get(link_name).cloned().unwrap_or(0)
+    }
+
+    fn add_body<N: na::RealField>(&mut self, body: &Body<N>) {
+        // links are named after their body, and built with the body's
+        // first joint only
+        let group = body.joints.first().and_then(|joint| joint.group);
+        if let (Some(name), Some(group)) = (&body.name, group) {
+            self.groups.insert(name.clone(), group);
+        }
+        for child in &body.bodies {
+            self.add_body(child);
+        }
+    }
+}
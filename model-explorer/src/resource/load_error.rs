@@ -0,0 +1,5 @@
+/// Why the model failed to load, shown on screen until it loads
+#[derive(Default)]
+pub struct LoadError {
+    pub message: Option<String>,
+}
@@ -1,5 +1,23 @@
+mod camera_pose;
+mod debug_draw;
+mod group_visibility;
+mod joint_groups;
+mod load_error;
+pub mod model_watcher;
 pub mod mouse_drag;
+pub mod perturbation;
 pub mod physics;
 mod reload_model;
+pub mod selection;
+mod sim_clock;
 
+pub use camera_pose::CameraPose;
+pub use debug_draw::DebugDraw;
+pub use group_visibility::{GroupVisibility, GROUP_COUNT};
+pub use joint_groups::JointGroups;
+pub use load_error::LoadError;
+pub use model_watcher::ModelWatcher;
+pub use perturbation::Perturbation;
 pub use reload_model::ReloadModel;
+pub use selection::Selection;
+pub use sim_clock::SimClock;
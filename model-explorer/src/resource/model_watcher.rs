@@ -0,0 +1,133 @@
+use mjcf_parser::{FileResolver, FilesystemResolver};
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long the files have to be left alone before a change is
+/// reported, so that an editor saving several files, or writing one
+/// in several steps, causes a single reload
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(300);
+
+/// Reads files from the filesystem, and remembers every file that
+/// was read, or failed to be read, so the model can be reloaded when
+/// one of them changes
+#[derive(Clone, Default)]
+pub struct RecordingResolver {
+    files: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl RecordingResolver {
+    /// Paths of the files read so far
+    pub fn files(&self) -> Vec<PathBuf> {
+        self.files
+            .lock()
+            .expect("recorded files lock is poisoned")
+            .clone()
+    }
+
+    fn record(&self, path: &Path) {
+        self.files
+            .lock()
+            .expect("recorded files lock is poisoned")
+            .push(path.to_path_buf());
+    }
+}
+
+impl FileResolver for RecordingResolver {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.record(path);
+        FilesystemResolver.read_to_string(path)
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.record(path);
+        FilesystemResolver.read(path)
+    }
+}
+
+/// Watches the model file and the files it depends on
+#[derive(Default)]
+pub struct ModelWatcher {
+    // the watcher and its channel are not Sync, which resources have
+    // to be
+    watched: Option<Mutex<Watched>>,
+}
+
+struct Watched {
+    /// Stops watching when dropped
+    _watcher: RecommendedWatcher,
+    events: Receiver<DebouncedEvent>,
+    files: HashSet<PathBuf>,
+}
+
+impl ModelWatcher {
+    /// Watch `files` for changes.
+    ///
+    /// The directories of the files are watched rather than the files
+    /// themselves, so that files replaced by editors, and files that
+    /// are created after they were found missing, are noticed too.
+    pub fn watch<I: IntoIterator<Item = PathBuf>>(files: I) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher: RecommendedWatcher = Watcher::new(sender, DEBOUNCE_DELAY)?;
+
+        let files: HashSet<PathBuf> = files
+            .into_iter()
+            .filter_map(|file| absolute_path(&file))
+            .collect();
+        let directories: HashSet<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+        for directory in directories {
+            watcher.watch(directory, RecursiveMode::NonRecursive)?;
+        }
+
+        Ok(Self {
+            watched: Some(Mutex::new(Watched {
+                _watcher: watcher,
+                events,
+                files,
+            })),
+        })
+    }
+
+    /// Whether any of the watched files changed since the last call
+    pub fn has_changed(&self) -> bool {
+        let watched = match &self.watched {
+            Some(watched) => watched.lock().expect("model watcher lock is poisoned"),
+            None => return false,
+        };
+
+        let mut changed = false;
+        while let Ok(event) = watched.events.try_recv() {
+            changed |= match event {
+                DebouncedEvent::Create(path)
+                | DebouncedEvent::Write(path)
+                | DebouncedEvent::Remove(path) => watched.files.contains(&path),
+                DebouncedEvent::Rename(from, to) => {
+                    watched.files.contains(&from) || watched.files.contains(&to)
+                }
+                // events were lost, so anything may have changed
+                DebouncedEvent::Rescan => true,
+                _ => false,
+            };
+        }
+        changed
+    }
+}
+
+/// Absolute path of `file`, with the directory's symbolic links
+/// resolved like in the watcher's events. The file itself does not
+/// have to exist, only its directory.
+fn absolute_path(file: &Path) -> Option<PathBuf> {
+    let directory = match file.parent() {
+        Some(directory) if directory != Path::new("") => directory,
+        _ => Path::new("."),
+    };
+    let name = file.file_name()?;
+    fs::canonicalize(directory)
+        .ok()
+        .map(|directory| directory.join(name))
+}
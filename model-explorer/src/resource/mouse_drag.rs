@@ -430,3 +430,21 @@ impl AsEnum for MouseDrag<FinishDragging, NotClicked> {
         Variant::FinishDraggingByNotClicked(self)
     }
 }
+
+impl Variant {
+    /// How far the mouse moved while dragging since the last frame,
+    /// or `None` when not dragging
+    pub fn delta(&self) -> Option<na::Vector2<f64>> {
+        match self {
+            Variant::DraggingByClicked(s) => match s.state().as_enum() {
+                StateVariant::Dragging(s) => Some(s.delta_end_position - s.delta_start_position),
+                _ => unreachable!(),
+            },
+            Variant::FinishDraggingByNotClicked(s) => match s.state().as_enum() {
+                StateVariant::FinishDragging(s) => Some(s.end_position - s.delta_start_position),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+}
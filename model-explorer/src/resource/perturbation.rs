@@ -0,0 +1,30 @@
+use nalgebra as na;
+use nphysics3d::force_generator::ForceGeneratorHandle;
+use nphysics3d::object::BodyPartHandle;
+use std::sync::{Arc, Mutex};
+
+/// The body being dragged with the mouse, if any
+#[derive(Default)]
+pub struct Perturbation {
+    pub active: Option<ActivePerturbation>,
+}
+
+pub struct ActivePerturbation {
+    /// Spring pulling the body towards the cursor
+    pub force_generator: ForceGeneratorHandle,
+    pub body_part: BodyPartHandle,
+    /// Point that was grabbed, in the body part's frame
+    pub local_point: na::Point3<f32>,
+    /// Where the spring pulls the grabbed point to, shared with the
+    /// force generator
+    pub target: Arc<Mutex<na::Point3<f32>>>,
+    /// Distance from the camera to the grabbed point. The target
+    /// stays this far away along the cursor ray.
+    pub distance: f32,
+}
+
+impl Perturbation {
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+}
@@ -0,0 +1,42 @@
+use amethyst::{
+    ecs::prelude::{Entity, WriteStorage},
+    renderer::Material,
+};
+use nalgebra as na;
+use nphysics3d::object::ColliderHandle;
+
+/// The collider last clicked on, and its entity
+#[derive(Default)]
+pub struct Selection {
+    pub selected: Option<Selected>,
+}
+
+pub struct Selected {
+    pub collider: ColliderHandle,
+    pub entity: Entity,
+    /// Where the cursor ray hit the collider, in world coordinates
+    pub point: na::Point3<f32>,
+    /// Material of the entity before it was highlighted
+    pub original_material: Material,
+}
+
+impl Selection {
+    pub fn collider(&self) -> Option<ColliderHandle> {
+        self.selected.as_ref().map(|selected| selected.collider)
+    }
+
+    pub fn entity(&self) -> Option<Entity> {
+        self.selected.as_ref().map(|selected| selected.entity)
+    }
+
+    /// Deselect, giving the entity back its original material
+    pub fn clear(&mut self, materials: &mut WriteStorage<'_, Material>) {
+        if let Some(selected) = self.selected.take() {
+            if materials.contains(selected.entity) {
+                materials
+                    .insert(selected.entity, selected.original_material)
+                    .expect("selected entity is not alive");
+            }
+        }
+    }
+}
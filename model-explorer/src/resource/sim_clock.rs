@@ -0,0 +1,100 @@
+/// Slowest and fastest simulation speed, relative to wall time
+const MIN_TIME_SCALE: f32 = 1.0 / 64.0;
+const MAX_TIME_SCALE: f32 = 16.0;
+
+/// Most physics steps taken in one frame. When the simulation falls
+/// further behind, e.g. on a slow machine or when fast-forwarding a
+/// model with a small timestep, the rest of the time is dropped so
+/// the frame rate does not collapse.
+const MAX_STEPS_PER_FRAME: usize = 64;
+
+/// Decides how many physics steps to take each frame, so that the
+/// simulation advances at the model timestep independently of the
+/// render frame rate
+pub struct SimClock {
+    paused: bool,
+    time_scale: f32,
+    /// Scaled wall time not yet simulated
+    accumulator: f32,
+    /// Steps requested while paused
+    pending_steps: usize,
+}
+
+impl SimClock {
+    pub fn new(paused: bool) -> Self {
+        Self {
+            paused,
+            time_scale: 1.0,
+            accumulator: 0.0,
+            pending_steps: 0,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Simulated seconds per wall clock second
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.accumulator = 0.0;
+        self.pending_steps = 0;
+    }
+
+    /// Take a single step on the next frame. Only has an effect while
+    /// paused.
+    pub fn single_step(&mut self) {
+        if self.paused {
+            self.pending_steps += 1;
+        }
+    }
+
+    /// Halve the simulation speed
+    pub fn slow_down(&mut self) {
+        self.time_scale = (self.time_scale / 2.0).max(MIN_TIME_SCALE);
+    }
+
+    /// Double the simulation speed
+    pub fn speed_up(&mut self) {
+        self.time_scale = (self.time_scale * 2.0).min(MAX_TIME_SCALE);
+    }
+
+    /// Forget time not yet simulated, e.g. after restarting the model
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+        self.pending_steps = 0;
+    }
+
+    /// Number of steps of `timestep` seconds to take for a frame that
+    /// took `frame_time` seconds
+    pub fn advance(&mut self, frame_time: f32, timestep: f32) -> usize {
+        if self.paused {
+            let steps = self.pending_steps.min(MAX_STEPS_PER_FRAME);
+            self.pending_steps -= steps;
+            return steps;
+        }
+        if timestep <= 0.0 {
+            return 0;
+        }
+
+        self.accumulator += frame_time * self.time_scale;
+        let steps = (self.accumulator / timestep) as usize;
+        if steps > MAX_STEPS_PER_FRAME {
+            self.accumulator = 0.0;
+            MAX_STEPS_PER_FRAME
+        } else {
+            self.accumulator -= steps as f32 * timestep;
+            steps
+        }
+    }
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
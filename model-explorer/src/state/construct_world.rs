@@ -1,14 +1,17 @@
-use super::run_sim::RunSimState;
+use super::load_model::load_model;
+use super::run_sim::{clear_model, RunSimState};
 use crate::asset;
 use crate::component::{self, Collider as ColliderComponent};
-use crate::resource;
+use crate::resource::{self, physics::PhysicsWorld};
+use crate::system::physics::set_transform_position;
 use amethyst::{
-    assets::{AssetLoaderSystemData, Handle},
-    core::{nalgebra as ana, Transform},
+    assets::{AssetLoaderSystemData, AssetStorage, Handle, Loader},
+    core::Transform,
     prelude::Builder,
     renderer::{
         ActiveCamera, Camera, Material, MaterialDefaults, Mesh, PosNormTex, Shape, Texture,
     },
+    ui::{get_default_font, Anchor, FontAsset, LineMode, UiText, UiTransform},
     GameData, SimpleState, SimpleTrans, StateData, Trans,
 };
 use mjcf_parser::MJCFModelDesc;
@@ -19,11 +22,19 @@ use ncollide3d::{
 };
 use nphysics3d::world::World;
 use nphysics_user_data::ColliderUserData;
+use std::path::PathBuf;
+
+const INSPECTOR_WIDTH: f32 = 420.0;
+const INSPECTOR_HEIGHT: f32 = 300.0;
+const INSPECTOR_MARGIN: f32 = 10.0;
+const INSPECTOR_TEXT_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
 
 pub struct ConstructWorldState<N>
 where
     N: na::RealField,
 {
+    /// File the model is reloaded from
+    model_file: PathBuf,
     model_desc: MJCFModelDesc<N>,
 
     // basic shape mesh
@@ -35,8 +46,9 @@ where
 }
 
 impl<'a, N: na::RealField> ConstructWorldState<N> {
-    pub fn new(model_desc: MJCFModelDesc<N>) -> Self {
+    pub fn new(model_file: PathBuf, model_desc: MJCFModelDesc<N>) -> Self {
         Self {
+            model_file,
             model_desc,
             sphere: None,
             cube: None,
@@ -80,32 +92,37 @@ impl SimpleState for ConstructWorldState<f32> {
         println!("ConstructWorldState update");
 
         println!("Checking reload state");
-        {
-            let mut reload_model = data.world.write_resource::<resource::ReloadModel>();
-            if let resource::ReloadModel::Reload = *reload_model {
-                return Trans::Pop;
-            } else {
-                *reload_model = resource::ReloadModel::Run;
+        let reload_model = std::mem::replace(
+            &mut *data.world.write_resource::<resource::ReloadModel>(),
+            resource::ReloadModel::Run,
+        );
+        if let resource::ReloadModel::Reload = reload_model {
+            match load_model(data.world, &self.model_file) {
+                Some(model_desc) => self.model_desc = model_desc,
+                // keep the previous model running, with the error
+                // shown, until the model is fixed
+                None => return Trans::Push(Box::new(RunSimState)),
             }
+            clear_model(data.world);
         }
 
         let mat_defaults = data.world.read_resource::<MaterialDefaults>().0.clone();
 
         println!("Creating nphysics world");
-        // TODO(dschwab): get gravity from model desc
         let mut world = World::<f32>::new();
-        world.set_gravity(na::Vector3::z() * -9.81);
-
+        // also sets the gravity and timestep from the model
         self.model_desc.build(&mut world);
+        data.world
+            .add_resource(resource::JointGroups::from_model(self.model_desc.model()));
 
         println!("Constructing collider entities");
         for collider in world.colliders() {
-            println!("Create material component");
-            let material = if let Some(rgba) = collider
+            let user_data = collider
                 .user_data()
-                .and_then(|x| x.downcast_ref::<ColliderUserData<f32>>())
-                .and_then(|x| x.rgba)
-            {
+                .and_then(|x| x.downcast_ref::<ColliderUserData<f32>>());
+
+            println!("Create material component");
+            let material = if let Some(rgba) = user_data.and_then(|x| x.rgba) {
                 println!("Creating material");
                 let albedo = data
                     .world
@@ -131,17 +148,7 @@ impl SimpleState for ConstructWorldState<f32> {
             println!("Create mesh component");
             let shape = collider.shape().as_ref();
             let mut trans = Transform::default();
-            trans.set_xyz(
-                collider.position().translation.x,
-                collider.position().translation.y,
-                collider.position().translation.z,
-            );
-            *trans.rotation_mut() = ana::Unit::new_unchecked(ana::Quaternion::new(
-                collider.position().rotation.coords.w,
-                collider.position().rotation.coords.x,
-                collider.position().rotation.coords.y,
-                collider.position().rotation.coords.z,
-            ));
+            set_transform_position(&mut trans, collider.position());
 
             let mesh: Handle<Mesh> = if shape.as_shape::<shape::Plane<f32>>().is_some() {
                 data.world.exec(|loader: AssetLoaderSystemData<'_, Mesh>| {
@@ -199,6 +206,7 @@ impl SimpleState for ConstructWorldState<f32> {
                 .create_entity()
                 .with(ColliderComponent {
                     id: collider.handle(),
+                    group: user_data.map_or(0, |x| x.group),
                 })
                 .with(mesh)
                 .with(trans)
@@ -206,20 +214,56 @@ impl SimpleState for ConstructWorldState<f32> {
                 .build();
         }
 
+        // the physics system steps the world from now on
+        data.world.write_resource::<PhysicsWorld<f32>>().world = Some(world);
+        data.world.write_resource::<resource::SimClock>().reset();
+
+        println!("Create inspector");
+        let font = get_default_font(
+            &data.world.read_resource::<Loader>(),
+            &data.world.read_resource::<AssetStorage<FontAsset>>(),
+        );
+        let mut inspector_text = UiText::new(font, String::new(), INSPECTOR_TEXT_COLOR, 16.0);
+        inspector_text.line_mode = LineMode::Wrap;
+        inspector_text.align = Anchor::TopLeft;
+        data.world
+            .create_entity()
+            .with(UiTransform::new(
+                String::from("inspector"),
+                Anchor::TopLeft,
+                INSPECTOR_MARGIN + INSPECTOR_WIDTH / 2.0,
+                -(INSPECTOR_MARGIN + INSPECTOR_HEIGHT / 2.0),
+                1.0,
+                INSPECTOR_WIDTH,
+                INSPECTOR_HEIGHT,
+                0,
+            ))
+            .with(inspector_text)
+            .with(component::InspectorText)
+            .build();
+
         // TODO(dschwab): Create lights
 
         // TODO(dschwab): Create multiple cameras from model desc
         println!("Create camera");
-        let mut cam_trans = Transform::default();
-        cam_trans.set_z(50.0);
-        let fps_cam = component::FPSCamera::default();
-        let cam_entity = data
+        let camera_pose = std::mem::replace(
+            &mut *data.world.write_resource::<resource::CameraPose>(),
+            Default::default(),
+        );
+        let cam_trans = camera_pose.transform.unwrap_or_else(|| {
+            let mut cam_trans = Transform::default();
+            cam_trans.set_z(50.0);
+            cam_trans
+        });
+        let cam_builder = data
             .world
             .create_entity()
             .with(Camera::standard_3d(800.0, 600.0))
-            .with(cam_trans)
-            .with(fps_cam)
-            .build();
+            .with(cam_trans);
+        let cam_entity = match camera_pose.arc_ball {
+            Some(arc_ball) => cam_builder.with(arc_ball).build(),
+            None => cam_builder.with(component::FPSCamera::default()).build(),
+        };
         (*data.world.write_resource::<ActiveCamera>()).entity = Some(cam_entity);
 
         Trans::Push(Box::new(RunSimState))
@@ -1,54 +1,200 @@
 use super::ConstructWorldState;
-use crate::resource;
-use amethyst::{GameData, SimpleState, SimpleTrans, StateData, Trans};
-use mjcf_parser::MJCFModelDesc;
+use crate::component;
+use crate::resource::{self, model_watcher::RecordingResolver};
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    ecs::prelude::World,
+    prelude::Builder,
+    ui::{get_default_font, Anchor, FontAsset, LineMode, UiText, UiTransform},
+    GameData, SimpleState, SimpleTrans, StateData, Trans,
+};
+use mjcf_parser::{error::MJCFParseError, MJCFModelDesc, Model, ParseOptions};
 use nalgebra as na;
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+const ERROR_WIDTH: f32 = 800.0;
+const ERROR_HEIGHT: f32 = 200.0;
+const ERROR_MARGIN: f32 = 10.0;
+const ERROR_TEXT_COLOR: [f32; 4] = [1.0, 0.3, 0.3, 1.0];
+
 // TODO(dschwab): Figure out how to do logging
 
 pub struct LoadModelState {
     model_file: PathBuf,
+    start_paused: bool,
+    /// The model could not be loaded, and is tried again once one of
+    /// its files changes
+    failed: bool,
 }
 
 impl LoadModelState {
     /// Create a new LoadModelState
     pub fn new(model_file: PathBuf) -> Self {
-        Self { model_file }
+        Self {
+            model_file,
+            start_paused: false,
+            failed: false,
+        }
     }
 
-    /// Parse the specified model creating the construction
-    /// descriptors
-    fn load_model<N: na::RealField + From<f32> + FromStr>(&self) -> MJCFModelDesc<N>
-    where
-        // TODO(dschwab): Why is this necessary?
-        <N as FromStr>::Err: std::fmt::Display,
-    {
-        println!("load_model called");
-
-        let model_xml: String =
-            fs::read_to_string(&self.model_file).expect("Failed to read model file");
-
-        MJCFModelDesc::parse_xml_string(&model_xml).expect("Failed to parse model file xml")
+    /// Start the simulation paused, e.g. to inspect the initial pose
+    /// of the model
+    pub fn start_paused(mut self, start_paused: bool) -> Self {
+        self.start_paused = start_paused;
+        self
     }
 }
 
 impl SimpleState for LoadModelState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         data.world.add_resource(resource::ReloadModel::Run);
+        data.world
+            .add_resource(resource::SimClock::new(self.start_paused));
+        data.world.add_resource(resource::CameraPose::default());
+        data.world.add_resource(resource::LoadError::default());
+
+        create_error_text(data.world);
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        // show an empty scene until the model is fixed
+        if self.failed
+            && !data
+                .world
+                .read_resource::<resource::ModelWatcher>()
+                .has_changed()
+        {
+            return Trans::None;
+        }
+
         println!("LoadModelState update");
 
         // reset the reload model state
-        let mut reload_model = data.world.write_resource::<resource::ReloadModel>();
-        *reload_model = resource::ReloadModel::Run;
+        *data.world.write_resource::<resource::ReloadModel>() = resource::ReloadModel::Run;
+
+        match load_model::<f32>(data.world, &self.model_file) {
+            Some(model_desc) => {
+                self.failed = false;
+                Trans::Push(Box::new(ConstructWorldState::new(
+                    self.model_file.clone(),
+                    model_desc,
+                )))
+            }
+            None => {
+                self.failed = true;
+                Trans::None
+            }
+        }
+    }
+}
+
+/// Parse the model file creating the construction descriptors.
+///
+/// The model is reloaded when it, or a file it uses, changes. Errors
+/// are shown on screen until the model loads again, and `None` is
+/// returned.
+pub(super) fn load_model<N: na::RealField + From<f32> + FromStr>(
+    world: &mut World,
+    model_file: &Path,
+) -> Option<MJCFModelDesc<N>>
+where
+    // TODO(dschwab): Why is this necessary?
+    <N as FromStr>::Err: std::fmt::Display,
+{
+    println!("load_model called");
 
-        let model_desc = self.load_model::<f32>();
+    let resolver = RecordingResolver::default();
+    let options = ParseOptions::new().file_resolver(resolver.clone());
+    let loaded = MJCFModelDesc::load_file_with_report(model_file, &options).and_then(
+        |(model_desc, report)| match report.into_errors().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(model_desc),
+        },
+    );
 
-        Trans::Push(Box::new(ConstructWorldState::new(model_desc)))
+    // files that failed to be read are watched too, so the model is
+    // loaded once they are created
+    let mut files = resolver.files();
+    if let Ok(model_desc) = &loaded {
+        files.extend(texture_files(model_desc.model(), model_file, &options));
     }
+    let model_watcher = resource::ModelWatcher::watch(files).unwrap_or_else(|error| {
+        println!("Not watching the model files for changes: {}", error);
+        resource::ModelWatcher::default()
+    });
+    world.add_resource(model_watcher);
+
+    let mut load_error = world.write_resource::<resource::LoadError>();
+    match loaded {
+        Ok(model_desc) => {
+            load_error.message = None;
+            Some(model_desc)
+        }
+        Err(error) => {
+            let message = describe_error(&error);
+            println!("Failed to load model file: {}", message);
+            load_error.message = Some(message);
+            None
+        }
+    }
+}
+
+/// Paths of the model's texture files. The parser does not read
+/// textures, so they are not recorded by the file resolver.
+fn texture_files<N: na::RealField>(
+    model: &Model<N>,
+    model_file: &Path,
+    options: &ParseOptions,
+) -> Vec<PathBuf> {
+    model
+        .asset
+        .textures
+        .iter()
+        .filter_map(|texture| texture.file.as_ref())
+        .map(|file| {
+            let file = match &model.compiler.texturedir {
+                Some(texturedir) => Path::new(texturedir).join(file),
+                None => PathBuf::from(file),
+            };
+            options
+                .get_file_resolver()
+                .resolve(Some(model_file), &file.to_string_lossy())
+        })
+        .collect()
+}
+
+/// The error, prefixed with where in the model it happened
+fn describe_error(error: &MJCFParseError) -> String {
+    match error.location() {
+        Some(location) => format!("{}: {}", location, error),
+        None => error.to_string(),
+    }
+}
+
+/// Create the UI text showing why the model failed to load. It stays
+/// when the model is cleared.
+fn create_error_text(world: &mut World) {
+    let font = get_default_font(
+        &world.read_resource::<Loader>(),
+        &world.read_resource::<AssetStorage<FontAsset>>(),
+    );
+    let mut error_text = UiText::new(font, String::new(), ERROR_TEXT_COLOR, 16.0);
+    error_text.line_mode = LineMode::Wrap;
+    error_text.align = Anchor::BottomLeft;
+    world
+        .create_entity()
+        .with(UiTransform::new(
+            String::from("load_error"),
+            Anchor::BottomLeft,
+            ERROR_MARGIN + ERROR_WIDTH / 2.0,
+            ERROR_MARGIN + ERROR_HEIGHT / 2.0,
+            1.0,
+            ERROR_WIDTH,
+            ERROR_HEIGHT,
+            0,
+        ))
+        .with(error_text)
+        .with(component::ErrorText)
+        .build();
 }
@@ -1,9 +1,23 @@
-use crate::resource;
+use crate::component;
+use crate::resource::{self, physics::PhysicsWorld};
 use amethyst::{
-    input::is_key_down, renderer::VirtualKeyCode, GameData, SimpleState, SimpleTrans, StateData,
-    StateEvent, Trans,
+    core::Transform,
+    ecs::prelude::{Entity, Join, World},
+    input::is_key_down,
+    renderer::{ActiveCamera, VirtualKeyCode},
+    GameData, SimpleState, SimpleTrans, StateData, StateEvent, Trans,
 };
 
+/// Keys that show or hide geom groups 0 to 5
+const GROUP_KEYS: [VirtualKeyCode; resource::GROUP_COUNT] = [
+    VirtualKeyCode::Key0,
+    VirtualKeyCode::Key1,
+    VirtualKeyCode::Key2,
+    VirtualKeyCode::Key3,
+    VirtualKeyCode::Key4,
+    VirtualKeyCode::Key5,
+];
+
 pub struct RunSimState;
 
 impl SimpleState for RunSimState {
@@ -17,9 +31,7 @@ impl SimpleState for RunSimState {
                 // restart the simulation
                 println!("Restart simulation");
 
-                // TODO(dschwab): Should probably filter the types of
-                // entities I'm deleting by which component they have.
-                data.world.delete_all();
+                clear_model(data.world);
 
                 *data.world.write_resource::<resource::ReloadModel>() =
                     resource::ReloadModel::Restart;
@@ -28,18 +40,150 @@ impl SimpleState for RunSimState {
             } else if is_key_down(&event, VirtualKeyCode::L) {
                 println!("Reload model");
 
-                // TODO(dschwab): Should probably filter the types of
-                // entities I'm deleting by which component they have.
-                data.world.delete_all();
+                return reload_model(data.world);
+            } else if is_key_down(&event, VirtualKeyCode::C) {
+                toggle_camera_mode(data.world);
+            } else if is_key_down(&event, VirtualKeyCode::K) {
+                let mut debug_draw = data.world.write_resource::<resource::DebugDraw>();
+                debug_draw.contacts = !debug_draw.contacts;
+            } else if is_key_down(&event, VirtualKeyCode::B) {
+                let mut debug_draw = data.world.write_resource::<resource::DebugDraw>();
+                debug_draw.aabbs = !debug_draw.aabbs;
+            } else if is_key_down(&event, VirtualKeyCode::J) {
+                let mut debug_draw = data.world.write_resource::<resource::DebugDraw>();
+                debug_draw.joints = !debug_draw.joints;
+            } else if is_key_down(&event, VirtualKeyCode::M) {
+                let mut debug_draw = data.world.write_resource::<resource::DebugDraw>();
+                debug_draw.inertia = !debug_draw.inertia;
+            } else if is_key_down(&event, VirtualKeyCode::F) {
+                let mut debug_draw = data.world.write_resource::<resource::DebugDraw>();
+                debug_draw.frames = !debug_draw.frames;
+            } else if let Some(group) = GROUP_KEYS.iter().position(|key| is_key_down(&event, *key))
+            {
+                let mut group_visibility = data.world.write_resource::<resource::GroupVisibility>();
+                group_visibility.toggle(group);
+                println!(
+                    "Geom group {} {}",
+                    group,
+                    if group_visibility.is_visible(group as i32) {
+                        "shown"
+                    } else {
+                        "hidden"
+                    }
+                );
+            } else if is_key_down(&event, VirtualKeyCode::Space) {
+                let mut clock = data.world.write_resource::<resource::SimClock>();
+                clock.toggle_pause();
+                println!(
+                    "Simulation {}",
+                    if clock.is_paused() {
+                        "paused"
+                    } else {
+                        "resumed"
+                    }
+                );
+            } else if is_key_down(&event, VirtualKeyCode::Period) {
+                data.world
+                    .write_resource::<resource::SimClock>()
+                    .single_step();
+            } else if is_key_down(&event, VirtualKeyCode::LBracket) {
+                let mut clock = data.world.write_resource::<resource::SimClock>();
+                clock.slow_down();
+                println!("Simulation speed {}x", clock.time_scale());
+            } else if is_key_down(&event, VirtualKeyCode::RBracket) {
+                let mut clock = data.world.write_resource::<resource::SimClock>();
+                clock.speed_up();
+                println!("Simulation speed {}x", clock.time_scale());
+            }
+        }
 
-                // reload the model and restart
-                *data.world.write_resource::<resource::ReloadModel>() =
-                    resource::ReloadModel::Reload;
+        Trans::None
+    }
 
-                return Trans::Pop;
-            }
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        if data
+            .world
+            .read_resource::<resource::ModelWatcher>()
+            .has_changed()
+        {
+            println!("Model changed, reloading");
+
+            return reload_model(data.world);
         }
 
         Trans::None
     }
 }
+
+/// Load the model again and restart the simulation. The model is
+/// only cleared once the new one loaded.
+fn reload_model(world: &mut World) -> SimpleTrans {
+    *world.write_resource::<resource::ReloadModel>() = resource::ReloadModel::Reload;
+
+    Trans::Pop
+}
+
+/// Delete the entities and physics world of the model, so it can be
+/// constructed again. The camera pose is kept for the next camera,
+/// and the error text is kept.
+pub(super) fn clear_model(world: &mut World) {
+    save_camera_pose(world);
+
+    let entities: Vec<Entity> = (
+        &world.entities(),
+        !&world.read_storage::<component::ErrorText>(),
+    )
+        .join()
+        .map(|(entity, _)| entity)
+        .collect();
+    world
+        .delete_entities(&entities)
+        .expect("model entities are not alive");
+    world.write_resource::<PhysicsWorld<f32>>().world = None;
+    *world.write_resource::<resource::Selection>() = Default::default();
+    *world.write_resource::<resource::Perturbation>() = Default::default();
+}
+
+/// Switch the active camera between the FPS and orbit camera. The
+/// orbit camera starts out orbiting the point the camera looks at.
+fn toggle_camera_mode(world: &mut World) {
+    let camera = match world.read_resource::<ActiveCamera>().entity {
+        Some(camera) => camera,
+        None => return,
+    };
+    let mut fps_cams = world.write_storage::<component::FPSCamera>();
+    let mut arc_balls = world.write_storage::<component::ArcBallCamera>();
+
+    if fps_cams.remove(camera).is_some() {
+        let arc_ball = world
+            .read_storage::<Transform>()
+            .get(camera)
+            .map(component::ArcBallCamera::from_transform)
+            .unwrap_or_default();
+        arc_balls
+            .insert(camera, arc_ball)
+            .expect("active camera is not alive");
+        println!("Orbit camera");
+    } else {
+        arc_balls.remove(camera);
+        fps_cams
+            .insert(camera, component::FPSCamera::default())
+            .expect("active camera is not alive");
+        println!("FPS camera");
+    }
+}
+
+fn save_camera_pose(world: &mut World) {
+    let camera = match world.read_resource::<ActiveCamera>().entity {
+        Some(camera) => camera,
+        None => return,
+    };
+    let camera_pose = resource::CameraPose {
+        transform: world.read_storage::<Transform>().get(camera).cloned(),
+        arc_ball: world
+            .read_storage::<component::ArcBallCamera>()
+            .get(camera)
+            .cloned(),
+    };
+    *world.write_resource::<resource::CameraPose>() = camera_pose;
+}
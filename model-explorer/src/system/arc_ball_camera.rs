@@ -1,23 +1,118 @@
+use crate::component;
+use crate::resource;
 use amethyst::{
-    core::Transform,
-    ecs::prelude::{Read, ReadStorage, System, WriteStorage},
+    core::{nalgebra as na, Transform},
+    ecs::prelude::{Read, ReaderId, Resources, System, SystemData, WriteStorage},
     input::InputHandler,
-    renderer::ActiveCamera,
+    renderer::{ActiveCamera, VirtualKeyCode},
+    shrev::EventChannel,
+    winit::{Event, MouseScrollDelta, WindowEvent},
 };
-use nalgebra as na;
+use std::f32::consts::FRAC_PI_2;
 
-pub struct ArcBallCameraSystem;
+const ROTATE_SENSITIVITY: f64 = 0.01f64;
+/// Fraction of the distance to the target panned per pixel
+const PAN_SENSITIVITY: f64 = 0.002f64;
+/// Fraction of the distance to the target zoomed per scroll line
+const ZOOM_SENSITIVITY: f32 = 0.1;
+/// Pixels in one scroll line, for touchpads that scroll by pixel
+const PIXELS_PER_LINE: f32 = 20.0;
+/// Keeps the camera from flipping over the poles
+const MAX_ELEVATION: f32 = FRAC_PI_2 - 0.01;
+
+/// Orbits the active camera, when it has an `ArcBallCamera`, around
+/// its target. Dragging rotates the camera, dragging with shift held
+/// pans the target and scrolling zooms.
+#[derive(Default)]
+pub struct ArcBallCameraSystem {
+    event_reader: Option<ReaderId<Event>>,
+}
 
 impl<'s> System<'s> for ArcBallCameraSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = (
         Read<'s, ActiveCamera>,
+        WriteStorage<'s, component::ArcBallCamera>,
+        Read<'s, resource::mouse_drag::Variant>,
+        Read<'s, resource::Perturbation>,
+        Read<'s, InputHandler<(), ()>>,
+        Read<'s, EventChannel<Event>>,
         WriteStorage<'s, Transform>,
-        Read<'s, InputHandler>,
     );
 
-    fn run(&mut self, (active_camera, mut transforms, input_handler): Self::SystemData) {
-        let mut transform = transforms.get_mut(active_camera.entity).unwrap();
+    fn run(
+        &mut self,
+        (
+            active_camera,
+            mut arc_balls,
+            mouse_drag,
+            perturbation,
+            input_handler,
+            events,
+            mut transforms,
+        ): Self::SystemData,
+    ) {
+        // read the events every frame, so old scrolling does not zoom
+        // a camera that was just switched to
+        let mut scroll = 0.0;
+        for event in events.read(self.event_reader.as_mut().expect("system is not set up")) {
+            if let Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } = event
+            {
+                scroll += match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32 / PIXELS_PER_LINE,
+                };
+            }
+        }
+
+        let active_camera_entity = match active_camera.entity {
+            Some(entity) => entity,
+            None => return,
+        };
+        let arc_ball = match arc_balls.get_mut(active_camera_entity) {
+            Some(arc_ball) => arc_ball,
+            None => return,
+        };
+
+        // dragging a body does not move the camera
+        let mouse_delta = if perturbation.is_active() {
+            None
+        } else {
+            mouse_drag.delta()
+        };
+        if let Some(mouse_delta) = mouse_delta {
+            let panning = input_handler.key_is_down(VirtualKeyCode::LShift)
+                || input_handler.key_is_down(VirtualKeyCode::RShift);
+            if panning {
+                let pan: na::Vector2<f32> =
+                    na::convert(PAN_SENSITIVITY * f64::from(arc_ball.distance) * mouse_delta);
+                // screen y grows downwards
+                let offset = arc_ball.rotation() * na::Vector3::new(-pan.x, pan.y, 0.0);
+                arc_ball.target += offset;
+            } else {
+                let rotate: na::Vector2<f32> = na::convert(-ROTATE_SENSITIVITY * mouse_delta);
+                arc_ball.yaw += rotate.x;
+                arc_ball.elevation = (arc_ball.elevation - rotate.y)
+                    .max(-MAX_ELEVATION)
+                    .min(MAX_ELEVATION);
+            }
+        }
+
+        if scroll != 0.0 {
+            arc_ball.distance = (arc_ball.distance * (1.0 - ZOOM_SENSITIVITY).powf(scroll))
+                .max(component::arc_ball_camera::MIN_DISTANCE);
+        }
+
+        if let Some(transform) = transforms.get_mut(active_camera_entity) {
+            arc_ball.update_transform(transform);
+        }
+    }
 
-        
+    fn setup(&mut self, res: &mut Resources) {
+        Self::SystemData::setup(res);
+        self.event_reader = Some(res.fetch_mut::<EventChannel<Event>>().register_reader());
     }
 }
@@ -0,0 +1,236 @@
+use crate::resource::{physics::PhysicsWorld, DebugDraw, GroupVisibility, JointGroups};
+use amethyst::{
+    ecs::prelude::{Read, System, WriteExpect},
+    renderer::{DebugLines, Rgba},
+};
+use nalgebra as na;
+use ncollide3d::bounding_volume::{BoundingVolume, AABB};
+use nphysics3d::joint::{PrismaticJoint, RevoluteJoint};
+use nphysics3d::object::{BodyPart, Multibody, MultibodyLink};
+
+/// Length of the drawn contact normals
+const NORMAL_LENGTH: f32 = 0.1;
+/// Length of the lines marking a contact point
+const POINT_SIZE: f32 = 0.02;
+/// Length of the drawn joint axes
+const JOINT_AXIS_LENGTH: f32 = 0.2;
+/// Length of the axes of body frames, the world frame is twice as big
+const FRAME_SIZE: f32 = 0.25;
+
+const POINT_COLOR: Rgba = Rgba(1.0, 1.0, 0.0, 1.0);
+const NORMAL_COLOR: Rgba = Rgba(0.0, 1.0, 1.0, 1.0);
+const AABB_COLOR: Rgba = Rgba(0.0, 1.0, 0.0, 1.0);
+const JOINT_COLOR: Rgba = Rgba(1.0, 0.0, 1.0, 1.0);
+const INERTIA_COLOR: Rgba = Rgba(1.0, 1.0, 1.0, 1.0);
+/// Colors of the x, y and z axes of frames
+const AXIS_COLORS: [Rgba; 3] = [
+    Rgba(1.0, 0.0, 0.0, 1.0),
+    Rgba(0.0, 1.0, 0.0, 1.0),
+    Rgba(0.0, 0.0, 1.0, 1.0),
+];
+
+/// Draws the debug layers turned on in `DebugDraw`
+pub struct DebugDrawSystem;
+
+impl<'s> System<'s> for DebugDrawSystem {
+    type SystemData = (
+        Read<'s, DebugDraw>,
+        Read<'s, PhysicsWorld<f32>>,
+        Read<'s, GroupVisibility>,
+        Read<'s, JointGroups>,
+        WriteExpect<'s, DebugLines>,
+    );
+
+    fn run(
+        &mut self,
+        (debug_draw, physics, group_visibility, joint_groups, mut debug_lines): Self::SystemData,
+    ) {
+        let world = match physics.world.as_ref() {
+            Some(world) => world,
+            None => return,
+        };
+
+        if debug_draw.contacts {
+            for (_, _, _, manifold) in world.collider_world().contact_pairs(true) {
+                for tracked in manifold.contacts() {
+                    let contact = &tracked.contact;
+                    let point = contact.world1;
+                    let normal = contact.normal.into_inner();
+                    draw_point(&mut debug_lines, &point, POINT_COLOR);
+                    draw_line(
+                        &mut debug_lines,
+                        &point,
+                        &(point + normal * NORMAL_LENGTH),
+                        NORMAL_COLOR,
+                    );
+                    // contact forces are not drawn, nphysics does not
+                    // expose the impulses its solver applied
+                }
+            }
+        }
+
+        if debug_draw.aabbs {
+            for collider in world.colliders() {
+                // the broad phase uses the shape's bounding box
+                // loosened by the collider's margin
+                let aabb = collider
+                    .shape()
+                    .aabb(collider.position())
+                    .loosened(collider.margin());
+                let is_bounded = aabb.mins().coords.iter().all(|value| value.is_finite())
+                    && aabb.maxs().coords.iter().all(|value| value.is_finite());
+                if is_bounded {
+                    draw_aabb(&mut debug_lines, &aabb);
+                }
+            }
+        }
+
+        if debug_draw.frames {
+            draw_frame(
+                &mut debug_lines,
+                &na::Isometry3::identity(),
+                2.0 * FRAME_SIZE,
+            );
+        }
+        if debug_draw.joints || debug_draw.inertia || debug_draw.frames {
+            let multibodies = world
+                .bodies()
+                .filter_map(|body| body.downcast_ref::<Multibody<f32>>());
+            for multibody in multibodies {
+                for link in multibody.links() {
+                    if debug_draw.joints
+                        && group_visibility.is_visible(joint_groups.group(link.name()))
+                    {
+                        draw_joint(&mut debug_lines, &link);
+                    }
+                    if debug_draw.inertia {
+                        draw_inertia(&mut debug_lines, &link);
+                    }
+                    if debug_draw.frames {
+                        draw_frame(&mut debug_lines, &link.position(), FRAME_SIZE);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn draw_line(
+    debug_lines: &mut DebugLines,
+    start: &na::Point3<f32>,
+    end: &na::Point3<f32>,
+    color: Rgba,
+) {
+    debug_lines.draw_line(
+        [start.x, start.y, start.z].into(),
+        [end.x, end.y, end.z].into(),
+        color,
+    );
+}
+
+/// Mark a point with a small cross
+fn draw_point(debug_lines: &mut DebugLines, point: &na::Point3<f32>, color: Rgba) {
+    for axis in &[na::Vector3::x(), na::Vector3::y(), na::Vector3::z()] {
+        let offset = axis * (POINT_SIZE / 2.0);
+        draw_line(debug_lines, &(point - offset), &(point + offset), color);
+    }
+}
+
+fn draw_aabb(debug_lines: &mut DebugLines, aabb: &AABB<f32>) {
+    let axes = na::Matrix3::from_diagonal(&aabb.half_extents());
+    draw_box(debug_lines, &aabb.center(), &axes, AABB_COLOR);
+}
+
+/// Draw the edges of a box. The columns of `axes` go from the center
+/// to the middle of three faces.
+fn draw_box(
+    debug_lines: &mut DebugLines,
+    center: &na::Point3<f32>,
+    axes: &na::Matrix3<f32>,
+    color: Rgba,
+) {
+    let corner = |x: bool, y: bool, z: bool| {
+        let sign = |positive| if positive { 1.0 } else { -1.0 };
+        center + axes * na::Vector3::new(sign(x), sign(y), sign(z))
+    };
+    for &a in &[false, true] {
+        for &b in &[false, true] {
+            draw_line(
+                debug_lines,
+                &corner(false, a, b),
+                &corner(true, a, b),
+                color,
+            );
+            draw_line(
+                debug_lines,
+                &corner(a, false, b),
+                &corner(a, true, b),
+                color,
+            );
+            draw_line(
+                debug_lines,
+                &corner(a, b, false),
+                &corner(a, b, true),
+                color,
+            );
+        }
+    }
+}
+
+/// Draw the x, y and z axes of a frame in red, green and blue
+fn draw_frame(debug_lines: &mut DebugLines, position: &na::Isometry3<f32>, size: f32) {
+    let origin = position * na::Point3::origin();
+    for (axis, color) in AXIS_COLORS.iter().enumerate() {
+        let end = position * na::Point3::from(na::Vector3::ith(axis, size));
+        draw_line(debug_lines, &origin, &end, *color);
+    }
+}
+
+/// Mark the anchor of the joint of a link, and draw the axis of hinge
+/// and slide joints
+fn draw_joint(debug_lines: &mut DebugLines, link: &MultibodyLink<f32>) {
+    let position = link.position();
+    // the link frame is `body_shift` away from the joint
+    let anchor = position * na::Point3::from(-link.body_shift());
+    let joint = link.joint();
+    let axis = if let Some(revolute) = joint.downcast_ref::<RevoluteJoint<f32>>() {
+        Some(revolute.axis())
+    } else if let Some(prismatic) = joint.downcast_ref::<PrismaticJoint<f32>>() {
+        Some(prismatic.axis())
+    } else {
+        None
+    };
+
+    draw_point(debug_lines, &anchor, JOINT_COLOR);
+    if let Some(axis) = axis {
+        let offset = position.rotation * axis.into_inner() * (JOINT_AXIS_LENGTH / 2.0);
+        draw_line(
+            debug_lines,
+            &(anchor - offset),
+            &(anchor + offset),
+            JOINT_COLOR,
+        );
+    }
+}
+
+/// Mark the center of mass of a link, and draw the box of uniform
+/// density with the same mass and inertia as the link
+fn draw_inertia(debug_lines: &mut DebugLines, link: &MultibodyLink<f32>) {
+    let inertia = link.inertia();
+    let mass = inertia.linear;
+    let center_of_mass = link.center_of_mass();
+    draw_point(debug_lines, &center_of_mass, INERTIA_COLOR);
+    if mass <= 0.0 {
+        return;
+    }
+
+    // the box's sides are along the principal axes of inertia. A box
+    // with sides x, y and z has moments m (y² + z²) / 12 and so on.
+    let eigen = na::SymmetricEigen::new(inertia.angular);
+    let moments = eigen.eigenvalues;
+    let moments_sum = moments.sum();
+    let half_extents =
+        moments.map(|moment| (6.0 * (moments_sum - 2.0 * moment) / mass).max(0.0).sqrt() / 2.0);
+    let axes = eigen.eigenvectors * na::Matrix3::from_diagonal(&half_extents);
+    draw_box(debug_lines, &center_of_mass, &axes, INERTIA_COLOR);
+}
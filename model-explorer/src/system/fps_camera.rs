@@ -1,13 +1,11 @@
 use crate::component;
 use crate::resource;
-use crate::resource::mouse_drag;
 use amethyst::{
     core::{nalgebra as na, Transform},
     ecs::prelude::{Read, ReadStorage, System, WriteStorage},
     input::InputHandler,
     renderer::{ActiveCamera, VirtualKeyCode},
 };
-use sm::{AsEnum, Machine};
 
 const MOUSE_SENSITIVITY: f64 = 0.01f64;
 const MOVE_SPEED: f32 = 0.1;
@@ -20,31 +18,22 @@ impl<'s> System<'s> for FPSCamera {
         Read<'s, ActiveCamera>,
         ReadStorage<'s, component::FPSCamera>,
         Read<'s, resource::mouse_drag::Variant>,
+        Read<'s, resource::Perturbation>,
         Read<'s, InputHandler<(), ()>>,
         WriteStorage<'s, Transform>,
     );
 
     fn run(
         &mut self,
-        (active_camera, fps_cams, mouse_drag, input_handler, mut transforms): Self::SystemData,
+        (active_camera, fps_cams, mouse_drag, perturbation, input_handler, mut transforms): Self::SystemData,
     ) {
         if let Some(active_camera_entity) = active_camera.entity.as_ref() {
             if fps_cams.get(*active_camera_entity).is_some() {
-                let mouse_delta = match (*mouse_drag).clone() {
-                    mouse_drag::Variant::DraggingByClicked(s) => match s.state().as_enum() {
-                        mouse_drag::StateVariant::Dragging(s) => {
-                            Some(s.delta_end_position - s.delta_start_position)
-                        }
-                        _ => unreachable!(),
-                    },
-                    mouse_drag::Variant::FinishDraggingByNotClicked(s) => match s.state().as_enum()
-                    {
-                        mouse_drag::StateVariant::FinishDragging(s) => {
-                            Some(s.end_position - s.delta_start_position)
-                        }
-                        _ => unreachable!(),
-                    },
-                    _ => None,
+                // dragging a body does not move the camera
+                let mouse_delta = if perturbation.is_active() {
+                    None
+                } else {
+                    mouse_drag.delta()
                 };
 
                 let transform = transforms
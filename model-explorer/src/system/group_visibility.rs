@@ -0,0 +1,31 @@
+use crate::component;
+use crate::resource::GroupVisibility;
+use amethyst::{
+    ecs::prelude::{Entities, Join, Read, ReadStorage, System, WriteStorage},
+    renderer::Hidden,
+};
+
+/// Hides the entities of colliders in hidden geom groups
+pub struct GroupVisibilitySystem;
+
+impl<'s> System<'s> for GroupVisibilitySystem {
+    type SystemData = (
+        Read<'s, GroupVisibility>,
+        Entities<'s>,
+        ReadStorage<'s, component::Collider>,
+        WriteStorage<'s, Hidden>,
+    );
+
+    fn run(&mut self, (group_visibility, entities, colliders, mut hiddens): Self::SystemData) {
+        for (entity, collider) in (&entities, &colliders).join() {
+            let visible = group_visibility.is_visible(collider.group);
+            if visible && hiddens.contains(entity) {
+                hiddens.remove(entity);
+            } else if !visible && !hiddens.contains(entity) {
+                hiddens
+                    .insert(entity, Hidden)
+                    .expect("collider entity is not alive");
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::component;
+use crate::resource::{physics::PhysicsWorld, Selection};
+use crate::system::picking::display_name;
+use amethyst::{
+    ecs::prelude::{Join, Read, ReadStorage, System, WriteStorage},
+    ui::UiText,
+};
+use nalgebra as na;
+use nphysics3d::{
+    object::{BodyPartHandle, ColliderHandle},
+    world::World,
+};
+use nphysics_user_data::ColliderUserData;
+use std::fmt::Write;
+
+/// Shows the name, shape, mass, pose, velocity and contacts of the
+/// selected collider in the inspector text, updated every frame
+pub struct InspectorSystem;
+
+impl<'s> System<'s> for InspectorSystem {
+    type SystemData = (
+        Read<'s, Selection>,
+        Read<'s, PhysicsWorld<f32>>,
+        ReadStorage<'s, component::InspectorText>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (selection, physics, inspector_texts, mut ui_texts): Self::SystemData) {
+        let description = match (selection.collider(), physics.world.as_ref()) {
+            (Some(collider), Some(world)) => describe_collider(world, collider).unwrap_or_default(),
+            _ => String::new(),
+        };
+
+        for (ui_text, _) in (&mut ui_texts, &inspector_texts).join() {
+            if ui_text.text != description {
+                ui_text.text = description.clone();
+            }
+        }
+    }
+}
+
+fn describe_collider(world: &World<f32>, handle: ColliderHandle) -> Option<String> {
+    let collider = world.collider(handle)?;
+    let user_data = collider
+        .user_data()
+        .and_then(|user_data| user_data.downcast_ref::<ColliderUserData<f32>>());
+
+    // writing to a String can not fail
+    let mut text = String::new();
+    writeln!(text, "{}", display_name(collider.name())).unwrap();
+    if let Some(geom_type) = user_data.and_then(|user_data| user_data.geom_type.as_ref()) {
+        writeln!(text, "geom: {}", geom_type).unwrap();
+    }
+
+    let body_part = collider.body_part(0);
+    match world.body_part(body_part) {
+        Some(part) if body_part != BodyPartHandle::ground() => {
+            let inertia = part.inertia();
+            let position = part.position();
+            let velocity = part.velocity();
+            let (roll, pitch, yaw) = position.rotation.euler_angles();
+            writeln!(text, "mass: {:.4}", inertia.linear).unwrap();
+            writeln!(text, "inertia: {}", vector(&inertia.angular.diagonal())).unwrap();
+            writeln!(text, "position: {}", vector(&position.translation.vector)).unwrap();
+            writeln!(
+                text,
+                "rotation: {} (roll, pitch, yaw)",
+                vector(&na::Vector3::new(roll, pitch, yaw))
+            )
+            .unwrap();
+            writeln!(text, "linear velocity: {}", vector(&velocity.linear)).unwrap();
+            writeln!(text, "angular velocity: {}", vector(&velocity.angular)).unwrap();
+        }
+        _ => writeln!(text, "static").unwrap(),
+    }
+
+    let mut contacts = vec![];
+    for (collider1, collider2, _, manifold) in world.collider_world().contact_pairs(true) {
+        let other = if collider1.handle() == handle {
+            collider2
+        } else if collider2.handle() == handle {
+            collider1
+        } else {
+            continue;
+        };
+        let count = manifold.contacts().count();
+        if count > 0 {
+            contacts.push(format!("{} ({})", display_name(other.name()), count));
+        }
+    }
+    if contacts.is_empty() {
+        writeln!(text, "contacts: none").unwrap();
+    } else {
+        writeln!(text, "contacts: {}", contacts.join(", ")).unwrap();
+    }
+
+    if let Some(user_data) = user_data {
+        if let Some(rgba) = user_data.rgba {
+            writeln!(text, "rgba: {}", vector(&rgba.coords)).unwrap();
+        }
+        writeln!(
+            text,
+            "friction: {:.4} torsional, {:.4} rolling",
+            user_data.torsional_friction, user_data.rolling_friction
+        )
+        .unwrap();
+    }
+    Some(text)
+}
+
+/// Values of a vector, rounded for display
+fn vector<'v, I: IntoIterator<Item = &'v f32>>(values: I) -> String {
+    let values: Vec<String> = values
+        .into_iter()
+        .map(|value| format!("{:.3}", value))
+        .collect();
+    format!("[{}]", values.join(", "))
+}
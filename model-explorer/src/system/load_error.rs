@@ -0,0 +1,27 @@
+use crate::component;
+use crate::resource::LoadError;
+use amethyst::{
+    ecs::prelude::{Join, Read, ReadStorage, System, WriteStorage},
+    ui::UiText,
+};
+
+/// Shows the error of the last failed model load in the error text,
+/// and clears it once the model loads
+pub struct LoadErrorSystem;
+
+impl<'s> System<'s> for LoadErrorSystem {
+    type SystemData = (
+        Read<'s, LoadError>,
+        ReadStorage<'s, component::ErrorText>,
+        WriteStorage<'s, UiText>,
+    );
+
+    fn run(&mut self, (load_error, error_texts, mut ui_texts): Self::SystemData) {
+        let message = load_error.message.as_ref().map_or("", String::as_str);
+        for (ui_text, _) in (&mut ui_texts, &error_texts).join() {
+            if ui_text.text != message {
+                ui_text.text = message.to_string();
+            }
+        }
+    }
+}
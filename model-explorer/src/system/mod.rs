@@ -1,5 +1,19 @@
+mod arc_ball_camera;
+mod debug_draw;
 mod fps_camera;
+mod group_visibility;
+mod inspector;
+mod load_error;
 pub mod mouse_drag;
+pub mod perturbation;
 pub mod physics;
+pub mod picking;
 
+pub use arc_ball_camera::ArcBallCameraSystem;
+pub use debug_draw::DebugDrawSystem;
 pub use fps_camera::FPSCamera;
+pub use group_visibility::GroupVisibilitySystem;
+pub use inspector::InspectorSystem;
+pub use load_error::LoadErrorSystem;
+pub use perturbation::PerturbationSystem;
+pub use picking::PickingSystem;
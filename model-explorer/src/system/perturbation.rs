@@ -0,0 +1,199 @@
+use crate::resource::{
+    mouse_drag,
+    perturbation::{ActivePerturbation, Perturbation},
+    physics::PhysicsWorld,
+    GroupVisibility,
+};
+use crate::system::picking;
+use amethyst::{
+    core::GlobalTransform,
+    ecs::prelude::{Read, ReadExpect, ReadStorage, System, Write, WriteExpect},
+    input::InputHandler,
+    renderer::{ActiveCamera, Camera, DebugLines, Rgba, ScreenDimensions, VirtualKeyCode},
+};
+use nalgebra as na;
+use nphysics3d::{
+    algebra::ForceType,
+    force_generator::ForceGenerator,
+    object::{BodyPartHandle, BodySet},
+    solver::IntegrationParameters,
+};
+use sm::{AsEnum, Machine};
+use std::sync::{Arc, Mutex};
+
+/// Stiffness of the spring per unit of mass of the dragged body
+const STIFFNESS: f32 = 100.0;
+/// Damping of the spring per unit of mass, about critical damping
+const DAMPING: f32 = 20.0;
+
+const SPRING_COLOR: Rgba = Rgba(1.0, 0.6, 0.1, 1.0);
+
+/// Pulls a point on a body part towards a target with a damped
+/// spring, scaled by the mass of the part
+pub struct SpringForce {
+    body_part: BodyPartHandle,
+    local_point: na::Point3<f32>,
+    target: Arc<Mutex<na::Point3<f32>>>,
+}
+
+impl ForceGenerator<f32> for SpringForce {
+    fn apply(&mut self, _: &IntegrationParameters<f32>, bodies: &mut BodySet<f32>) -> bool {
+        let target = *self.target.lock().expect("spring target lock is poisoned");
+        let body = match bodies.body_mut(self.body_part.0) {
+            Some(body) => body,
+            // the body was removed, so this generator is removed too
+            None => return false,
+        };
+
+        let (point, force) = match body.part(self.body_part.1) {
+            Some(part) => {
+                let mass = part.inertia().linear;
+                let point = part.position() * self.local_point;
+                let velocity = part.velocity().linear;
+                let force = (target - point) * (STIFFNESS * mass) - velocity * (DAMPING * mass);
+                (point, force)
+            }
+            None => return false,
+        };
+        body.apply_force_at_point(self.body_part.1, &force, &point, ForceType::Force, true);
+        true
+    }
+}
+
+/// Drags a dynamic body around while the mouse is dragged with ctrl
+/// held, by attaching a spring between the grabbed point and the
+/// cursor, and draws the spring
+pub struct PerturbationSystem;
+
+impl<'s> System<'s> for PerturbationSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Read<'s, mouse_drag::Variant>,
+        Read<'s, InputHandler<(), ()>>,
+        Read<'s, ActiveCamera>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, GlobalTransform>,
+        ReadExpect<'s, ScreenDimensions>,
+        Write<'s, PhysicsWorld<f32>>,
+        Read<'s, GroupVisibility>,
+        Write<'s, Perturbation>,
+        WriteExpect<'s, DebugLines>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mouse_drag,
+            input_handler,
+            active_camera,
+            cameras,
+            global_transforms,
+            screen_dimensions,
+            mut physics,
+            group_visibility,
+            mut perturbation,
+            mut debug_lines,
+        ): Self::SystemData,
+    ) {
+        let world = match physics.world.as_mut() {
+            Some(world) => world,
+            None => {
+                perturbation.active = None;
+                return;
+            }
+        };
+        let cursor_ray = |position| {
+            picking::cursor_ray(
+                position,
+                &active_camera,
+                &cameras,
+                &global_transforms,
+                &screen_dimensions,
+            )
+        };
+
+        match &*mouse_drag {
+            mouse_drag::Variant::StartDraggingByClicked(s) => {
+                let ctrl_down = input_handler.key_is_down(VirtualKeyCode::LControl)
+                    || input_handler.key_is_down(VirtualKeyCode::RControl);
+                if !ctrl_down || perturbation.is_active() {
+                    return;
+                }
+                let start_position = match s.state().as_enum() {
+                    mouse_drag::StateVariant::StartDragging(s) => s.start_position,
+                    _ => unreachable!(),
+                };
+                let ray = match cursor_ray(start_position) {
+                    Some(ray) => ray,
+                    None => return,
+                };
+                let (collider, point) = match picking::cast_ray(world, &ray, &group_visibility) {
+                    Some(hit) => hit,
+                    None => return,
+                };
+                let body_part = match world.collider(collider) {
+                    Some(collider) => collider.body_part(0),
+                    None => return,
+                };
+                let is_dynamic = world
+                    .body(body_part.0)
+                    .map_or(false, |body| body.is_dynamic());
+                let local_point = match world.body_part(body_part) {
+                    Some(part) if is_dynamic => part.position().inverse() * point,
+                    _ => return,
+                };
+
+                let target = Arc::new(Mutex::new(point));
+                let force_generator = world.add_force_generator(SpringForce {
+                    body_part,
+                    local_point,
+                    target: target.clone(),
+                });
+                perturbation.active = Some(ActivePerturbation {
+                    force_generator,
+                    body_part,
+                    local_point,
+                    target,
+                    distance: na::distance(&ray.origin, &point),
+                });
+            }
+            mouse_drag::Variant::DraggingByClicked(s) => {
+                let active = match perturbation.active.as_ref() {
+                    Some(active) => active,
+                    None => return,
+                };
+                let cursor_position = match s.state().as_enum() {
+                    mouse_drag::StateVariant::Dragging(s) => s.delta_end_position,
+                    _ => unreachable!(),
+                };
+                if let Some(ray) = cursor_ray(cursor_position) {
+                    *active
+                        .target
+                        .lock()
+                        .expect("spring target lock is poisoned") = ray.point_at(active.distance);
+                }
+            }
+            _ => {
+                if let Some(active) = perturbation.active.take() {
+                    world.remove_force_generator(active.force_generator);
+                }
+                return;
+            }
+        }
+
+        if let Some(active) = perturbation.active.as_ref() {
+            if let Some(part) = world.body_part(active.body_part) {
+                let grabbed = part.position() * active.local_point;
+                let target = *active
+                    .target
+                    .lock()
+                    .expect("spring target lock is poisoned");
+                debug_lines.draw_line(
+                    [grabbed.x, grabbed.y, grabbed.z].into(),
+                    [target.x, target.y, target.z].into(),
+                    SPRING_COLOR,
+                );
+            }
+        }
+    }
+}
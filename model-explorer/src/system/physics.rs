@@ -1,12 +1,14 @@
 use crate::component;
-use crate::resource::physics::PhysicsWorld;
+use crate::resource::{physics::PhysicsWorld, SimClock};
 use amethyst::{
-    core::Transform,
-    ecs::prelude::{Read, ReadStorage, System, WriteStorage},
+    core::{nalgebra as ana, Time, Transform},
+    ecs::prelude::{Join, Read, ReadStorage, System, Write, WriteStorage},
 };
 use nalgebra as na;
 use std::marker::PhantomData;
 
+/// Steps the physics world as often as the `SimClock` asks for, and
+/// moves the entities with a `Collider` to where their colliders are
 pub struct PhysicsSystem<N>
 where
     N: na::RealField,
@@ -22,17 +24,49 @@ impl<N: na::RealField> Default for PhysicsSystem<N> {
     }
 }
 
-impl<'s, N> System<'s> for PhysicsSystem<N>
-where
-    N: na::RealField,
-{
+impl<'s> System<'s> for PhysicsSystem<f32> {
     type SystemData = (
-        Read<'s, PhysicsWorld<N>>,
+        Write<'s, PhysicsWorld<f32>>,
+        Write<'s, SimClock>,
+        Read<'s, Time>,
         WriteStorage<'s, Transform>,
         ReadStorage<'s, component::Collider>,
     );
 
-    fn run(&mut self, (_physics, _transforms, _colliders): Self::SystemData) {
-        // TODO(dschwab): Implement me
+    fn run(&mut self, (mut physics, mut clock, time, mut transforms, colliders): Self::SystemData) {
+        let world = match physics.world.as_mut() {
+            Some(world) => world,
+            None => return,
+        };
+
+        let steps = clock.advance(time.delta_seconds(), world.timestep());
+        if steps == 0 {
+            return;
+        }
+        for _ in 0..steps {
+            world.step();
+        }
+
+        for (transform, collider) in (&mut transforms, &colliders).join() {
+            if let Some(collider) = world.collider(collider.id) {
+                set_transform_position(transform, collider.position());
+            }
+        }
     }
 }
+
+/// Set the translation and rotation of an amethyst transform from an
+/// nphysics position, keeping its scale
+pub fn set_transform_position(transform: &mut Transform, position: &na::Isometry3<f32>) {
+    transform.set_xyz(
+        position.translation.x,
+        position.translation.y,
+        position.translation.z,
+    );
+    *transform.rotation_mut() = ana::Unit::new_unchecked(ana::Quaternion::new(
+        position.rotation.coords.w,
+        position.rotation.coords.x,
+        position.rotation.coords.y,
+        position.rotation.coords.z,
+    ));
+}
@@ -0,0 +1,224 @@
+use crate::component;
+use crate::resource::{
+    mouse_drag,
+    physics::PhysicsWorld,
+    selection::{Selected, Selection},
+    GroupVisibility,
+};
+use amethyst::{
+    assets::{AssetStorage, Handle, Loader},
+    core::{nalgebra as ana, GlobalTransform},
+    ecs::prelude::{Entities, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage},
+    renderer::{ActiveCamera, Camera, Material, ScreenDimensions, Texture},
+};
+use nalgebra as na;
+use ncollide3d::{query::Ray, world::CollisionGroups};
+use nphysics3d::{object::ColliderHandle, world::World};
+use nphysics_user_data::ColliderUserData;
+use sm::{AsEnum, Machine};
+use std::cmp::Ordering;
+
+/// Furthest the mouse can move between press and release, in pixels,
+/// for the press to count as a click instead of a drag
+const CLICK_TOLERANCE: f64 = 3.0;
+
+/// Albedo of the selected geom
+const HIGHLIGHT_COLOR: [f32; 4] = [1.0, 0.6, 0.1, 1.0];
+
+/// Selects the collider under the cursor when the mouse is clicked,
+/// and highlights it. Clicking on nothing clears the selection.
+/// Colliders in hidden groups can not be selected.
+#[derive(Default)]
+pub struct PickingSystem {
+    highlight: Option<Handle<Texture>>,
+}
+
+impl<'s> System<'s> for PickingSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Read<'s, mouse_drag::Variant>,
+        Read<'s, ActiveCamera>,
+        ReadStorage<'s, Camera>,
+        ReadStorage<'s, GlobalTransform>,
+        ReadExpect<'s, ScreenDimensions>,
+        Read<'s, PhysicsWorld<f32>>,
+        Read<'s, GroupVisibility>,
+        Entities<'s>,
+        ReadStorage<'s, component::Collider>,
+        WriteStorage<'s, Material>,
+        Write<'s, Selection>,
+        ReadExpect<'s, Loader>,
+        Read<'s, AssetStorage<Texture>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mouse_drag,
+            active_camera,
+            cameras,
+            global_transforms,
+            screen_dimensions,
+            physics,
+            group_visibility,
+            entities,
+            colliders,
+            mut materials,
+            mut selection,
+            loader,
+            textures,
+        ): Self::SystemData,
+    ) {
+        let click_position = match clicked_position(&mouse_drag) {
+            Some(position) => position,
+            None => return,
+        };
+        let world = match physics.world.as_ref() {
+            Some(world) => world,
+            None => return,
+        };
+        let ray = match cursor_ray(
+            click_position,
+            &active_camera,
+            &cameras,
+            &global_transforms,
+            &screen_dimensions,
+        ) {
+            Some(ray) => ray,
+            None => return,
+        };
+
+        selection.clear(&mut materials);
+
+        let (collider, point) = match cast_ray(world, &ray, &group_visibility) {
+            Some(hit) => hit,
+            None => return,
+        };
+        let entity = match (&entities, &colliders)
+            .join()
+            .find(|(_, component)| component.id == collider)
+        {
+            Some((entity, _)) => entity,
+            None => return,
+        };
+        let original_material = match materials.get(entity) {
+            Some(material) => material.clone(),
+            None => return,
+        };
+
+        if let Some(selected) = world.collider(collider) {
+            println!(
+                "Selected {} at [{}, {}, {}]",
+                display_name(selected.name()),
+                point.x,
+                point.y,
+                point.z
+            );
+        }
+
+        let highlight = self
+            .highlight
+            .get_or_insert_with(|| loader.load_from_data(HIGHLIGHT_COLOR.into(), (), &textures))
+            .clone();
+        materials
+            .insert(
+                entity,
+                Material {
+                    albedo: highlight,
+                    ..original_material.clone()
+                },
+            )
+            .expect("selected entity is not alive");
+
+        selection.selected = Some(Selected {
+            collider,
+            entity,
+            point,
+            original_material,
+        });
+    }
+}
+
+/// Where the mouse was released, if the mouse was clicked without
+/// dragging this frame
+fn clicked_position(mouse_drag: &mouse_drag::Variant) -> Option<na::Point2<f64>> {
+    if let mouse_drag::Variant::FinishDraggingByNotClicked(s) = mouse_drag {
+        if let mouse_drag::StateVariant::FinishDragging(s) = s.state().as_enum() {
+            if na::distance(&s.start_position, &s.end_position) <= CLICK_TOLERANCE {
+                return Some(s.end_position);
+            }
+        }
+    }
+    None
+}
+
+/// Ray from the active camera through a point on the screen, in world
+/// coordinates. `None` while the window has no area, e.g. when it is
+/// minimized.
+pub fn cursor_ray(
+    screen_position: na::Point2<f64>,
+    active_camera: &ActiveCamera,
+    cameras: &ReadStorage<'_, Camera>,
+    global_transforms: &ReadStorage<'_, GlobalTransform>,
+    screen_dimensions: &ScreenDimensions,
+) -> Option<Ray<f32>> {
+    if screen_dimensions.width() <= 0.0 || screen_dimensions.height() <= 0.0 {
+        return None;
+    }
+    let camera_entity = active_camera.entity?;
+    let camera = cameras.get(camera_entity)?;
+    let camera_transform = global_transforms.get(camera_entity)?;
+    let inverse_projection = camera.proj.try_inverse()?;
+
+    // normalized device coordinates, y points up
+    let x = 2.0 * screen_position.x as f32 / screen_dimensions.width() - 1.0;
+    let y = 1.0 - 2.0 * screen_position.y as f32 / screen_dimensions.height();
+    let unproject = |depth: f32| {
+        let view = inverse_projection * ana::Vector4::new(x, y, depth, 1.0);
+        let world = camera_transform.0 * (view / view.w);
+        na::Point3::new(world.x, world.y, world.z)
+    };
+
+    let near = unproject(-1.0);
+    let far = unproject(1.0);
+    Some(Ray::new(near, (far - near).normalize()))
+}
+
+/// Closest collider in a shown group hit by a ray, and where it was
+/// hit
+pub fn cast_ray(
+    world: &World<f32>,
+    ray: &Ray<f32>,
+    group_visibility: &GroupVisibility,
+) -> Option<(ColliderHandle, na::Point3<f32>)> {
+    world
+        .collider_world()
+        .interferences_with_ray(ray, &CollisionGroups::new())
+        .filter(|(_, intersection)| intersection.toi.is_finite())
+        .filter(|(collider, _)| is_shown(world, collider.handle(), group_visibility))
+        .min_by(|(_, a), (_, b)| a.toi.partial_cmp(&b.toi).unwrap_or(Ordering::Equal))
+        .map(|(collider, intersection)| (collider.handle(), ray.point_at(intersection.toi)))
+}
+
+/// Whether the geom group of a collider is shown
+fn is_shown(
+    world: &World<f32>,
+    handle: ColliderHandle,
+    group_visibility: &GroupVisibility,
+) -> bool {
+    let group = world
+        .collider(handle)
+        .and_then(|collider| collider.user_data())
+        .and_then(|user_data| user_data.downcast_ref::<ColliderUserData<f32>>())
+        .map_or(0, |user_data| user_data.group);
+    group_visibility.is_visible(group)
+}
+
+/// Name of a collider for messages, geoms do not need a name
+pub fn display_name(name: &str) -> &str {
+    if name.is_empty() {
+        "unnamed geom"
+    } else {
+        name
+    }
+}
@@ -6,6 +6,11 @@ where
     N: na::RealField,
 {
     pub rgba: Option<na::Point4<f32>>,
+    /// Type of the geom the collider was made from, e.g. `"capsule"`,
+    /// since the collider shape may only approximate it
+    pub geom_type: Option<String>,
+    /// Visibility group of the geom, 0 unless the model sets one
+    pub group: i32,
     pub torsional_friction: N,
     pub rolling_friction: N,
 }
@@ -17,6 +22,8 @@ where
     fn default() -> Self {
         ColliderUserData {
             rgba: None,
+            geom_type: None,
+            group: 0,
             torsional_friction: N::from(0.005),
             rolling_friction: N::from(0.0001),
         }